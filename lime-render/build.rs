@@ -1,6 +1,8 @@
 fn main() {
     println!("cargo:rerun-if-changed=shaders/d2/frag.glsl");
     println!("cargo:rerun-if-changed=shaders/d2/vert.glsl");
+    println!("cargo:rerun-if-changed=shaders/d2/image/frag.glsl");
+    println!("cargo:rerun-if-changed=shaders/d2/image/vert.glsl");
     println!("cargo:rerun-if-changed=shaders/d3/frag.glsl");
     println!("cargo:rerun-if-changed=shaders/d3/vert.glsl");
 }
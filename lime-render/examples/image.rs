@@ -9,7 +9,7 @@ use std::fs::File;
 
 use image::png::PNGEncoder;
 use image::ColorType;
-use render::{d2, Color, ImageTarget};
+use render::{d2, Color, ImageTarget, RenderConfig};
 use shrev::EventChannel;
 use specs::prelude::*;
 
@@ -44,7 +44,8 @@ fn main() {
         .with(D3, "D3", &[])
         .with(D2, "D2", &[]);
     world.add_resource(EventChannel::<winit::Event>::new());
-    render::init::<ImageTarget>(&mut world, &mut dispatcher, [500, 500], "D3", "D2");
+    let config = RenderConfig::default();
+    render::init::<ImageTarget>(&mut world, &mut dispatcher, [500, 500], config, "D3", "D2");
     let mut dispatcher = dispatcher.build();
 
     dispatcher.run_now(&mut world.res);
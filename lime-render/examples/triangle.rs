@@ -4,7 +4,7 @@ extern crate shrev;
 extern crate specs;
 extern crate winit;
 
-use render::{d2, Color, SwapchainTarget};
+use render::{d2, Color, RenderConfig, SwapchainTarget};
 use shrev::EventChannel;
 use specs::prelude::*;
 use winit::{Event, EventsLoop, WindowBuilder, WindowEvent};
@@ -42,7 +42,8 @@ fn main() {
         .with(D3, "D3", &[])
         .with(D2, "D2", &[]);
     world.add_resource(EventChannel::<winit::Event>::new());
-    render::init::<SwapchainTarget>(&mut world, &mut dispatcher, window, "D3", "D2");
+    let config = RenderConfig::default();
+    render::init::<SwapchainTarget>(&mut world, &mut dispatcher, window, config, "D3", "D2");
     let mut dispatcher = dispatcher.build();
 
     let mut quit = false;
@@ -0,0 +1,12 @@
+#version 450
+
+layout(location = 0) in vec2 v_uv;
+layout(location = 1) in vec4 v_tint;
+
+layout(location = 0) out vec4 f_color;
+
+layout(set = 0, binding = 1) uniform sampler2D tex;
+
+void main() {
+    f_color = texture(tex, v_uv) * v_tint;
+}
\ No newline at end of file
@@ -1,14 +1,19 @@
 #version 450
 
 layout(location = 0) in vec3 v_normal;
+layout(location = 1) in vec4 v_color;
+
 layout(location = 0) out vec4 f_color;
 
-const vec3 LIGHT = vec3(0.0, 0.0, 1.0);
+layout(set = 0, binding = 1) uniform Light {
+    vec4 direction;
+    vec4 color;
+    vec4 ambient;
+} light;
 
 void main() {
-    float brightness = dot(normalize(v_normal), normalize(LIGHT));
-    vec3 dark_color = vec3(0.6, 0.0, 0.0);
-    vec3 regular_color = vec3(1.0, 0.0, 0.0);
+    float diffuse = max(dot(normalize(v_normal), -normalize(light.direction.xyz)), 0.0);
+    vec3 intensity = light.ambient.rgb + light.color.rgb * diffuse;
 
-    f_color = vec4(mix(dark_color, regular_color, brightness), 1.0);
+    f_color = vec4(v_color.rgb * intensity, v_color.a);
 }
\ No newline at end of file
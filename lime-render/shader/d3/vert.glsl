@@ -2,17 +2,22 @@
 
 layout(location = 0) in vec3 position;
 layout(location = 1) in vec3 normal;
+layout(location = 2) in mat4 world;
 
 layout(location = 0) out vec3 v_normal;
+layout(location = 1) out vec4 v_color;
 
 layout(set = 0, binding = 0) uniform Data {
-    mat4 world;
     mat4 view;
     mat4 proj;
 } uniforms;
 
+layout(push_constant) uniform PushConstants {
+    vec4 color;
+} constants;
+
 void main() {
-    mat4 worldview = uniforms.view * uniforms.world;
-    v_normal = transpose(inverse(mat3(worldview))) * normal;
-    gl_Position = uniforms.proj * worldview * vec4(position, 1.0);
+    v_normal = transpose(inverse(mat3(world))) * normal;
+    v_color = constants.color;
+    gl_Position = uniforms.proj * uniforms.view * world * vec4(position, 1.0);
 }
\ No newline at end of file
@@ -0,0 +1,57 @@
+use vulkano::format::Format;
+use vulkano::instance::PhysicalDevice;
+use vulkano::swapchain::PresentMode;
+
+use Color;
+
+/// Settings for the render pass and swapchain, passed to `init`.
+#[derive(Clone, Debug)]
+pub struct RenderConfig {
+    /// The color of pixels not covered by anything drawn.
+    pub clear_color: Color,
+    /// The number of samples per pixel. Values greater than one enable multisample
+    /// antialiasing. Falls back to one sample if the device does not support this count.
+    pub samples: u32,
+    /// The format of the depth buffer used by `d3`.
+    pub depth_format: Format,
+    /// How frames are queued for display. This only applies to `SwapchainTarget`, and falls back
+    /// to `PresentMode::Fifo`, which is always supported, if the surface does not support it.
+    pub present_mode: PresentMode,
+}
+
+impl RenderConfig {
+    pub(crate) fn is_multisampled(&self) -> bool {
+        self.samples > 1
+    }
+
+    /// This config with `samples` replaced by one if `phys` cannot render color and depth
+    /// attachments with that many samples.
+    pub(crate) fn supported_by(&self, phys: PhysicalDevice) -> Self {
+        let limits = phys.limits();
+        let counts =
+            limits.framebuffer_color_sample_counts() & limits.framebuffer_depth_sample_counts();
+        if self.samples.is_power_of_two() && counts & self.samples != 0 {
+            return self.clone();
+        }
+
+        warn!(
+            "{} samples per pixel are not supported by the device, falling back to 1.",
+            self.samples
+        );
+        RenderConfig {
+            samples: 1,
+            ..self.clone()
+        }
+    }
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        RenderConfig {
+            clear_color: Color::BLACK,
+            samples: 1,
+            depth_format: Format::D16Unorm,
+            present_mode: PresentMode::Mailbox,
+        }
+    }
+}
@@ -1,9 +1,17 @@
 use std::iter::once;
+use std::path::Path;
 use std::sync::Arc;
 
 use failure::{err_msg, Fallible};
+use image;
 use vulkano::device::{Device, Queue};
+use vulkano::format::Format;
+use vulkano::image::{Dimensions, ImmutableImage};
 use vulkano::instance::{DeviceExtensions, PhysicalDevice, QueueFamily};
+use vulkano::sync::GpuFuture;
+
+use texture;
+use {PendingTexture, Texture};
 
 pub struct Context {
     transfer: Arc<Queue>,
@@ -85,4 +93,47 @@ impl Context {
     pub fn queue_families(&self) -> impl Iterator<Item = QueueFamily> {
         once(self.transfer.family()).chain(once(self.graphics.family()))
     }
+
+    /// Uploads an image to the GPU. `data` holds 8-bit RGBA pixels in row-major order, in the
+    /// sRGB color space.
+    pub fn create_texture(&self, data: &[u8], dimensions: [u32; 2]) -> Fallible<Texture> {
+        self.begin_create_texture(data, dimensions)?.wait()
+    }
+
+    /// Like `create_texture`, but returns without waiting for the upload to finish.
+    pub fn begin_create_texture(
+        &self,
+        data: &[u8],
+        dimensions: [u32; 2],
+    ) -> Fallible<PendingTexture> {
+        texture::check_len(data, dimensions)?;
+        let [width, height] = dimensions;
+
+        let (image, future) = ImmutableImage::from_iter(
+            data.iter().cloned(),
+            Dimensions::Dim2d { width, height },
+            Format::R8G8B8A8Srgb,
+            Arc::clone(&self.graphics),
+        )?;
+        let future: Box<GpuFuture + Send + Sync> = Box::new(future);
+        let fence = future.then_signal_fence_and_flush()?;
+
+        let texture = Texture::new(image, dimensions);
+        #[cfg(feature = "software")]
+        let texture = texture.with_data(data);
+        Ok(PendingTexture::new(texture, fence))
+    }
+
+    /// Loads an image file and uploads it to the GPU.
+    pub fn load_texture(&self, path: impl AsRef<Path>) -> Fallible<Texture> {
+        self.begin_load_texture(path)?.wait()
+    }
+
+    /// Like `load_texture`, but returns without waiting for the upload to finish. The file is
+    /// still read and decoded before returning.
+    pub fn begin_load_texture(&self, path: impl AsRef<Path>) -> Fallible<PendingTexture> {
+        let image = image::open(path)?.to_rgba();
+        let dimensions = [image.width(), image.height()];
+        self.begin_create_texture(&image.into_raw(), dimensions)
+    }
 }
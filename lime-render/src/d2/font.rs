@@ -0,0 +1,34 @@
+use std::fmt;
+
+use rusttype;
+
+/// Identifies a font added to a `Renderer`.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FontId(pub(in d2) usize);
+
+/// A font which has been loaded into the glyph cache of a `Renderer`.
+#[derive(Clone)]
+pub struct Font {
+    id: FontId,
+    font: rusttype::Font<'static>,
+}
+
+impl Font {
+    pub(in d2) fn new(id: FontId, font: rusttype::Font<'static>) -> Self {
+        Font { id, font }
+    }
+
+    pub fn id(&self) -> FontId {
+        self.id
+    }
+
+    pub fn rusttype(&self) -> &rusttype::Font<'static> {
+        &self.font
+    }
+}
+
+impl fmt::Debug for Font {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Font").field("id", &self.id).finish()
+    }
+}
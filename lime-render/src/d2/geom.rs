@@ -23,6 +23,66 @@ impl From<LogicalPosition> for Point {
     }
 }
 
+/// An axis-aligned rectangle in logical pixels.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Rect {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl Rect {
+    pub fn new(left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        Rect {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> f32 {
+        self.bottom - self.top
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.left >= self.right || self.top >= self.bottom
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.left <= point.0 && point.0 < self.right && self.top <= point.1 && point.1 < self.bottom
+    }
+
+    /// Returns the overlapping area of two rectangles, or `None` if they do not overlap.
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        let rect = Rect::new(
+            self.left.max(other.left),
+            self.top.max(other.top),
+            self.right.min(other.right),
+            self.bottom.min(other.bottom),
+        );
+        if rect.is_empty() {
+            None
+        } else {
+            Some(rect)
+        }
+    }
+}
+
+impl PartialEq for Rect {
+    fn eq(&self, other: &Self) -> bool {
+        ulps_eq!(self.left, other.left)
+            && ulps_eq!(self.top, other.top)
+            && ulps_eq!(self.right, other.right)
+            && ulps_eq!(self.bottom, other.bottom)
+    }
+}
+
 unsafe impl VertexMember for Point {
     fn format() -> (VertexMemberTy, usize) {
         (VertexMemberTy::F32, 2)
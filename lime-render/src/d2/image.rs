@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use failure::Fallible;
+use utils::throw;
+use vulkano::buffer::CpuBufferPool;
+use vulkano::command_buffer::{AutoCommandBufferBuilder, DynamicState};
+use vulkano::descriptor::descriptor_set::FixedSizeDescriptorSetsPool;
+use vulkano::descriptor::PipelineLayoutAbstract;
+use vulkano::device::Device;
+use vulkano::framebuffer::{RenderPassAbstract, Subpass};
+use vulkano::pipeline::vertex::SingleBufferDefinition;
+use vulkano::pipeline::GraphicsPipeline;
+use vulkano::sampler::Sampler;
+
+use d2::{clip_state, Point, Rect};
+use {Color, Texture};
+
+type Pipeline = Arc<
+    GraphicsPipeline<
+        SingleBufferDefinition<Vertex>,
+        Box<PipelineLayoutAbstract + Send + Sync>,
+        Arc<RenderPassAbstract + Send + Sync>,
+    >,
+>;
+
+pub(in d2) struct ImageBrush {
+    vbuf: CpuBufferPool<Vertex>,
+    ubuf: CpuBufferPool<vs::ty::Data>,
+    pipe: Pipeline,
+    pool: FixedSizeDescriptorSetsPool<Pipeline>,
+    sampler: Arc<Sampler>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ImageSection {
+    pub(crate) vertices: Vec<Vertex>,
+    pub(crate) texture: Texture,
+    pub(crate) clip: Option<Rect>,
+}
+
+impl ImageBrush {
+    pub(in d2) fn new(
+        device: &Arc<Device>,
+        subpass: Subpass<Arc<RenderPassAbstract + Send + Sync>>,
+    ) -> Self {
+        let vs = vs::Shader::load(Arc::clone(device)).unwrap_or_else(throw);
+        let fs = fs::Shader::load(Arc::clone(device)).unwrap_or_else(throw);
+
+        let pipe = Arc::new(
+            GraphicsPipeline::start()
+                .vertex_input_single_buffer::<Vertex>()
+                .vertex_shader(vs.main_entry_point(), ())
+                .triangle_list()
+                .viewports_scissors_dynamic(1)
+                .fragment_shader(fs.main_entry_point(), ())
+                .blend_alpha_blending()
+                .render_pass(subpass)
+                .build(Arc::clone(device))
+                .unwrap_or_else(throw),
+        );
+
+        let vbuf = CpuBufferPool::vertex_buffer(Arc::clone(device));
+        let ubuf = CpuBufferPool::uniform_buffer(Arc::clone(device));
+
+        let pool = FixedSizeDescriptorSetsPool::new(Arc::clone(&pipe), 0);
+        let sampler = Sampler::simple_repeat_linear(Arc::clone(device));
+
+        ImageBrush {
+            pipe,
+            vbuf,
+            ubuf,
+            pool,
+            sampler,
+        }
+    }
+
+    pub(in d2) fn draw(
+        &mut self,
+        cmd: AutoCommandBufferBuilder,
+        section: &ImageSection,
+        state: &DynamicState,
+        logical_size: [f32; 2],
+    ) -> Fallible<AutoCommandBufferBuilder> {
+        if section.vertices.is_empty() {
+            return Ok(cmd);
+        }
+        let image = match section.texture.image() {
+            Some(image) => image,
+            None => {
+                warn!("Textures created with `Texture::from_rgba8` cannot be drawn on the GPU.");
+                return Ok(cmd);
+            }
+        };
+
+        let state = clip_state(state, section.clip, logical_size);
+        let vbuf = self.vbuf.chunk(section.vertices.iter().cloned())?;
+        let ubuf = self.ubuf.next(vs::ty::Data {
+            dimensions: logical_size,
+        })?;
+        let set = self
+            .pool
+            .next()
+            .add_buffer(ubuf)?
+            .add_sampled_image(Arc::clone(image), Arc::clone(&self.sampler))?
+            .build()?;
+
+        Ok(cmd.draw(Arc::clone(&self.pipe), &state, vbuf, set, ())?)
+    }
+}
+
+impl ImageSection {
+    pub(in d2) fn new(
+        rect: Rect,
+        texture: &Texture,
+        uv: Rect,
+        tint: Color,
+        clip: Option<Rect>,
+    ) -> Self {
+        let vertices = vec![
+            Vertex::new(Point(rect.left, rect.bottom), Point(uv.left, uv.bottom), tint),
+            Vertex::new(Point(rect.left, rect.top), Point(uv.left, uv.top), tint),
+            Vertex::new(Point(rect.right, rect.bottom), Point(uv.right, uv.bottom), tint),
+            Vertex::new(Point(rect.right, rect.bottom), Point(uv.right, uv.bottom), tint),
+            Vertex::new(Point(rect.left, rect.top), Point(uv.left, uv.top), tint),
+            Vertex::new(Point(rect.right, rect.top), Point(uv.right, uv.top), tint),
+        ];
+        ImageSection {
+            vertices,
+            texture: texture.clone(),
+            clip,
+        }
+    }
+
+    /// Extends this section with `next`. Returns `false` if the sections cannot be merged
+    /// because they use different textures or clip rectangles.
+    pub(in d2) fn append(&mut self, next: &ImageSection) -> bool {
+        if self.texture != next.texture || self.clip != next.clip {
+            return false;
+        }
+        self.vertices.extend_from_slice(&next.vertices);
+        true
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Vertex {
+    pub(crate) position: Point,
+    pub(crate) uv: Point,
+    pub(crate) tint: Color,
+}
+
+impl Vertex {
+    fn new(position: Point, uv: Point, tint: Color) -> Self {
+        Vertex { position, uv, tint }
+    }
+}
+
+impl_vertex!(Vertex, position, uv, tint);
+
+#[allow(unused)]
+mod vs {
+    #[derive(VulkanoShader)]
+    #[ty = "vertex"]
+    #[path = "shader/d2/image/vert.glsl"]
+    struct Dummy;
+}
+
+#[allow(unused)]
+mod fs {
+    #[derive(VulkanoShader)]
+    #[ty = "fragment"]
+    #[path = "shader/d2/image/frag.glsl"]
+    struct Dummy;
+}
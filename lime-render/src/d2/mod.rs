@@ -1,43 +1,89 @@
+mod font;
 mod geom;
+mod image;
 mod tri;
 
-pub use self::geom::Point;
+pub use self::font::{Font, FontId};
+pub use self::geom::{Point, Rect};
 
+pub(crate) use self::image::ImageSection;
+pub(crate) use self::tri::TriangleSection;
+
+use std::mem;
 use std::sync::Arc;
 
 use failure::Fallible;
-use rusttype::PositionedGlyph;
+use rusttype::{self, PositionedGlyph};
 use utils::throw;
 use vulkano::command_buffer::{AutoCommandBufferBuilder, DynamicState};
 use vulkano::device::Device;
 use vulkano::framebuffer::{RenderPassAbstract, Subpass};
-use vulkano_glyph::{FontId, GlyphBrush, Section as GlyphSection};
+use vulkano::pipeline::viewport::Scissor;
+use vulkano_glyph::{self, GlyphBrush};
 
-use d2::tri::{TriangleBrush, TriangleSection};
-use Color;
+use d2::image::ImageBrush;
+use d2::tri::{TriangleBrush, Vertex as TriangleVertex};
+use {Color, Texture};
 
 pub struct Renderer {
-    tri_brush: TriangleBrush,
-    glyph_brush: GlyphBrush<'static>,
+    brushes: Option<Brushes>,
+    fonts: usize,
     sections: Vec<Section>,
+    clips: Vec<Rect>,
+}
+
+// The GPU pipelines used to draw sections. These are absent when rendering in software.
+struct Brushes {
+    tri: TriangleBrush,
+    image: ImageBrush,
+    glyph: GlyphBrush<'static>,
+    fonts: Vec<vulkano_glyph::FontId>,
 }
 
-enum Section {
+/// A batch of drawing commands, recorded in the order they were issued.
+#[derive(Clone, Debug)]
+pub(crate) enum Section {
     Triangle(TriangleSection),
+    Image(ImageSection),
     Glyph(GlyphSection),
 }
 
+#[derive(Clone, Debug)]
+pub(crate) struct GlyphSection {
+    pub(crate) glyphs: Vec<PositionedGlyph<'static>>,
+    pub(crate) font: FontId,
+    pub(crate) color: Color,
+}
+
 impl Renderer {
     pub(crate) fn new(
         device: &Arc<Device>,
         subpass: Subpass<Arc<RenderPassAbstract + Send + Sync>>,
     ) -> Self {
-        let tri_brush = TriangleBrush::new(device, subpass.clone());
-        let glyph_brush = GlyphBrush::new(device, subpass).unwrap_or_else(throw);
+        let tri = TriangleBrush::new(device, subpass.clone());
+        let image = ImageBrush::new(device, subpass.clone());
+        let glyph = GlyphBrush::new(device, subpass).unwrap_or_else(throw);
+        Renderer {
+            brushes: Some(Brushes {
+                tri,
+                image,
+                glyph,
+                fonts: Vec::new(),
+            }),
+            fonts: 0,
+            sections: Vec::new(),
+            clips: Vec::new(),
+        }
+    }
+
+    /// Creates a renderer which only records sections, for the software rasterizer.
+    #[cfg(feature = "software")]
+    pub(crate) fn software() -> Self {
         Renderer {
-            tri_brush,
-            glyph_brush,
+            brushes: None,
+            fonts: 0,
             sections: Vec::new(),
+            clips: Vec::new(),
         }
     }
 
@@ -47,15 +93,37 @@ impl Renderer {
         state: &DynamicState,
         logical_size: [f32; 2],
     ) -> Fallible<AutoCommandBufferBuilder> {
-        for section in self.sections.drain(..) {
-            match section {
-                Section::Triangle(section) => {
-                    cmd = self.tri_brush.draw(cmd, &section, state, logical_size)?;
+        let sections = self.take_sections();
+        let brushes = self
+            .brushes
+            .as_mut()
+            .expect("software renderer cannot record commands");
+
+        // Queue all glyphs up front, so the glyph cache is only updated once per frame.
+        let queued: Vec<_> = sections
+            .iter()
+            .map(|section| match section {
+                Section::Glyph(section) => Some(brushes.glyph.queue_glyphs(
+                    section.glyphs.iter().cloned(),
+                    brushes.fonts[section.font.0],
+                    section.color.into(),
+                )),
+                _ => None,
+            })
+            .collect();
+
+        for (section, queued) in sections.iter().zip(queued) {
+            match (section, queued) {
+                (Section::Triangle(section), _) => {
+                    cmd = brushes.tri.draw(cmd, section, state, logical_size)?;
                 }
-                Section::Glyph(section) => {
-                    cmd = self.glyph_brush.draw(
+                (Section::Image(section), _) => {
+                    cmd = brushes.image.draw(cmd, section, state, logical_size)?;
+                }
+                (Section::Glyph(section), Some(queued)) => {
+                    cmd = brushes.glyph.draw(
                         cmd,
-                        &section,
+                        &queued,
                         state,
                         [
                             [1.0, 0.0, 0.0, 0.0],
@@ -66,28 +134,211 @@ impl Renderer {
                         logical_size,
                     )?;
                 }
+                (Section::Glyph(_), None) => unreachable!(),
             }
         }
 
-        self.glyph_brush.clear();
-
+        brushes.glyph.clear();
         Ok(cmd)
     }
 
+    /// Removes and returns the sections queued since the last frame.
+    pub(crate) fn take_sections(&mut self) -> Vec<Section> {
+        if !self.clips.is_empty() {
+            warn!("Unbalanced clip stack: {} clips not popped.", self.clips.len());
+            self.clips.clear();
+        }
+        mem::replace(&mut self.sections, Vec::new())
+    }
+
+    pub fn add_font(&mut self, font: rusttype::Font<'static>) -> Font {
+        if let Some(ref mut brushes) = self.brushes {
+            let id = brushes.glyph.add_font(font.clone());
+            brushes.fonts.push(id);
+        }
+        let id = FontId(self.fonts);
+        self.fonts += 1;
+        Font::new(id, font)
+    }
+
+    /// Restricts drawing to the intersection of `rect` and the current clip rectangle, until
+    /// the matching call to `pop_clip`.
+    pub fn push_clip(&mut self, rect: Rect) {
+        let clip = match self.clip() {
+            Some(clip) => clip
+                .intersect(&rect)
+                .unwrap_or_else(|| Rect::new(rect.left, rect.top, rect.left, rect.top)),
+            None => rect,
+        };
+        self.clips.push(clip);
+    }
+
+    /// Restores the clip rectangle in effect before the last call to `push_clip`.
+    pub fn pop_clip(&mut self) {
+        if self.clips.pop().is_none() {
+            warn!("Clip stack popped while empty.");
+        }
+    }
+
+    /// The area drawing is currently restricted to, or `None` if drawing is not clipped.
+    pub fn clip(&self) -> Option<Rect> {
+        self.clips.last().cloned()
+    }
+
     pub fn draw_tris(&mut self, vertices: &[Point], color: Color) {
-        let subsection = self.tri_brush.queue_tris(vertices, color);
-        if let Some(Section::Triangle(section)) = self.sections.last_mut() {
-            section.append(&subsection);
+        let clip = self.clip();
+        if clip.map(|clip| clip.is_empty()).unwrap_or(false) {
+            return;
+        }
+
+        let vertices = vertices
+            .iter()
+            .map(|&position| TriangleVertex::new(position, color))
+            .collect();
+        self.push_tris(TriangleSection::new(vertices, clip));
+    }
+
+    /// Draws triangles with a color for each vertex. Colors are interpolated across each
+    /// triangle.
+    pub fn draw_tris_colored(&mut self, vertices: &[(Point, Color)]) {
+        let clip = self.clip();
+        if clip.map(|clip| clip.is_empty()).unwrap_or(false) {
             return;
         }
+
+        let vertices = vertices
+            .iter()
+            .map(|&(position, color)| TriangleVertex::new(position, color))
+            .collect();
+        self.push_tris(TriangleSection::new(vertices, clip));
+    }
+
+    fn push_tris(&mut self, subsection: TriangleSection) {
+        if let Some(Section::Triangle(section)) = self.sections.last_mut() {
+            if section.append(&subsection) {
+                return;
+            }
+        }
         self.sections.push(Section::Triangle(subsection));
     }
 
+    /// Draws the `uv` region of `texture`, multiplied by `tint`, stretched over `rect`. Texture
+    /// coordinates range from `0.0` to `1.0` across the texture.
+    pub fn draw_image(&mut self, rect: Rect, texture: &Texture, uv: Rect, tint: Color) {
+        let clip = self.clip();
+        if clip.map(|clip| clip.is_empty()).unwrap_or(false) {
+            return;
+        }
+
+        let subsection = ImageSection::new(rect, texture, uv, tint, clip);
+        if let Some(Section::Image(section)) = self.sections.last_mut() {
+            if section.append(&subsection) {
+                return;
+            }
+        }
+        self.sections.push(Section::Image(subsection));
+    }
+
     pub fn draw_glyphs<I>(&mut self, glyphs: I, font: FontId, color: Color)
     where
         I: IntoIterator<Item = PositionedGlyph<'static>>,
     {
-        let section = self.glyph_brush.queue_glyphs(glyphs, font, color.into());
-        self.sections.push(Section::Glyph(section));
+        let clip = self.clip();
+        if clip.map(|clip| clip.is_empty()).unwrap_or(false) {
+            return;
+        }
+
+        // The glyph pipeline cannot be scissored, so glyphs crossing the edge of the clip are
+        // drawn as triangles instead.
+        let mut inside = Vec::new();
+        let mut cut = Vec::new();
+        for glyph in glyphs {
+            match (clip, glyph.pixel_bounding_box()) {
+                (Some(clip), Some(bb)) if !contains(clip, bb) => {
+                    cut_glyph(&glyph, bb, color, clip, &mut cut)
+                }
+                _ => inside.push(glyph),
+            }
+        }
+
+        if !inside.is_empty() {
+            self.sections.push(Section::Glyph(GlyphSection {
+                glyphs: inside,
+                font,
+                color,
+            }));
+        }
+        if !cut.is_empty() {
+            self.push_tris(TriangleSection::new(cut, clip));
+        }
+    }
+}
+
+fn contains(clip: Rect, bb: rusttype::Rect<i32>) -> bool {
+    clip.left <= bb.min.x as f32
+        && clip.top <= bb.min.y as f32
+        && bb.max.x as f32 <= clip.right
+        && bb.max.y as f32 <= clip.bottom
+}
+
+/// Rasterizes the part of `glyph` inside `clip` as a square for each pixel. Like the glyph cache,
+/// the glyph is rasterized at its logical size.
+fn cut_glyph(
+    glyph: &PositionedGlyph,
+    bb: rusttype::Rect<i32>,
+    color: Color,
+    clip: Rect,
+    vertices: &mut Vec<TriangleVertex>,
+) {
+    glyph.draw(|x, y, coverage| {
+        let (x, y) = ((bb.min.x + x as i32) as f32, (bb.min.y + y as i32) as f32);
+        let outside =
+            x + 1.0 <= clip.left || clip.right <= x || y + 1.0 <= clip.top || clip.bottom <= y;
+        if coverage <= 0.0 || outside {
+            return;
+        }
+
+        let color = Color::rgba(color.r, color.g, color.b, color.a * coverage);
+        let quad = [
+            Point(x, y),
+            Point(x + 1.0, y),
+            Point(x, y + 1.0),
+            Point(x + 1.0, y + 1.0),
+        ];
+        for &i in &[0, 1, 2, 2, 1, 3] {
+            vertices.push(TriangleVertex::new(quad[i], color));
+        }
+    });
+}
+
+/// Returns `state` with a scissor covering `clip`, converted from logical pixels to the pixels of
+/// the viewport. No clip gives a scissor covering the whole viewport.
+pub(in d2) fn clip_state(
+    state: &DynamicState,
+    clip: Option<Rect>,
+    logical_size: [f32; 2],
+) -> DynamicState {
+    let scissor = match clip {
+        Some(clip) => {
+            let viewport = &state.viewports.as_ref().expect("viewport not set")[0];
+            let scale = [
+                viewport.dimensions[0] / logical_size[0],
+                viewport.dimensions[1] / logical_size[1],
+            ];
+            let left = (viewport.origin[0] + clip.left * scale[0]).round().max(0.0);
+            let top = (viewport.origin[1] + clip.top * scale[1]).round().max(0.0);
+            let right = (viewport.origin[0] + clip.right * scale[0]).round().max(left);
+            let bottom = (viewport.origin[1] + clip.bottom * scale[1]).round().max(top);
+            Scissor {
+                origin: [left as i32, top as i32],
+                dimensions: [(right - left) as u32, (bottom - top) as u32],
+            }
+        }
+        None => Scissor::irrelevant(),
+    };
+
+    DynamicState {
+        scissors: Some(vec![scissor]),
+        ..state.clone()
     }
 }
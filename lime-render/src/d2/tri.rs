@@ -1,4 +1,3 @@
-use std::ops::Range;
 use std::sync::Arc;
 
 use failure::Fallible;
@@ -12,7 +11,7 @@ use vulkano::framebuffer::{RenderPassAbstract, Subpass};
 use vulkano::pipeline::vertex::SingleBufferDefinition;
 use vulkano::pipeline::GraphicsPipeline;
 
-use d2::Point;
+use d2::{clip_state, Point, Rect};
 use Color;
 
 type Pipeline = Arc<
@@ -28,12 +27,12 @@ pub(in d2) struct TriangleBrush {
     ubuf: CpuBufferPool<vs::ty::Data>,
     pipe: Pipeline,
     pool: FixedSizeDescriptorSetsPool<Pipeline>,
-    queued: Vec<Vertex>,
 }
 
 #[derive(Clone, Debug)]
-pub(in d2) struct TriangleSection {
-    range: Range<usize>,
+pub(crate) struct TriangleSection {
+    pub(crate) vertices: Vec<Vertex>,
+    pub(crate) clip: Option<Rect>,
 }
 
 impl TriangleBrush {
@@ -49,8 +48,9 @@ impl TriangleBrush {
                 .vertex_input_single_buffer::<Vertex>()
                 .vertex_shader(vs.main_entry_point(), ())
                 .triangle_list()
-                .viewports_dynamic_scissors_irrelevant(1)
+                .viewports_scissors_dynamic(1)
                 .fragment_shader(fs.main_entry_point(), ())
+                .blend_alpha_blending()
                 .render_pass(subpass)
                 .build(Arc::clone(device))
                 .unwrap_or_else(throw),
@@ -66,7 +66,6 @@ impl TriangleBrush {
             vbuf,
             ubuf,
             pool,
-            queued: Vec::new(),
         }
     }
 
@@ -77,46 +76,47 @@ impl TriangleBrush {
         state: &DynamicState,
         logical_size: [f32; 2],
     ) -> Fallible<AutoCommandBufferBuilder> {
-        if section.range.len() == 0 {
+        if section.vertices.is_empty() {
             return Ok(cmd);
         }
 
-        let vbuf = self
-            .vbuf
-            .chunk(self.queued[section.range.clone()].iter().cloned())?;
+        let state = clip_state(state, section.clip, logical_size);
+
+        let vbuf = self.vbuf.chunk(section.vertices.iter().cloned())?;
         let ubuf = self.ubuf.next(vs::ty::Data {
             dimensions: logical_size,
         })?;
         let set = self.pool.next().add_buffer(ubuf)?.build()?;
 
-        Ok(cmd.draw(Arc::clone(&self.pipe), state, vbuf, set, ())?)
+        Ok(cmd.draw(Arc::clone(&self.pipe), &state, vbuf, set, ())?)
     }
+}
 
-    pub(in d2) fn queue_tris(&mut self, vertices: &[Point], color: Color) -> TriangleSection {
+impl TriangleSection {
+    pub(in d2) fn new(vertices: Vec<Vertex>, clip: Option<Rect>) -> Self {
         debug_assert!(vertices.len() % 3 == 0);
-        let start = self.queued.len();
-        self.queued
-            .extend(vertices.iter().map(|&v| Vertex::new(v, color)));
-        let end = self.queued.len();
-        TriangleSection { range: start..end }
+        TriangleSection { vertices, clip }
     }
-}
 
-impl TriangleSection {
-    pub(in d2) fn append(&mut self, next: &TriangleSection) {
-        debug_assert_eq!(self.range.end, next.range.start);
-        self.range.end = next.range.end;
+    /// Extends this section with `next`. Returns `false` if the sections cannot be merged
+    /// because they are clipped differently.
+    pub(in d2) fn append(&mut self, next: &TriangleSection) -> bool {
+        if self.clip != next.clip {
+            return false;
+        }
+        self.vertices.extend_from_slice(&next.vertices);
+        true
     }
 }
 
 #[derive(Copy, Clone, Debug)]
-struct Vertex {
-    position: Point,
-    color: Color,
+pub(crate) struct Vertex {
+    pub(crate) position: Point,
+    pub(crate) color: Color,
 }
 
 impl Vertex {
-    fn new(position: Point, color: Color) -> Self {
+    pub(in d2) fn new(position: Point, color: Color) -> Self {
         Vertex { position, color }
     }
 }
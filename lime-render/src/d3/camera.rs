@@ -0,0 +1,85 @@
+use cgmath::{self, Deg, Matrix4, Point3, Rad, Vector3};
+
+/// The viewpoint from which meshes are drawn, stored as a resource.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Camera {
+    pub eye: Point3<f32>,
+    pub target: Point3<f32>,
+    pub up: Vector3<f32>,
+    pub projection: Projection,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Projection {
+    /// A perspective projection with the given vertical field of view.
+    Perspective { fovy: Rad<f32>, near: f32, far: f32 },
+    /// An orthographic projection showing `height` units vertically.
+    Orthographic { height: f32, near: f32, far: f32 },
+}
+
+impl Camera {
+    /// Creates a camera with a perspective projection, looking at the origin from the positive
+    /// z axis.
+    pub fn perspective(fovy: impl Into<Rad<f32>>, near: f32, far: f32) -> Self {
+        Camera::new(Projection::Perspective {
+            fovy: fovy.into(),
+            near,
+            far,
+        })
+    }
+
+    /// Creates a camera with an orthographic projection, looking at the origin from the
+    /// positive z axis.
+    pub fn orthographic(height: f32, near: f32, far: f32) -> Self {
+        Camera::new(Projection::Orthographic { height, near, far })
+    }
+
+    fn new(projection: Projection) -> Self {
+        Camera {
+            eye: Point3::new(0.0, 0.0, 2.0),
+            target: Point3::new(0.0, 0.0, 0.0),
+            up: Vector3::unit_y(),
+            projection,
+        }
+    }
+
+    pub fn look_at(mut self, eye: Point3<f32>, target: Point3<f32>, up: Vector3<f32>) -> Self {
+        self.eye = eye;
+        self.target = target;
+        self.up = up;
+        self
+    }
+
+    /// The transformation from world space to view space.
+    pub fn view(&self) -> Matrix4<f32> {
+        Matrix4::look_at(self.eye, self.target, self.up)
+    }
+
+    /// The transformation from view space to Vulkan clip space, for a viewport with the given
+    /// ratio of width to height.
+    pub fn projection(&self, aspect: f32) -> Matrix4<f32> {
+        let proj = match self.projection {
+            Projection::Perspective { fovy, near, far } => {
+                cgmath::perspective(fovy, aspect, near, far)
+            }
+            Projection::Orthographic { height, near, far } => {
+                let (w, h) = (height * aspect / 2.0, height / 2.0);
+                cgmath::ortho(-w, w, -h, h, near, far)
+            }
+        };
+        gl_to_vulkan() * proj
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera::perspective(Deg(60.0), 0.1, 100.0)
+    }
+}
+
+// Converts from OpenGL clip space, as produced by `cgmath`, to Vulkan clip space, which has
+// the y axis pointing down and depth ranging from 0 to 1.
+fn gl_to_vulkan() -> Matrix4<f32> {
+    Matrix4::from_translation(Vector3::new(0.0, 0.0, 0.5))
+        * Matrix4::from_nonuniform_scale(1.0, -1.0, 0.5)
+}
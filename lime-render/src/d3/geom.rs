@@ -1,3 +1,4 @@
+use cgmath::Vector3;
 use vulkano::pipeline::vertex::{VertexMember, VertexMemberTy};
 
 #[repr(C)]
@@ -21,3 +22,15 @@ unsafe impl VertexMember for Vector {
         (VertexMemberTy::F32, 3)
     }
 }
+
+impl From<Vector3<f32>> for Vector {
+    fn from(v: Vector3<f32>) -> Self {
+        Vector(v.x, v.y, v.z)
+    }
+}
+
+impl From<Vector> for Vector3<f32> {
+    fn from(v: Vector) -> Self {
+        Vector3::new(v.0, v.1, v.2)
+    }
+}
@@ -0,0 +1,23 @@
+use cgmath::Vector3;
+
+use Color;
+
+/// A directional light illuminating all meshes, stored as a resource.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Light {
+    /// The direction in which the light travels, in world space.
+    pub direction: Vector3<f32>,
+    pub color: Color,
+    /// Light reaching every surface regardless of its orientation.
+    pub ambient: Color,
+}
+
+impl Default for Light {
+    fn default() -> Self {
+        Light {
+            direction: Vector3::new(0.0, 0.0, -1.0),
+            color: Color::rgb(0.4, 0.4, 0.4),
+            ambient: Color::rgb(0.6, 0.6, 0.6),
+        }
+    }
+}
@@ -0,0 +1,279 @@
+//! Loading of meshes from Wavefront OBJ and glTF 2.0 files.
+//!
+//! Both loaders return the vertices and indices of a single mesh, ready to be passed to
+//! `Mesh::new`. Normals missing from the file are computed from the faces sharing each vertex,
+//! weighted by their area. Texture coordinates and materials are ignored.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use cgmath::{InnerSpace, Matrix, Matrix3, Matrix4, SquareMatrix, Vector3, Vector4, Zero};
+use failure::{err_msg, Fallible};
+use gltf;
+use gltf::mesh::Mode;
+
+use d3::{Indices, Vector};
+
+/// The vertices and indices of a loaded mesh.
+pub type MeshData = (Vec<(Vector, Vector)>, Indices);
+
+/// Loads a mesh from `path`, choosing the format by its extension: `obj` for Wavefront OBJ and
+/// `gltf` or `glb` for glTF 2.0.
+pub fn from_path(path: impl AsRef<Path>) -> Fallible<MeshData> {
+    let path = path.as_ref();
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+    match ext.as_ref().map(String::as_str) {
+        Some("obj") => obj(BufReader::new(File::open(path)?)),
+        Some("gltf") | Some("glb") => gltf(path),
+        _ => Err(err_msg(format!(
+            "unsupported mesh format '{}'",
+            path.display()
+        ))),
+    }
+}
+
+/// Parses a Wavefront OBJ file. Only vertex positions, normals and faces are read, and all
+/// objects and groups in the file are merged into one mesh. Faces with more than three vertices
+/// are split into triangle fans.
+pub fn obj(reader: impl BufRead) -> Fallible<MeshData> {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut builder = Builder::default();
+    let mut lookup = HashMap::new();
+
+    for (line_num, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_num = line_num + 1;
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("v") => positions.push(parse_vector(&mut words, line_num)?),
+            Some("vn") => normals.push(parse_vector(&mut words, line_num)?),
+            Some("f") => {
+                let mut face = Vec::new();
+                for word in words {
+                    let key = parse_face_vertex(word, positions.len(), normals.len(), line_num)?;
+                    let idx = *lookup.entry(key).or_insert_with(|| {
+                        let (pos, normal) = key;
+                        builder.push(positions[pos], normal.map(|n| normals[n]))
+                    });
+                    face.push(idx);
+                }
+                if face.len() < 3 {
+                    return Err(err_msg(format!(
+                        "line {}: face has fewer than three vertices",
+                        line_num
+                    )));
+                }
+                for i in 2..face.len() {
+                    builder.indices.extend(&[face[0], face[i - 1], face[i]]);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    Ok(builder.finish())
+}
+
+/// Loads a glTF 2.0 file, in either the JSON or the binary format. The meshes of all nodes in
+/// the default scene are merged into one mesh, with node transforms applied. Primitives not
+/// made of triangle lists are skipped.
+pub fn gltf(path: impl AsRef<Path>) -> Fallible<MeshData> {
+    let (document, buffers, _) = gltf::import(path)?;
+    let mut builder = Builder::default();
+
+    match document.default_scene().or_else(|| document.scenes().next()) {
+        Some(scene) => {
+            for node in scene.nodes() {
+                gltf_node(&mut builder, &node, Matrix4::identity(), &buffers)?;
+            }
+        }
+        None => {
+            for mesh in document.meshes() {
+                gltf_mesh(&mut builder, &mesh, Matrix4::identity(), &buffers)?;
+            }
+        }
+    }
+
+    Ok(builder.finish())
+}
+
+fn gltf_node(
+    builder: &mut Builder,
+    node: &gltf::Node,
+    parent: Matrix4<f32>,
+    buffers: &[gltf::buffer::Data],
+) -> Fallible<()> {
+    let transform = parent * Matrix4::from(node.transform().matrix());
+    if let Some(mesh) = node.mesh() {
+        gltf_mesh(builder, &mesh, transform, buffers)?;
+    }
+    for child in node.children() {
+        gltf_node(builder, &child, transform, buffers)?;
+    }
+    Ok(())
+}
+
+fn gltf_mesh(
+    builder: &mut Builder,
+    mesh: &gltf::Mesh,
+    transform: Matrix4<f32>,
+    buffers: &[gltf::buffer::Data],
+) -> Fallible<()> {
+    let normal_matrix = Matrix3::from_cols(
+        transform.x.truncate(),
+        transform.y.truncate(),
+        transform.z.truncate(),
+    ).invert()
+        .map(|m| m.transpose())
+        .unwrap_or_else(Matrix3::identity);
+
+    for primitive in mesh.primitives() {
+        if primitive.mode() != Mode::Triangles {
+            warn!("Skipping glTF primitive with mode {:?}.", primitive.mode());
+            continue;
+        }
+
+        let reader = primitive.reader(|buffer| Some(&*buffers[buffer.index()]));
+        let positions = reader
+            .read_positions()
+            .ok_or_else(|| err_msg("glTF primitive has no positions"))?;
+        let mut normals = reader.read_normals();
+
+        let base = builder.positions.len() as u32;
+        for [x, y, z] in positions {
+            let pos = (transform * Vector4::new(x, y, z, 1.0)).truncate();
+            let normal = normals
+                .as_mut()
+                .and_then(|normals| normals.next())
+                .map(|[x, y, z]| (normal_matrix * Vector3::new(x, y, z)).normalize().into());
+            builder.push(pos.into(), normal);
+        }
+        let count = builder.positions.len() as u32 - base;
+
+        match reader.read_indices() {
+            Some(indices) => for idx in indices.into_u32() {
+                if idx >= count {
+                    return Err(err_msg(format!(
+                        "glTF primitive has index {} out of range for {} vertices",
+                        idx, count
+                    )));
+                }
+                builder.indices.push(base + idx);
+            },
+            None => builder.indices.extend(base..base + count),
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct Builder {
+    positions: Vec<Vector>,
+    normals: Vec<Option<Vector>>,
+    indices: Vec<u32>,
+}
+
+impl Builder {
+    fn push(&mut self, position: Vector, normal: Option<Vector>) -> u32 {
+        self.positions.push(position);
+        self.normals.push(normal);
+        self.positions.len() as u32 - 1
+    }
+
+    fn finish(self) -> MeshData {
+        let normals: Vec<Vector> = if self.normals.iter().any(Option::is_none) {
+            let computed = compute_normals(&self.positions, &self.indices);
+            self.normals
+                .into_iter()
+                .zip(computed)
+                .map(|(normal, computed)| normal.unwrap_or(computed))
+                .collect()
+        } else {
+            self.normals.into_iter().map(Option::unwrap).collect()
+        };
+
+        let count = self.positions.len();
+        let vertices = self.positions.into_iter().zip(normals).collect();
+        (vertices, Indices::compact(self.indices, count))
+    }
+}
+
+/// Computes a normal for each vertex by summing the normals of the triangles around it. The
+/// cross product is proportional to the area of the triangle, so larger faces weigh more.
+fn compute_normals(positions: &[Vector], indices: &[u32]) -> Vec<Vector> {
+    let mut sums = vec![Vector3::zero(); positions.len()];
+    for tri in indices.chunks(3).filter(|tri| tri.len() == 3) {
+        let p = |i: usize| Vector3::from(positions[tri[i] as usize]);
+        let normal = (p(1) - p(0)).cross(p(2) - p(0));
+        for &idx in tri {
+            sums[idx as usize] += normal;
+        }
+    }
+
+    sums.into_iter()
+        .map(|sum| {
+            if sum.magnitude2() > 0.0 {
+                sum.normalize().into()
+            } else {
+                Vector(0.0, 0.0, 1.0)
+            }
+        })
+        .collect()
+}
+
+fn parse_vector<'a>(
+    words: &mut impl Iterator<Item = &'a str>,
+    line_num: usize,
+) -> Fallible<Vector> {
+    let mut coord = || -> Fallible<f32> {
+        let word = words
+            .next()
+            .ok_or_else(|| err_msg(format!("line {}: expected three coordinates", line_num)))?;
+        word.parse()
+            .map_err(|_| err_msg(format!("line {}: invalid number '{}'", line_num, word)))
+    };
+    Ok(Vector(coord()?, coord()?, coord()?))
+}
+
+/// Parses a face vertex of the form `v`, `v/vt`, `v//vn` or `v/vt/vn`, returning the zero-based
+/// indices of its position and normal.
+fn parse_face_vertex(
+    word: &str,
+    positions: usize,
+    normals: usize,
+    line_num: usize,
+) -> Fallible<(usize, Option<usize>)> {
+    let mut parts = word.split('/');
+    let pos = parse_index(parts.next().unwrap_or(""), positions, line_num)?;
+    let normal = match parts.nth(1) {
+        Some(part) if !part.is_empty() => Some(parse_index(part, normals, line_num)?),
+        _ => None,
+    };
+    Ok((pos, normal))
+}
+
+/// Parses a one-based index, or a negative index counting back from the end of the list.
+fn parse_index(word: &str, len: usize, line_num: usize) -> Fallible<usize> {
+    let idx: isize = word
+        .parse()
+        .map_err(|_| err_msg(format!("line {}: invalid index '{}'", line_num, word)))?;
+    let resolved = if idx > 0 {
+        idx - 1
+    } else {
+        len as isize + idx
+    };
+    if idx == 0 || resolved < 0 || resolved >= len as isize {
+        return Err(err_msg(format!(
+            "line {}: index {} out of range",
+            line_num, idx
+        )));
+    }
+    Ok(resolved as usize)
+}
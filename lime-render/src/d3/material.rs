@@ -0,0 +1,25 @@
+use specs::prelude::*;
+
+use Color;
+
+/// The surface properties of a mesh entity. Entities without a material are drawn white.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Material {
+    pub color: Color,
+}
+
+impl Material {
+    pub fn new(color: Color) -> Self {
+        Material { color }
+    }
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Material::new(Color::WHITE)
+    }
+}
+
+impl Component for Material {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -1,16 +1,47 @@
+use std::path::Path;
 use std::sync::Arc;
 
+use failure::Fallible;
+use specs::prelude::*;
 use utils::throw;
 use vulkano::buffer::{BufferUsage, ImmutableBuffer};
 use vulkano::sync::GpuFuture;
 
-use d3::{Vector, Vertex};
+use d3::{load, Vector, Vertex};
 use Context;
 
 #[derive(Clone)]
 pub struct Mesh {
+    pub(in d3) buffers: Option<Buffers>,
+    #[cfg(feature = "software")]
+    pub(crate) data: Arc<MeshData>,
+}
+
+#[derive(Clone)]
+pub(in d3) struct Buffers {
     pub(in d3) vertices: Arc<ImmutableBuffer<[Vertex]>>,
-    pub(in d3) indices: Arc<ImmutableBuffer<[u16]>>,
+    pub(in d3) indices: IndexBuffer,
+}
+
+#[derive(Clone)]
+pub(in d3) enum IndexBuffer {
+    U16(Arc<ImmutableBuffer<[u16]>>),
+    U32(Arc<ImmutableBuffer<[u32]>>),
+}
+
+/// The indices of the triangles of a mesh. Meshes with more than 65536 vertices need 32 bit
+/// indices.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Indices {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+/// A copy of the vertices and indices of a mesh, kept for the software rasterizer.
+#[cfg(feature = "software")]
+pub(crate) struct MeshData {
+    pub(crate) vertices: Vec<Vertex>,
+    pub(crate) indices: Vec<u32>,
 }
 
 impl Mesh {
@@ -18,21 +49,156 @@ impl Mesh {
     where
         V: IntoIterator<Item = (Vector, Vector)>,
         V::IntoIter: ExactSizeIterator,
-        I: IntoIterator<Item = u16>,
-        I::IntoIter: ExactSizeIterator,
+        I: Into<Indices>,
     {
+        let indices = indices.into();
+
+        #[cfg(feature = "software")]
+        let (vertices, data) = {
+            let vertices: Vec<_> = vertices.into_iter().collect();
+            let data = Arc::new(MeshData::new(vertices.iter().cloned(), &indices));
+            (vertices, data)
+        };
+
         let (vertices, vertices_future) = ImmutableBuffer::from_iter(
             vertices.into_iter().map(Vertex::new),
             BufferUsage::vertex_buffer(),
             Arc::clone(ctx.transfer_queue()),
         ).unwrap_or_else(throw);
-        let (indices, indices_future) = ImmutableBuffer::from_iter(
-            indices.into_iter(),
-            BufferUsage::index_buffer(),
-            Arc::clone(ctx.transfer_queue()),
-        ).unwrap_or_else(throw);
+        let (indices, indices_future): (_, Box<GpuFuture>) = match indices {
+            Indices::U16(indices) => {
+                let (buffer, future) = ImmutableBuffer::from_iter(
+                    indices.into_iter(),
+                    BufferUsage::index_buffer(),
+                    Arc::clone(ctx.transfer_queue()),
+                ).unwrap_or_else(throw);
+                (IndexBuffer::U16(buffer), Box::new(future))
+            }
+            Indices::U32(indices) => {
+                let (buffer, future) = ImmutableBuffer::from_iter(
+                    indices.into_iter(),
+                    BufferUsage::index_buffer(),
+                    Arc::clone(ctx.transfer_queue()),
+                ).unwrap_or_else(throw);
+                (IndexBuffer::U32(buffer), Box::new(future))
+            }
+        };
 
         let future = vertices_future.join(indices_future);
-        (Mesh { vertices, indices }, Box::new(future))
+        let mesh = Mesh {
+            buffers: Some(Buffers { vertices, indices }),
+            #[cfg(feature = "software")]
+            data,
+        };
+        (mesh, Box::new(future))
+    }
+
+    /// Loads a mesh from a Wavefront OBJ file or a glTF 2.0 file, depending on the extension of
+    /// `path`. See the `load` module for details.
+    pub fn load(ctx: &Context, path: impl AsRef<Path>) -> Fallible<(Self, Box<GpuFuture>)> {
+        let (vertices, indices) = load::from_path(path)?;
+        Ok(Mesh::new(ctx, vertices, indices))
+    }
+
+    /// Creates a mesh without uploading it to the GPU. It can only be drawn by the software
+    /// rasterizer.
+    #[cfg(feature = "software")]
+    pub fn software<V>(vertices: V, indices: impl Into<Indices>) -> Self
+    where
+        V: IntoIterator<Item = (Vector, Vector)>,
+    {
+        Mesh {
+            buffers: None,
+            data: Arc::new(MeshData::new(vertices, &indices.into())),
+        }
+    }
+
+    /// Whether `self` and `other` are clones of the same mesh, and so can be drawn together.
+    #[cfg(not(feature = "software"))]
+    pub(in d3) fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.buffers, &other.buffers) {
+            (Some(lhs), Some(rhs)) => Arc::ptr_eq(&lhs.vertices, &rhs.vertices),
+            _ => false,
+        }
+    }
+
+    /// Whether `self` and `other` are clones of the same mesh, and so can be drawn together.
+    #[cfg(feature = "software")]
+    pub(in d3) fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.data, &other.data)
+    }
+}
+
+impl Indices {
+    /// Uses 16 bit indices if they are large enough to index `vertex_count` vertices.
+    pub fn compact(indices: Vec<u32>, vertex_count: usize) -> Self {
+        if vertex_count <= usize::from(u16::max_value()) + 1 {
+            Indices::U16(indices.into_iter().map(|idx| idx as u16).collect())
+        } else {
+            Indices::U32(indices)
+        }
     }
+
+    pub fn len(&self) -> usize {
+        match *self {
+            Indices::U16(ref indices) => indices.len(),
+            Indices::U32(ref indices) => indices.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn to_u32(&self) -> Vec<u32> {
+        match *self {
+            Indices::U16(ref indices) => indices.iter().map(|&idx| u32::from(idx)).collect(),
+            Indices::U32(ref indices) => indices.clone(),
+        }
+    }
+}
+
+impl From<Vec<u16>> for Indices {
+    fn from(indices: Vec<u16>) -> Self {
+        Indices::U16(indices)
+    }
+}
+
+impl From<Vec<u32>> for Indices {
+    fn from(indices: Vec<u32>) -> Self {
+        Indices::U32(indices)
+    }
+}
+
+#[cfg(feature = "software")]
+impl MeshData {
+    fn new<V>(vertices: V, indices: &Indices) -> Self
+    where
+        V: IntoIterator<Item = (Vector, Vector)>,
+    {
+        MeshData {
+            vertices: vertices.into_iter().map(Vertex::new).collect(),
+            indices: indices.to_u32(),
+        }
+    }
+}
+
+/// A component drawing a mesh at the entity's `Transform`.
+#[derive(Clone)]
+pub struct MeshHandle {
+    mesh: Mesh,
+}
+
+impl MeshHandle {
+    pub fn new(mesh: Mesh) -> Self {
+        MeshHandle { mesh }
+    }
+
+    pub fn mesh(&self) -> &Mesh {
+        &self.mesh
+    }
+}
+
+impl Component for MeshHandle {
+    type Storage = DenseVecStorage<Self>;
 }
@@ -1,12 +1,25 @@
+mod camera;
 mod geom;
+mod light;
+pub mod load;
+mod material;
 mod mesh;
+mod sys;
+mod transform;
 
+pub use self::camera::{Camera, Projection};
 pub use self::geom::Vector;
-pub use self::mesh::Mesh;
+pub use self::light::Light;
+pub use self::material::Material;
+pub use self::mesh::{Indices, Mesh, MeshHandle};
+pub use self::sys::MeshDrawSystem;
+pub use self::transform::{Parent, Transform};
 
 use std::sync::Arc;
 
+use cgmath::Matrix4;
 use failure::Fallible;
+use specs::World;
 use utils::throw;
 use vulkano::buffer::CpuBufferPool;
 use vulkano::command_buffer::{AutoCommandBufferBuilder, DynamicState};
@@ -14,22 +27,33 @@ use vulkano::descriptor::descriptor_set::FixedSizeDescriptorSetsPool;
 use vulkano::descriptor::PipelineLayoutAbstract;
 use vulkano::device::Device;
 use vulkano::framebuffer::{RenderPassAbstract, Subpass};
-use vulkano::pipeline::vertex::SingleBufferDefinition;
+use vulkano::pipeline::vertex::OneVertexOneInstanceDefinition;
 use vulkano::pipeline::GraphicsPipeline;
 
+use d3::mesh::IndexBuffer;
+
 type Pipeline = Arc<
     GraphicsPipeline<
-        SingleBufferDefinition<Vertex>,
+        OneVertexOneInstanceDefinition<Vertex, Instance>,
         Box<PipelineLayoutAbstract + Send + Sync>,
         Arc<RenderPassAbstract + Send + Sync>,
     >,
 >;
 
 pub struct Renderer {
+    gpu: Option<Gpu>,
+    queued: Vec<Batch>,
+    pub(crate) camera: Camera,
+    pub(crate) light: Light,
+}
+
+// The GPU pipeline used to draw meshes. This is absent when rendering in software.
+struct Gpu {
     ubuf: CpuBufferPool<vs::ty::Data>,
+    light_buf: CpuBufferPool<fs::ty::Light>,
+    instance_buf: CpuBufferPool<Instance>,
     pool: FixedSizeDescriptorSetsPool<Pipeline>,
     pipe: Pipeline,
-    queued: Vec<Mesh>,
 }
 
 impl Renderer {
@@ -42,7 +66,7 @@ impl Renderer {
 
         let pipe = Arc::new(
             GraphicsPipeline::start()
-                .vertex_input(SingleBufferDefinition::new())
+                .vertex_input(OneVertexOneInstanceDefinition::new())
                 .vertex_shader(vs.main_entry_point(), ())
                 .triangle_list()
                 .viewports_dynamic_scissors_irrelevant(1)
@@ -54,13 +78,32 @@ impl Renderer {
         );
 
         let ubuf = CpuBufferPool::uniform_buffer(Arc::clone(device));
+        let light_buf = CpuBufferPool::uniform_buffer(Arc::clone(device));
+        let instance_buf = CpuBufferPool::vertex_buffer(Arc::clone(device));
         let pool = FixedSizeDescriptorSetsPool::new(Arc::clone(&pipe), 0);
 
         Renderer {
-            pipe,
-            ubuf,
+            gpu: Some(Gpu {
+                pipe,
+                ubuf,
+                light_buf,
+                instance_buf,
+                pool,
+            }),
+            queued: Vec::new(),
+            camera: Camera::default(),
+            light: Light::default(),
+        }
+    }
+
+    /// Creates a renderer which only records meshes, for the software rasterizer.
+    #[cfg(feature = "software")]
+    pub(crate) fn software() -> Self {
+        Renderer {
+            gpu: None,
             queued: Vec::new(),
-            pool,
+            camera: Camera::default(),
+            light: Light::default(),
         }
     }
 
@@ -69,49 +112,126 @@ impl Renderer {
         mut cmd: AutoCommandBufferBuilder,
         state: &DynamicState,
     ) -> Fallible<AutoCommandBufferBuilder> {
-        let ubuf = self.ubuf.next(vs::ty::Data {
-            world: [
-                [1.0, 0.0, 0.0, 0.0],
-                [0.0, 1.0, 0.0, 0.0],
-                [0.0, 0.0, 1.0, 0.0],
-                [0.0, 0.0, 0.0, 1.0],
-            ],
-            view: [
-                [1.0, 0.0, 0.0, 0.0],
-                [0.0, 1.0, 0.0, 0.0],
-                [0.0, 0.0, 1.0, 0.0],
-                [0.0, 0.0, 0.0, 1.0],
-            ],
-            proj: [
-                [1.0, 0.0, 0.0, 0.0],
-                [0.0, 1.0, 0.0, 0.0],
-                [0.0, 0.0, 1.0, 0.0],
-                [0.0, 0.0, 0.0, 1.0],
-            ],
+        let gpu = self
+            .gpu
+            .as_mut()
+            .expect("software renderer cannot record commands");
+        let viewport = &state.viewports.as_ref().expect("viewport not set")[0];
+        let aspect = viewport.dimensions[0] / viewport.dimensions[1];
+        let ubuf = gpu.ubuf.next(vs::ty::Data {
+            view: self.camera.view().into(),
+            proj: self.camera.projection(aspect).into(),
+        })?;
+        let light = &self.light;
+        let light_buf = gpu.light_buf.next(fs::ty::Light {
+            direction: light.direction.extend(0.0).into(),
+            color: light.color.into(),
+            ambient: light.ambient.into(),
         })?;
-        let set = Arc::new(self.pool.next().add_buffer(ubuf)?.build()?);
-        for mesh in self.queued.drain(..) {
-            cmd = cmd.draw_indexed(
-                Arc::clone(&self.pipe),
-                state,
-                mesh.vertices,
-                mesh.indices,
-                Arc::clone(&set),
-                (),
-            )?;
+        let set = Arc::new(
+            gpu.pool
+                .next()
+                .add_buffer(ubuf)?
+                .add_buffer(light_buf)?
+                .build()?,
+        );
+        for batch in self.queued.drain(..) {
+            if batch.instances.is_empty() {
+                continue;
+            }
+            let buffers = match batch.mesh.buffers {
+                Some(buffers) => buffers,
+                None => {
+                    warn!("Skipping mesh which was not uploaded to the GPU.");
+                    continue;
+                }
+            };
+            let instances = gpu
+                .instance_buf
+                .chunk(batch.instances.into_iter().map(Instance::new))?;
+            let constants = vs::ty::PushConstants {
+                color: batch.material.color.into(),
+            };
+            cmd = match buffers.indices {
+                IndexBuffer::U16(indices) => cmd.draw_indexed(
+                    Arc::clone(&gpu.pipe),
+                    state,
+                    (buffers.vertices, instances),
+                    indices,
+                    Arc::clone(&set),
+                    constants,
+                )?,
+                IndexBuffer::U32(indices) => cmd.draw_indexed(
+                    Arc::clone(&gpu.pipe),
+                    state,
+                    (buffers.vertices, instances),
+                    indices,
+                    Arc::clone(&set),
+                    constants,
+                )?,
+            };
         }
         Ok(cmd)
     }
 
-    pub fn draw_mesh(&mut self, mesh: Mesh) {
-        self.queued.push(mesh);
+    /// Removes and returns the meshes queued since the last frame, one for each instance.
+    #[cfg(feature = "software")]
+    pub(crate) fn take_meshes(&mut self) -> Vec<(Mesh, Matrix4<f32>, Material)> {
+        let mut meshes = Vec::new();
+        for batch in self.queued.drain(..) {
+            for world in batch.instances {
+                meshes.push((batch.mesh.clone(), world, batch.material));
+            }
+        }
+        meshes
     }
+
+    /// Queues `mesh` to be drawn as seen by the `Camera`, after transforming it from model space
+    /// to world space by `world`.
+    pub fn draw_mesh(&mut self, mesh: Mesh, world: Matrix4<f32>, material: &Material) {
+        self.draw_mesh_instanced(mesh, Some(world), material);
+    }
+
+    /// Queues a copy of `mesh` to be drawn for each transform in `worlds`. Copies of the same
+    /// mesh with the same material, including those queued by `draw_mesh`, are drawn with a
+    /// single draw call.
+    pub fn draw_mesh_instanced<I>(&mut self, mesh: Mesh, worlds: I, material: &Material)
+    where
+        I: IntoIterator<Item = Matrix4<f32>>,
+    {
+        let batch = self
+            .queued
+            .iter()
+            .position(|batch| batch.mesh.ptr_eq(&mesh) && batch.material == *material);
+        match batch {
+            Some(idx) => self.queued[idx].instances.extend(worlds),
+            None => self.queued.push(Batch {
+                mesh,
+                material: *material,
+                instances: worlds.into_iter().collect(),
+            }),
+        }
+    }
+}
+
+// The instances of a mesh drawn with the same material.
+struct Batch {
+    mesh: Mesh,
+    material: Material,
+    instances: Vec<Matrix4<f32>>,
+}
+
+pub(crate) fn register(world: &mut World) {
+    world.register::<MeshHandle>();
+    world.register::<Transform>();
+    world.register::<Material>();
+    world.register::<Parent>();
 }
 
 #[derive(Copy, Clone, Debug)]
-struct Vertex {
-    position: Vector,
-    normal: Vector,
+pub(crate) struct Vertex {
+    pub(crate) position: Vector,
+    pub(crate) normal: Vector,
 }
 
 impl Vertex {
@@ -122,6 +242,19 @@ impl Vertex {
 
 impl_vertex!(Vertex, position, normal);
 
+#[derive(Copy, Clone, Debug)]
+struct Instance {
+    world: [[f32; 4]; 4],
+}
+
+impl Instance {
+    fn new(world: Matrix4<f32>) -> Self {
+        Instance { world: world.into() }
+    }
+}
+
+impl_vertex!(Instance, world);
+
 #[allow(unused)]
 mod vs {
     #[derive(VulkanoShader)]
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use cgmath::Matrix4;
+use specs::prelude::*;
+
+use d3::{Material, MeshHandle, Parent, Renderer, Transform};
+
+/// Draws every entity with a `MeshHandle` and a `Transform`.
+pub struct MeshDrawSystem;
+
+impl MeshDrawSystem {
+    pub const NAME: &'static str = "d3::Draw";
+}
+
+impl<'a> System<'a> for MeshDrawSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteExpect<'a, Renderer>,
+        ReadStorage<'a, MeshHandle>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, Material>,
+        ReadStorage<'a, Parent>,
+    );
+
+    fn run(
+        &mut self,
+        (ents, mut renderer, meshes, transforms, materials, parents): Self::SystemData,
+    ) {
+        let mut worlds = HashMap::new();
+        for (entity, mesh, _) in (&*ents, &meshes, &transforms).join() {
+            let world = world_transform(entity, &transforms, &parents, &mut worlds);
+            let material = materials.get(entity).cloned().unwrap_or_default();
+            renderer.draw_mesh(mesh.mesh().clone(), world, &material);
+        }
+    }
+}
+
+// Combines the transforms of an entity and its ancestors. Results are cached in `worlds` so
+// that shared ancestors are only visited once.
+fn world_transform(
+    entity: Entity,
+    transforms: &ReadStorage<Transform>,
+    parents: &ReadStorage<Parent>,
+    worlds: &mut HashMap<Entity, Matrix4<f32>>,
+) -> Matrix4<f32> {
+    if let Some(&world) = worlds.get(&entity) {
+        return world;
+    }
+
+    // Collect the ancestors whose transforms are not yet known, nearest first.
+    let mut chain = vec![entity];
+    let mut world = Matrix4::from_scale(1.0);
+    while let Some(parent) = parents.get(chain[chain.len() - 1]) {
+        if let Some(&parent_world) = worlds.get(&parent.entity) {
+            world = parent_world;
+            break;
+        }
+        if chain.contains(&parent.entity) {
+            warn!("Entity {:?} is its own ancestor.", parent.entity);
+            break;
+        }
+        chain.push(parent.entity);
+    }
+
+    for &ent in chain.iter().rev() {
+        if let Some(transform) = transforms.get(ent) {
+            world = world * transform.matrix();
+        }
+        worlds.insert(ent, world);
+    }
+    world
+}
@@ -0,0 +1,72 @@
+use cgmath::{Matrix4, One, Quaternion, Vector3};
+use specs::prelude::*;
+
+/// The position, orientation and size of a mesh, relative to the entity's `Parent` if it has
+/// one and otherwise to world space. Scaling is applied first, followed by rotation and then
+/// translation.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    pub translation: Vector3<f32>,
+    pub rotation: Quaternion<f32>,
+    pub scale: Vector3<f32>,
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        Transform {
+            translation: Vector3::new(0.0, 0.0, 0.0),
+            rotation: Quaternion::one(),
+            scale: Vector3::new(1.0, 1.0, 1.0),
+        }
+    }
+
+    pub fn from_translation(translation: Vector3<f32>) -> Self {
+        Transform {
+            translation,
+            ..Transform::identity()
+        }
+    }
+
+    pub fn with_rotation(mut self, rotation: Quaternion<f32>) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vector3<f32>) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// The transformation from model space to world space.
+    pub fn matrix(&self) -> Matrix4<f32> {
+        Matrix4::from_translation(self.translation)
+            * Matrix4::from(self.rotation)
+            * Matrix4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z)
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::identity()
+    }
+}
+
+impl Component for Transform {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Attaches an entity to another, so that its `Transform` is relative to the parent's.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Parent {
+    pub entity: Entity,
+}
+
+impl Parent {
+    pub fn new(entity: Entity) -> Self {
+        Parent { entity }
+    }
+}
+
+impl Component for Parent {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,22 @@
+/// The number of physical pixels per logical pixel of the window being rendered to.
+///
+/// Layout, input and `d2` drawing all use logical pixels, which are scaled by this factor when
+/// rendered.
+#[derive(Copy, Clone, Debug)]
+pub struct DpiFactor(f64);
+
+impl DpiFactor {
+    pub fn new(factor: f64) -> Self {
+        DpiFactor(factor)
+    }
+
+    pub fn get(self) -> f64 {
+        self.0
+    }
+}
+
+impl Default for DpiFactor {
+    fn default() -> Self {
+        DpiFactor(1.0)
+    }
+}
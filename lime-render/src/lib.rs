@@ -1,6 +1,9 @@
 #[macro_use]
 extern crate approx;
+pub extern crate cgmath;
 extern crate failure;
+extern crate gltf;
+extern crate image;
 extern crate lime_utils as utils;
 #[macro_use]
 extern crate log;
@@ -20,15 +23,28 @@ extern crate winit;
 
 pub mod d2;
 pub mod d3;
+pub mod snapshot;
 
 mod color;
+mod config;
 mod context;
+mod dpi;
+mod screenshot;
+#[cfg(feature = "software")]
+mod software;
 mod sys;
 mod target;
+mod texture;
 
 pub use self::color::Color;
+pub use self::config::RenderConfig;
 pub use self::context::Context;
-pub use self::target::{ImageTarget, SwapchainTarget, Target};
+pub use self::dpi::DpiFactor;
+pub use self::screenshot::{Screenshot, ScreenshotRequest};
+#[cfg(feature = "software")]
+pub use self::software::SoftwareTarget;
+pub use self::target::{ImageTarget, OffscreenTarget, SwapchainTarget, Target};
+pub use self::texture::{PendingTexture, Texture};
 
 use specs::{DispatcherBuilder, World};
 
@@ -36,8 +52,25 @@ pub fn init<T: Target>(
     world: &mut World,
     dispatcher: &mut DispatcherBuilder,
     data: T::InitData,
+    config: RenderConfig,
     d3: &str,
     d2: &str,
 ) {
-    sys::RenderSystem::<T>::add(world, dispatcher, data, d3, d2);
+    sys::RenderSystem::<T>::add(world, dispatcher, data, config, d3, d2);
+}
+
+/// Adds a renderer which draws to a `SoftwareTarget` on the CPU, for environments without a
+/// GPU. No `Context` is added, so images are drawn from textures created with
+/// `Texture::from_rgba8`, and offscreen targets are not drawn. Only the clear color of `config`
+/// is used.
+#[cfg(feature = "software")]
+pub fn init_software(
+    world: &mut World,
+    dispatcher: &mut DispatcherBuilder,
+    dimensions: [u32; 2],
+    config: RenderConfig,
+    d3: &str,
+    d2: &str,
+) {
+    software::SoftwareRenderSystem::add(world, dispatcher, dimensions, config, d3, d2);
 }
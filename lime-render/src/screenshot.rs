@@ -0,0 +1,98 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use failure::Fallible;
+use vulkano::buffer::{BufferUsage, CpuAccessibleBuffer};
+use vulkano::format::Format;
+
+use {snapshot, Context};
+
+/// A resource used to request a screenshot. The next frame rendered after `request` is called
+/// is copied back from the GPU, and sent through the `EventChannel<Screenshot>` resource once
+/// it is available, usually a frame or two later.
+#[derive(Debug, Default)]
+pub struct ScreenshotRequest {
+    requested: bool,
+}
+
+impl ScreenshotRequest {
+    pub fn request(&mut self) {
+        self.requested = true;
+    }
+
+    pub(crate) fn take(&mut self) -> bool {
+        let requested = self.requested;
+        self.requested = false;
+        requested
+    }
+}
+
+/// A captured frame, sent in response to a `ScreenshotRequest`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Screenshot {
+    /// 8-bit RGBA pixels in row-major order.
+    pub data: Vec<u8>,
+    pub dimensions: [u32; 2],
+}
+
+impl Screenshot {
+    pub fn save(&self, path: impl AsRef<Path>) -> Fallible<()> {
+        snapshot::write_png(path, &self.data, self.dimensions)
+    }
+}
+
+/// A frame being copied into a CPU buffer, which can be read once the GPU has finished the frame.
+pub(crate) struct PendingScreenshot {
+    buffer: Arc<CpuAccessibleBuffer<[u8]>>,
+    dimensions: [u32; 2],
+    format: Format,
+}
+
+impl PendingScreenshot {
+    pub(crate) fn new(ctx: &Context, [width, height]: [u32; 2], format: Format) -> Fallible<Self> {
+        let buffer = CpuAccessibleBuffer::from_iter(
+            Arc::clone(ctx.device()),
+            BufferUsage::transfer_destination(),
+            (0..4 * width * height).map(|_| 0),
+        )?;
+        Ok(PendingScreenshot {
+            buffer,
+            dimensions: [width, height],
+            format,
+        })
+    }
+
+    pub(crate) fn buffer(&self) -> &Arc<CpuAccessibleBuffer<[u8]>> {
+        &self.buffer
+    }
+
+    /// Reads back the frame, or returns `None` if the GPU is still using the buffer.
+    pub(crate) fn try_read(&self) -> Option<Screenshot> {
+        let data = self.buffer.read().ok()?;
+        let mut data = data.to_vec();
+        if is_bgra(self.format) {
+            for pixel in data.chunks_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+        Some(Screenshot {
+            data,
+            dimensions: self.dimensions,
+        })
+    }
+}
+
+/// Whether frames of `format` can be captured. Only 8-bit RGBA and BGRA formats are supported.
+pub(crate) fn is_supported(format: Format) -> bool {
+    is_bgra(format) || match format {
+        Format::R8G8B8A8Unorm | Format::R8G8B8A8Srgb => true,
+        _ => false,
+    }
+}
+
+fn is_bgra(format: Format) -> bool {
+    match format {
+        Format::B8G8R8A8Unorm | Format::B8G8R8A8Srgb => true,
+        _ => false,
+    }
+}
@@ -0,0 +1,134 @@
+//! Comparison of rendered images against golden files, for tests.
+//!
+//! Set the `LIME_BLESS` environment variable to overwrite the golden files with the rendered
+//! images instead of comparing them.
+
+use std::env;
+use std::fs::File;
+use std::path::Path;
+
+use failure::{err_msg, Fallible};
+use image::png::{PNGDecoder, PNGEncoder};
+use image::{ColorType, DecodingResult, ImageDecoder};
+
+/// The environment variable which enables bless mode.
+pub const BLESS_VAR: &str = "LIME_BLESS";
+
+/// The default largest difference allowed in any channel of a pixel.
+pub const DEFAULT_TOLERANCE: u8 = 2;
+
+/// The result of comparing two RGBA images of the same size.
+#[derive(Clone, Debug)]
+pub struct Diff {
+    /// The number of pixels with a channel differing by more than the tolerance.
+    pub mismatched: usize,
+    /// The largest difference in any channel of any pixel.
+    pub max_difference: u8,
+    /// The first mismatched pixel, in reading order.
+    pub first: Option<[u32; 2]>,
+    /// An RGBA image with mismatched pixels in red and the rest of the expected image faded.
+    pub image: Vec<u8>,
+}
+
+impl Diff {
+    pub fn is_match(&self) -> bool {
+        self.mismatched == 0
+    }
+}
+
+/// Compares two RGBA images with the given dimensions pixel by pixel.
+pub fn compare(expected: &[u8], actual: &[u8], [width, _]: [u32; 2], tolerance: u8) -> Diff {
+    assert_eq!(expected.len(), actual.len());
+
+    let mut diff = Diff {
+        mismatched: 0,
+        max_difference: 0,
+        first: None,
+        image: Vec::with_capacity(expected.len()),
+    };
+    for (i, (exp, act)) in expected.chunks(4).zip(actual.chunks(4)).enumerate() {
+        let difference = exp
+            .iter()
+            .zip(act)
+            .map(|(&e, &a)| if e > a { e - a } else { a - e })
+            .max()
+            .unwrap_or(0);
+        diff.max_difference = diff.max_difference.max(difference);
+
+        if difference > tolerance {
+            diff.mismatched += 1;
+            if diff.first.is_none() {
+                let i = i as u32;
+                diff.first = Some([i % width, i / width]);
+            }
+            diff.image.extend_from_slice(&[255, 0, 0, 255]);
+        } else {
+            let luma = (u32::from(exp[0]) + u32::from(exp[1]) + u32::from(exp[2])) / 12;
+            diff.image.extend_from_slice(&[luma as u8, luma as u8, luma as u8, 255]);
+        }
+    }
+    diff
+}
+
+/// Compares an RGBA image with `expected.png` in `dir`, panicking if they differ by more than
+/// `tolerance` in any channel of any pixel.
+///
+/// The image is always written to `actual.png`, and on failure the differences are written to
+/// `diff.png`. In bless mode `expected.png` is overwritten instead.
+pub fn assert_matches(dir: impl AsRef<Path>, data: &[u8], dimensions: [u32; 2], tolerance: u8) {
+    let dir = dir.as_ref();
+    write_png(dir.join("actual.png"), data, dimensions).unwrap();
+
+    let expected_path = dir.join("expected.png");
+    if env::var_os(BLESS_VAR).is_some() {
+        write_png(&expected_path, data, dimensions).unwrap();
+        return;
+    }
+
+    let (expected, expected_dims) = read_png(&expected_path).unwrap_or_else(|err| {
+        panic!(
+            "failed to read {}: {} (set {} to create it)",
+            expected_path.display(),
+            err,
+            BLESS_VAR
+        )
+    });
+    assert_eq!(
+        dimensions,
+        expected_dims,
+        "dimensions differ from {}",
+        expected_path.display()
+    );
+
+    let diff = compare(&expected, data, dimensions, tolerance);
+    if !diff.is_match() {
+        let diff_path = dir.join("diff.png");
+        write_png(&diff_path, &diff.image, dimensions).unwrap();
+        panic!(
+            "{} pixels differ from {} by more than {} (largest difference {}, first at {:?}), \
+             see {}",
+            diff.mismatched,
+            expected_path.display(),
+            tolerance,
+            diff.max_difference,
+            diff.first.unwrap(),
+            diff_path.display()
+        );
+    }
+}
+
+/// Reads an 8 bit RGBA PNG file.
+pub fn read_png(path: impl AsRef<Path>) -> Fallible<(Vec<u8>, [u32; 2])> {
+    let mut decoder = PNGDecoder::new(File::open(path)?);
+    let (width, height) = decoder.dimensions()?;
+    match decoder.read_image()? {
+        DecodingResult::U8(data) => Ok((data, [width, height])),
+        _ => Err(err_msg("expected an 8 bit image")),
+    }
+}
+
+/// Writes an 8 bit RGBA PNG file.
+pub fn write_png(path: impl AsRef<Path>, data: &[u8], [width, height]: [u32; 2]) -> Fallible<()> {
+    PNGEncoder::new(File::create(path)?).encode(data, width, height, ColorType::RGBA(8))?;
+    Ok(())
+}
@@ -0,0 +1,323 @@
+mod raster;
+
+use cgmath::{InnerSpace, Matrix, Matrix3, Matrix4, SquareMatrix, Vector3, Vector4};
+use failure::Fallible;
+use shrev::EventChannel;
+use specs::prelude::*;
+
+use self::raster::{interpolate, rasterize, Canvas};
+use d2::{GlyphSection, ImageSection, Section, TriangleSection};
+use d3::{Camera, Light, Material, Mesh};
+use {
+    d2, d3, Color, DpiFactor, OffscreenTarget, RenderConfig, Screenshot, ScreenshotRequest, Texture,
+};
+
+/// A render target drawn to by the CPU, for rendering without a GPU.
+pub struct SoftwareTarget {
+    canvas: Canvas,
+    data: Vec<u8>,
+}
+
+impl SoftwareTarget {
+    pub fn new(dimensions: [u32; 2]) -> Self {
+        SoftwareTarget {
+            canvas: Canvas::new(dimensions),
+            data: Vec::new(),
+        }
+    }
+
+    pub fn resize(&mut self, dimensions: [u32; 2]) {
+        self.canvas = Canvas::new(dimensions);
+    }
+
+    pub fn dimensions(&self) -> [u32; 2] {
+        self.canvas.dimensions()
+    }
+
+    pub fn read<R, T>(&mut self, read: R) -> Fallible<T>
+    where
+        R: FnOnce(&[u8], [u32; 2]) -> Fallible<T>,
+    {
+        self.canvas.read_rgba8(&mut self.data);
+        read(&self.data, self.dimensions())
+    }
+}
+
+pub(crate) struct SoftwareRenderSystem {
+    clear_color: Color,
+}
+
+impl SoftwareRenderSystem {
+    pub const NAME: &'static str = "render::Render";
+
+    pub(crate) fn add(
+        world: &mut World,
+        dispatcher: &mut DispatcherBuilder,
+        dimensions: [u32; 2],
+        config: RenderConfig,
+        d3_sys: &str,
+        d2_sys: &str,
+    ) {
+        world.add_resource(d3::Renderer::software());
+        world.add_resource(d2::Renderer::software());
+        world.add_resource(SoftwareTarget::new(dimensions));
+        world.add_resource(DpiFactor::default());
+        d3::register(world);
+        world.register::<OffscreenTarget>();
+        world.add_resource(Camera::default());
+        world.add_resource(Light::default());
+        world.add_resource(ScreenshotRequest::default());
+        world.add_resource(EventChannel::<Screenshot>::new());
+
+        let sys = SoftwareRenderSystem {
+            clear_color: config.clear_color,
+        };
+        dispatcher.add(sys, Self::NAME, &[d3_sys, d2_sys])
+    }
+}
+
+impl<'a> System<'a> for SoftwareRenderSystem {
+    type SystemData = (
+        ReadExpect<'a, DpiFactor>,
+        ReadExpect<'a, Camera>,
+        ReadExpect<'a, Light>,
+        WriteExpect<'a, SoftwareTarget>,
+        WriteExpect<'a, d3::Renderer>,
+        WriteExpect<'a, d2::Renderer>,
+        WriteExpect<'a, ScreenshotRequest>,
+        WriteExpect<'a, EventChannel<Screenshot>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            dpi_factor,
+            camera,
+            light,
+            mut target,
+            mut d3,
+            mut d2,
+            mut screenshot_request,
+            mut screenshots,
+        ): Self::SystemData,
+    ) {
+        let scale = dpi_factor.get() as f32;
+        let canvas = &mut target.canvas;
+        canvas.clear(self.clear_color.into(), 1.0);
+
+        for (mesh, world, material) in d3.take_meshes() {
+            draw_mesh(canvas, &mesh, &world, &material, &camera, &light);
+        }
+        for section in d2.take_sections() {
+            match section {
+                Section::Triangle(section) => draw_tris(canvas, &section, scale),
+                Section::Image(section) => draw_image(canvas, &section, scale),
+                Section::Glyph(section) => draw_glyphs(canvas, &section, scale),
+            }
+        }
+
+        if screenshot_request.take() {
+            let result = target.read(|data, dimensions| {
+                screenshots.single_write(Screenshot {
+                    data: data.to_vec(),
+                    dimensions,
+                });
+                Ok(())
+            });
+            if let Err(err) = result {
+                warn!("Failed to capture screenshot: {}.", err);
+            }
+        }
+    }
+}
+
+// Matches the shaders in `shader/d3`. Triangles crossing the camera plane are skipped rather
+// than clipped.
+fn draw_mesh(
+    canvas: &mut Canvas,
+    mesh: &Mesh,
+    world: &Matrix4<f32>,
+    material: &Material,
+    camera: &Camera,
+    light: &Light,
+) {
+    let [w, h] = canvas.dimensions();
+    let scissor = canvas.scissor(None, 1.0);
+    let transform = camera.projection(w as f32 / h as f32) * camera.view() * world;
+    let normal_transform =
+        Matrix3::from_cols(world.x.truncate(), world.y.truncate(), world.z.truncate())
+            .invert()
+            .map(|m| m.transpose())
+            .unwrap_or_else(Matrix3::identity);
+    let to_light = -light.direction.normalize();
+    let vertices = &mesh.data.vertices;
+
+    'tris: for tri in mesh.data.indices.chunks(3).filter(|tri| tri.len() == 3) {
+        let mut screen = [[0.0; 2]; 3];
+        let mut attrs = [[0.0; 4]; 3];
+        let mut inv_w = [0.0; 3];
+        for (i, &index) in tri.iter().enumerate() {
+            let vertex = vertices[index as usize];
+            let (pos, normal) = (vertex.position, vertex.normal);
+            let pos = transform * Vector4::new(pos.0, pos.1, pos.2, 1.0);
+            if pos.w <= 0.0 {
+                continue 'tris;
+            }
+            let normal = normal_transform * Vector3::new(normal.0, normal.1, normal.2);
+
+            inv_w[i] = 1.0 / pos.w;
+            screen[i] = [
+                (pos.x * inv_w[i] + 1.0) * 0.5 * w as f32,
+                (pos.y * inv_w[i] + 1.0) * 0.5 * h as f32,
+            ];
+            attrs[i] = [pos.z * inv_w[i], normal.x, normal.y, normal.z];
+        }
+
+        rasterize(screen, scissor, |x, y, bary| {
+            let depth = interpolate(bary, attrs)[0];
+            if !(0.0..=1.0).contains(&depth) {
+                return;
+            }
+
+            // Depth varies linearly in screen space, but other attributes must be corrected
+            // for perspective.
+            let weights = [bary[0] * inv_w[0], bary[1] * inv_w[1], bary[2] * inv_w[2]];
+            let sum = weights[0] + weights[1] + weights[2];
+            let weights = [weights[0] / sum, weights[1] / sum, weights[2] / sum];
+            let [_, nx, ny, nz] = interpolate(weights, attrs);
+
+            let diffuse = Vector3::new(nx, ny, nz).normalize().dot(to_light).max(0.0);
+            let shade = |base: f32, ambient: f32, light: f32| base * (ambient + light * diffuse);
+            let color = Color::rgba(
+                shade(material.color.r, light.ambient.r, light.color.r),
+                shade(material.color.g, light.ambient.g, light.color.g),
+                shade(material.color.b, light.ambient.b, light.color.b),
+                material.color.a,
+            );
+            canvas.write_depth(x, y, depth, color);
+        });
+    }
+}
+
+// Matches the shaders in `shader/d2`, with logical pixels multiplied by `scale`.
+fn draw_tris(canvas: &mut Canvas, section: &TriangleSection, scale: f32) {
+    let scissor = canvas.scissor(section.clip, scale);
+
+    for tri in section.vertices.chunks(3) {
+        let position = |i: usize| [tri[i].position.0 * scale, tri[i].position.1 * scale];
+        let color = |i: usize| {
+            let color = tri[i].color;
+            [color.r, color.g, color.b, color.a]
+        };
+
+        let colors = [color(0), color(1), color(2)];
+        rasterize([position(0), position(1), position(2)], scissor, |x, y, bary| {
+            let [r, g, b, a] = interpolate(bary, colors);
+            canvas.blend(x, y, Color::rgba(r, g, b, a));
+        });
+    }
+}
+
+// Matches the shaders in `shader/d2/image`. Textures are sampled like the GPU sampler, with
+// linear filtering and repeating texture coordinates.
+fn draw_image(canvas: &mut Canvas, section: &ImageSection, scale: f32) {
+    let texture = &section.texture;
+    let data = match texture.data() {
+        Some(data) => data,
+        None => {
+            warn!("Textures rendered on the GPU cannot be drawn by the software renderer.");
+            return;
+        }
+    };
+    if data.is_empty() {
+        return;
+    }
+    let scissor = canvas.scissor(section.clip, scale);
+
+    for tri in section.vertices.chunks(3) {
+        let position = |i: usize| [tri[i].position.0 * scale, tri[i].position.1 * scale];
+        let uv = |i: usize| [tri[i].uv.0, tri[i].uv.1, 0.0, 0.0];
+        let tint = |i: usize| {
+            let tint = tri[i].tint;
+            [tint.r, tint.g, tint.b, tint.a]
+        };
+
+        let (uvs, tints) = ([uv(0), uv(1), uv(2)], [tint(0), tint(1), tint(2)]);
+        rasterize([position(0), position(1), position(2)], scissor, |x, y, bary| {
+            let [u, v, _, _] = interpolate(bary, uvs);
+            let [r, g, b, a] = interpolate(bary, tints);
+            let texel = sample(data, texture, u, v);
+            canvas.blend(x, y, Color::rgba(texel[0] * r, texel[1] * g, texel[2] * b, texel[3] * a));
+        });
+    }
+}
+
+/// Samples the sRGB pixels `data` of `texture` at `(u, v)`, returning a linear color.
+fn sample(data: &[u8], texture: &Texture, u: f32, v: f32) -> [f32; 4] {
+    let [w, h] = texture.dimensions();
+    let wrap = |x: i64, n: u32| ((x % n as i64 + n as i64) % n as i64) as usize;
+    let texel = |x: i64, y: i64| {
+        let i = 4 * (wrap(y, h) * w as usize + wrap(x, w));
+        let channel = |c: u8| c as f32 / 255.0;
+        [
+            srgb_to_linear(channel(data[i])),
+            srgb_to_linear(channel(data[i + 1])),
+            srgb_to_linear(channel(data[i + 2])),
+            channel(data[i + 3]),
+        ]
+    };
+
+    let (x, y) = (u * w as f32 - 0.5, v * h as f32 - 0.5);
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (x0, y0) = (x0 as i64, y0 as i64);
+    let (t00, t10, t01, t11) = (
+        texel(x0, y0),
+        texel(x0 + 1, y0),
+        texel(x0, y0 + 1),
+        texel(x0 + 1, y0 + 1),
+    );
+
+    let mut result = [0.0; 4];
+    for (i, value) in result.iter_mut().enumerate() {
+        let top = t00[i] + (t10[i] - t00[i]) * fx;
+        let bottom = t01[i] + (t11[i] - t01[i]) * fx;
+        *value = top + (bottom - top) * fy;
+    }
+    result
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+// Glyphs are rasterized at their logical size, and each pixel is scaled up by `scale`, like
+// the glyph cache used on the GPU.
+fn draw_glyphs(canvas: &mut Canvas, section: &GlyphSection, scale: f32) {
+    let color = section.color;
+    let scissor = canvas.scissor(None, scale);
+
+    for glyph in &section.glyphs {
+        let bb = match glyph.pixel_bounding_box() {
+            Some(bb) => bb,
+            None => continue,
+        };
+        glyph.draw(|x, y, coverage| {
+            let (x, y) = ((bb.min.x + x as i32) as f32, (bb.min.y + y as i32) as f32);
+            let color = Color::rgba(color.r, color.g, color.b, color.a * coverage);
+            let quad = [
+                [x * scale, y * scale],
+                [(x + 1.0) * scale, y * scale],
+                [x * scale, (y + 1.0) * scale],
+                [(x + 1.0) * scale, (y + 1.0) * scale],
+            ];
+            for &tri in &[[quad[0], quad[1], quad[2]], [quad[2], quad[1], quad[3]]] {
+                rasterize(tri, scissor, |x, y, _| canvas.blend(x, y, color));
+            }
+        });
+    }
+}
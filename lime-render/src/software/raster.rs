@@ -0,0 +1,152 @@
+use d2::Rect;
+use Color;
+
+/// An RGBA color buffer with an accompanying depth buffer, stored as floats.
+pub(in software) struct Canvas {
+    dimensions: [u32; 2],
+    color: Vec<[f32; 4]>,
+    depth: Vec<f32>,
+}
+
+/// The pixels a triangle may cover, as `[left, top, right, bottom]`.
+pub(in software) type Scissor = [u32; 4];
+
+impl Canvas {
+    pub(in software) fn new(dimensions: [u32; 2]) -> Self {
+        let len = (dimensions[0] * dimensions[1]) as usize;
+        Canvas {
+            dimensions,
+            color: vec![[0.0; 4]; len],
+            depth: vec![1.0; len],
+        }
+    }
+
+    pub(in software) fn dimensions(&self) -> [u32; 2] {
+        self.dimensions
+    }
+
+    pub(in software) fn clear(&mut self, color: [f32; 4], depth: f32) {
+        for pixel in &mut self.color {
+            *pixel = color;
+        }
+        for pixel in &mut self.depth {
+            *pixel = depth;
+        }
+    }
+
+    /// The scissor covering `clip`, in logical pixels which are `scale` physical pixels wide, or
+    /// the whole canvas if there is no clip.
+    pub(in software) fn scissor(&self, clip: Option<Rect>, scale: f32) -> Scissor {
+        let [w, h] = self.dimensions;
+        match clip {
+            Some(clip) => {
+                let px = |x: f32, max: u32| ((x * scale).round().max(0.0) as u32).min(max);
+                let (left, top) = (px(clip.left, w), px(clip.top, h));
+                [left, top, px(clip.right, w).max(left), px(clip.bottom, h).max(top)]
+            }
+            None => [0, 0, w, h],
+        }
+    }
+
+    /// Blends `color` over the pixel at `(x, y)`, weighting by its alpha.
+    pub(in software) fn blend(&mut self, x: u32, y: u32, color: Color) {
+        let idx = self.index(x, y);
+        let dst = &mut self.color[idx];
+        let src = [color.r, color.g, color.b, color.a];
+        for (dst, src) in dst.iter_mut().zip(&src) {
+            *dst = src * color.a + *dst * (1.0 - color.a);
+        }
+    }
+
+    /// Writes `color` to the pixel at `(x, y)` if `depth` is less than the stored depth.
+    pub(in software) fn write_depth(&mut self, x: u32, y: u32, depth: f32, color: Color) {
+        let idx = self.index(x, y);
+        if depth < self.depth[idx] {
+            self.depth[idx] = depth;
+            self.color[idx] = [color.r, color.g, color.b, color.a];
+        }
+    }
+
+    /// Converts the color buffer to 8 bit RGBA.
+    pub(in software) fn read_rgba8(&self, data: &mut Vec<u8>) {
+        data.clear();
+        data.extend(
+            self.color
+                .iter()
+                .flat_map(|pixel| pixel.iter())
+                .map(|&c| (c.max(0.0).min(1.0) * 255.0).round() as u8),
+        );
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        (y * self.dimensions[0] + x) as usize
+    }
+}
+
+/// Calls `fragment` with the position and barycentric coordinates of every pixel inside the
+/// triangle `tri`, given in pixels. Pixels are sampled at their centers, and pixels lying
+/// exactly on an edge are only covered by triangles to the right of or below it, so adjacent
+/// triangles never draw the same pixel twice.
+pub(in software) fn rasterize(
+    tri: [[f32; 2]; 3],
+    scissor: Scissor,
+    mut fragment: impl FnMut(u32, u32, [f32; 3]),
+) {
+    let area = edge(tri[0], tri[1], tri[2]);
+    if !area.is_normal() {
+        return;
+    }
+    // Order the vertices so that points inside the triangle have positive edge functions.
+    let (tri, swapped) = if area < 0.0 {
+        ([tri[0], tri[2], tri[1]], true)
+    } else {
+        (tri, false)
+    };
+    let area = area.abs();
+
+    let min = |i: usize| tri[0][i].min(tri[1][i]).min(tri[2][i]);
+    let max = |i: usize| tri[0][i].max(tri[1][i]).max(tri[2][i]);
+    let left = (min(0).floor().max(0.0) as u32).max(scissor[0]);
+    let top = (min(1).floor().max(0.0) as u32).max(scissor[1]);
+    let right = (max(0).ceil().max(0.0) as u32).min(scissor[2]);
+    let bottom = (max(1).ceil().max(0.0) as u32).min(scissor[3]);
+
+    let edges = [(tri[1], tri[2]), (tri[2], tri[0]), (tri[0], tri[1])];
+    for y in top..bottom {
+        for x in left..right {
+            let p = [x as f32 + 0.5, y as f32 + 0.5];
+            let mut bary = [0.0; 3];
+            let inside = edges.iter().enumerate().all(|(i, &(a, b))| {
+                let w = edge(a, b, p);
+                bary[i] = w / area;
+                w > 0.0 || (w >= 0.0 && is_top_left(a, b))
+            });
+            if inside {
+                if swapped {
+                    bary.swap(1, 2);
+                }
+                fragment(x, y, bary);
+            }
+        }
+    }
+}
+
+/// Interpolates a value given at the vertices of a triangle.
+pub(in software) fn interpolate(bary: [f32; 3], values: [[f32; 4]; 3]) -> [f32; 4] {
+    let mut result = [0.0; 4];
+    for (i, value) in result.iter_mut().enumerate() {
+        *value = bary[0] * values[0][i] + bary[1] * values[1][i] + bary[2] * values[2][i];
+    }
+    result
+}
+
+fn edge(a: [f32; 2], b: [f32; 2], p: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+// With y pointing down, a top edge is horizontal and runs to the right, and a left edge runs
+// upwards.
+fn is_top_left(a: [f32; 2], b: [f32; 2]) -> bool {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    dy < 0.0 || (dy <= 0.0 && dx > 0.0)
+}
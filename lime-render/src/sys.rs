@@ -6,6 +6,7 @@ use shrev::{EventChannel, ReaderId};
 use specs::prelude::*;
 use utils::throw;
 use vulkano::command_buffer::{AutoCommandBuffer, AutoCommandBufferBuilder, DynamicState};
+use vulkano::format::ClearValue;
 use vulkano::framebuffer::Subpass;
 use vulkano::instance::{Instance, PhysicalDevice};
 use vulkano::pipeline::viewport::Viewport;
@@ -13,13 +14,22 @@ use vulkano::sync::GpuFuture;
 use vulkano_win;
 use winit::{self, WindowEvent};
 
-use {d2, d3, Context, Target};
+use screenshot::{self, PendingScreenshot};
+use {
+    d2, d3, target, Context, DpiFactor, OffscreenTarget, RenderConfig, Screenshot,
+    ScreenshotRequest, Target,
+};
 
 pub(crate) struct RenderSystem<T> {
     prev_frame: Option<Box<GpuFuture + Send + Sync>>,
     swapchain_dirty: bool,
     event_rx: ReaderId<winit::Event>,
     state: DynamicState,
+    clear_values: Vec<ClearValue>,
+    // Whether a screenshot has been requested but not yet recorded.
+    capture: bool,
+    // Frames being copied back for screenshots, in the order they were rendered.
+    screenshots: Vec<PendingScreenshot>,
     _target: PhantomData<T>,
 }
 
@@ -33,6 +43,7 @@ where
         world: &mut World,
         dispatcher: &mut DispatcherBuilder,
         data: T::InitData,
+        config: RenderConfig,
         d3_sys: &str,
         d2_sys: &str,
     ) {
@@ -46,7 +57,8 @@ where
             .expect("no device available");
         info!("Using device: {} (type: {:?}).", phys.name(), phys.ty());
 
-        let (target, ctx) = T::new(phys, data).unwrap_or_else(throw);
+        let config = config.supported_by(phys);
+        let (target, ctx) = T::new(phys, data, &config).unwrap_or_else(throw);
 
         let event_rx = world
             .write_resource::<EventChannel<winit::Event>>()
@@ -71,6 +83,13 @@ where
             ctx.device(),
             Subpass::from(Arc::clone(target.render_pass()), 1).unwrap(),
         ));
+        world.add_resource(DpiFactor::new(f64::from(target.hidpi_factor())));
+        d3::register(world);
+        world.register::<OffscreenTarget>();
+        world.add_resource(d3::Camera::default());
+        world.add_resource(d3::Light::default());
+        world.add_resource(ScreenshotRequest::default());
+        world.add_resource(EventChannel::<Screenshot>::new());
         world.add_resource(ctx);
         world.add_resource(target);
 
@@ -80,6 +99,9 @@ where
                 swapchain_dirty: false,
                 event_rx,
                 state,
+                clear_values: target::clear_values(&config),
+                capture: false,
+                screenshots: Vec::new(),
                 _target: PhantomData::<T>,
             },
             Self::NAME,
@@ -93,6 +115,7 @@ where
         target: &mut T,
         d3: &mut d3::Renderer,
         d2: &mut d2::Renderer,
+        offscreen: &mut WriteStorage<OffscreenTarget>,
     ) {
         for _ in 0..5 {
             if self.swapchain_dirty {
@@ -107,7 +130,7 @@ where
                     }
                 }
             } else {
-                match self.try_render(ctx, target, d3, d2) {
+                match self.try_render(ctx, target, d3, d2, offscreen) {
                     Ok(()) => {
                         trace!("Draw succeeded.");
                         break;
@@ -134,6 +157,7 @@ where
         target: &mut T,
         d3: &mut d3::Renderer,
         d2: &mut d2::Renderer,
+        offscreen: &mut WriteStorage<OffscreenTarget>,
     ) -> Fallible<()> {
         let (fb, acquire) = target.acquire(ctx)?;
 
@@ -141,19 +165,38 @@ where
             last_frame.cleanup_finished();
         }
 
-        let command_buffer = AutoCommandBufferBuilder::primary_one_time_submit(
+        let mut command_buffer = AutoCommandBufferBuilder::primary_one_time_submit(
             Arc::clone(ctx.device()),
             ctx.graphics_queue().family(),
-        )?.begin_render_pass(
-            fb,
-            false,
-            vec![[0.0, 0.0, 0.0, 1.0].into(), 1f32.into()],
         )?;
+        // Offscreen targets are rendered first, so the main target can draw their images.
+        for offscreen in (&mut *offscreen).join() {
+            command_buffer = offscreen.commit(command_buffer)?;
+        }
+        let command_buffer =
+            command_buffer.begin_render_pass(fb, false, self.clear_values.clone())?;
         let command_buffer = d3.commit(command_buffer, &self.state)?.next_subpass(false)?;
-        let command_buffer = d2
+        let mut command_buffer = d2
             .commit(command_buffer, &self.state, target.logical_size())?
-            .end_render_pass()?
-            .build()?;
+            .end_render_pass()?;
+
+        let mut pending = None;
+        if self.capture {
+            let format = target.format();
+            if !target.can_copy_frame() {
+                warn!("Cannot capture frames, the target does not support transfers.");
+                self.capture = false;
+            } else if screenshot::is_supported(format) {
+                let screenshot = PendingScreenshot::new(ctx, target.dimensions(), format)?;
+                let buffer = Arc::clone(screenshot.buffer());
+                command_buffer = target.copy_frame(command_buffer, buffer)?;
+                pending = Some(screenshot);
+            } else {
+                warn!("Cannot capture frames with format {:?}.", format);
+                self.capture = false;
+            }
+        }
+        let command_buffer = command_buffer.build()?;
 
         self.prev_frame = Some(match self.prev_frame.take() {
             Some(last_frame) => {
@@ -161,6 +204,10 @@ where
             }
             None => self.execute(ctx, target, acquire, command_buffer)?,
         });
+        if let Some(pending) = pending {
+            self.screenshots.push(pending);
+            self.capture = false;
+        }
         Ok(())
     }
 
@@ -187,22 +234,57 @@ where
     type SystemData = (
         ReadExpect<'a, EventChannel<winit::Event>>,
         ReadExpect<'a, Context>,
+        WriteExpect<'a, DpiFactor>,
+        ReadExpect<'a, d3::Camera>,
+        ReadExpect<'a, d3::Light>,
         WriteExpect<'a, T>,
         WriteExpect<'a, d3::Renderer>,
         WriteExpect<'a, d2::Renderer>,
+        WriteStorage<'a, OffscreenTarget>,
+        WriteExpect<'a, ScreenshotRequest>,
+        WriteExpect<'a, EventChannel<Screenshot>>,
     );
 
-    fn run(&mut self, (event_tx, ctx, mut target, mut d3, mut d2): Self::SystemData) {
+    fn run(
+        &mut self,
+        (
+            event_tx,
+            ctx,
+            mut dpi_factor,
+            camera,
+            light,
+            mut target,
+            mut d3,
+            mut d2,
+            mut offscreen,
+            mut screenshot_request,
+            mut screenshots,
+        ): Self::SystemData,
+    ) {
         for event in event_tx.read(&mut self.event_rx) {
             if let winit::Event::WindowEvent {
-                event: WindowEvent::HiDpiFactorChanged(_factor),
+                event: WindowEvent::HiDpiFactorChanged(factor),
                 ..
-            } = event
+            } = *event
             {
-                //                self.target.set_hidpi_factor(factor)
+                trace!("DPI factor changed to {}.", factor);
+                *dpi_factor = DpiFactor::new(factor);
+                // The physical size of the window changes with its DPI factor.
+                self.swapchain_dirty = true;
             }
         }
 
-        self.render(&ctx, &mut target, &mut d3, &mut d2);
+        // The frames copied for screenshots can be read once the GPU has finished with them.
+        // Frames finish in order, so stop at the first one still in use.
+        while let Some(captured) = self.screenshots.first().and_then(PendingScreenshot::try_read) {
+            screenshots.single_write(captured);
+            self.screenshots.remove(0);
+        }
+        self.capture |= screenshot_request.take();
+
+        target.set_hidpi_factor(dpi_factor.get() as f32);
+        d3.camera = *camera;
+        d3.light = *light;
+        self.render(&ctx, &mut target, &mut d3, &mut d2, &mut offscreen);
     }
 }
@@ -0,0 +1,229 @@
+mod image;
+mod offscreen;
+mod swapchain;
+
+pub use self::image::ImageTarget;
+pub use self::offscreen::OffscreenTarget;
+pub use self::swapchain::SwapchainTarget;
+
+use std::sync::Arc;
+
+use failure::Fallible;
+use vulkano::buffer::CpuAccessibleBuffer;
+use vulkano::command_buffer::AutoCommandBufferBuilder;
+use vulkano::device::Device;
+use vulkano::format::{ClearValue, Format};
+use vulkano::framebuffer::{
+    Framebuffer, FramebufferAbstract, FramebufferCreationError, RenderPassAbstract,
+};
+use vulkano::image::{AttachmentImage, ImageViewAccess};
+use vulkano::instance::PhysicalDevice;
+use vulkano::sync::GpuFuture;
+
+use {Context, RenderConfig};
+
+pub trait Target: Sized + Send + Sync + 'static {
+    type InitData;
+
+    fn new(
+        phys: PhysicalDevice,
+        data: Self::InitData,
+        config: &RenderConfig,
+    ) -> Fallible<(Self, Context)>;
+
+    fn render_pass(&self) -> &Arc<RenderPassAbstract + Send + Sync>;
+    fn format(&self) -> Format;
+    fn dimensions(&self) -> [u32; 2];
+    fn hidpi_factor(&self) -> f32;
+    fn set_hidpi_factor(&mut self, factor: f32);
+
+    fn logical_size(&self) -> [f32; 2] {
+        let [w, h] = self.dimensions();
+        let f = self.hidpi_factor();
+        [w as f32 / f, h as f32 / f]
+    }
+
+    fn recreate(&mut self, ctx: &Context) -> Fallible<()>;
+
+    fn acquire(
+        &mut self,
+        ctx: &Context,
+    ) -> Fallible<(
+        Arc<FramebufferAbstract + Send + Sync>,
+        Box<GpuFuture + Send + Sync>,
+    )>;
+    /// Whether `copy_frame` can be used. This requires the target's images to support being a
+    /// transfer source.
+    fn can_copy_frame(&self) -> bool {
+        true
+    }
+    /// Records a copy of the image acquired for the current frame into `buffer`, which holds
+    /// four bytes per pixel.
+    fn copy_frame(
+        &self,
+        cmd: AutoCommandBufferBuilder,
+        buffer: Arc<CpuAccessibleBuffer<[u8]>>,
+    ) -> Fallible<AutoCommandBufferBuilder>;
+
+    fn present<F>(&mut self, ctx: &Context, fut: F) -> Fallible<Box<GpuFuture + Send + Sync>>
+    where
+        F: GpuFuture + Send + Sync + 'static;
+}
+
+// The sample count of `config` must already have been checked with
+// `RenderConfig::supported_by`, so the attachments and clear values match this render pass.
+fn create_render_pass(
+    device: Arc<Device>,
+    format: Format,
+    config: &RenderConfig,
+) -> Fallible<Arc<RenderPassAbstract + Send + Sync>> {
+    if !config.is_multisampled() {
+        return Ok(Arc::new(ordered_passes_renderpass!(device,
+                attachments: {
+                    color: {
+                        load: Clear,
+                        store: Store,
+                        format: format,
+                        samples: 1,
+                    },
+                    depth: {
+                        load: Clear,
+                        store: DontCare,
+                        format: config.depth_format,
+                        samples: 1,
+                    }
+                },
+                passes: [
+                    {
+                        color: [color],
+                        depth_stencil: {depth},
+                        input: []
+                    },
+                    {
+                        color: [color],
+                        depth_stencil: { },
+                        input: []
+                    }
+                ]
+        )?));
+    }
+
+    // Both subpasses draw to a multisampled image, which is resolved into the target image at
+    // the end of the second.
+    Ok(Arc::new(ordered_passes_renderpass!(device,
+            attachments: {
+                color: {
+                    load: DontCare,
+                    store: Store,
+                    format: format,
+                    samples: 1,
+                },
+                depth: {
+                    load: Clear,
+                    store: DontCare,
+                    format: config.depth_format,
+                    samples: config.samples,
+                },
+                multisampled: {
+                    load: Clear,
+                    store: DontCare,
+                    format: format,
+                    samples: config.samples,
+                }
+            },
+            passes: [
+                {
+                    color: [multisampled],
+                    depth_stencil: {depth},
+                    input: []
+                },
+                {
+                    color: [multisampled],
+                    depth_stencil: { },
+                    input: [],
+                    resolve: [color]
+                }
+            ]
+    )?))
+}
+
+/// The images used by a framebuffer besides the one being rendered to.
+struct Attachments {
+    depth: Arc<AttachmentImage>,
+    multisampled: Option<Arc<AttachmentImage>>,
+}
+
+impl Attachments {
+    fn new(
+        ctx: &Context,
+        dimensions: [u32; 2],
+        format: Format,
+        config: &RenderConfig,
+    ) -> Fallible<Self> {
+        let device = ctx.device();
+        if config.is_multisampled() {
+            Ok(Attachments {
+                depth: AttachmentImage::transient_multisampled(
+                    Arc::clone(device),
+                    dimensions,
+                    config.samples,
+                    config.depth_format,
+                )?,
+                multisampled: Some(AttachmentImage::transient_multisampled(
+                    Arc::clone(device),
+                    dimensions,
+                    config.samples,
+                    format,
+                )?),
+            })
+        } else {
+            Ok(Attachments {
+                depth: AttachmentImage::transient(
+                    Arc::clone(device),
+                    dimensions,
+                    config.depth_format,
+                )?,
+                multisampled: None,
+            })
+        }
+    }
+}
+
+/// The values the attachments of the render pass are cleared to at the start of each frame.
+pub(crate) fn clear_values(config: &RenderConfig) -> Vec<ClearValue> {
+    let color = ClearValue::from(<[f32; 4]>::from(config.clear_color));
+    if config.is_multisampled() {
+        vec![ClearValue::None, 1f32.into(), color]
+    } else {
+        vec![color, 1f32.into()]
+    }
+}
+
+fn create_framebuffers<I: ImageViewAccess + Send + Sync + 'static>(
+    pass: &Arc<RenderPassAbstract + Send + Sync>,
+    images: impl IntoIterator<Item = Arc<I>>,
+    attachments: &Attachments,
+) -> Result<Vec<Arc<FramebufferAbstract + Send + Sync>>, FramebufferCreationError> {
+    images
+        .into_iter()
+        .map(|img| create_framebuffer(Arc::clone(pass), img, attachments))
+        .collect()
+}
+
+fn create_framebuffer<I: ImageViewAccess + Send + Sync + 'static>(
+    pass: Arc<RenderPassAbstract + Send + Sync>,
+    img: Arc<I>,
+    attachments: &Attachments,
+) -> Result<Arc<FramebufferAbstract + Send + Sync>, FramebufferCreationError> {
+    let depth = Arc::clone(&attachments.depth);
+    Ok(match attachments.multisampled {
+        Some(ref multisampled) => Arc::new(
+            Framebuffer::start(pass)
+                .add(img)?
+                .add(depth)?
+                .add(Arc::clone(multisampled))?
+                .build()?,
+        ),
+        None => Arc::new(Framebuffer::start(pass).add(img)?.add(depth)?.build()?),
+    })
+}
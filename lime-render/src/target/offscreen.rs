@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use failure::Fallible;
+use specs::prelude::*;
+use vulkano::command_buffer::{AutoCommandBufferBuilder, DynamicState};
+use vulkano::format::Format;
+use vulkano::framebuffer::{FramebufferAbstract, RenderPassAbstract, Subpass};
+use vulkano::image::AttachmentImage;
+use vulkano::pipeline::viewport::Viewport;
+
+use d3::{Camera, Light};
+use target::{clear_values, create_framebuffer, create_render_pass, Attachments};
+use {d2, d3, Color, Context, RenderConfig, Texture};
+
+const FORMAT: Format = Format::R8G8B8A8Srgb;
+
+/// A component holding an image which is rendered to each frame, before the main target. The
+/// result can be drawn by the main `d2::Renderer` with `texture`.
+///
+/// Each offscreen target has its own `d3` and `d2` renderers, camera and light, which are drawn
+/// to like those of the main target. Systems drawing to an offscreen target should run before
+/// the render system. Offscreen targets are not drawn by the software renderer.
+pub struct OffscreenTarget {
+    pub camera: Camera,
+    pub light: Light,
+    config: RenderConfig,
+    render_pass: Arc<RenderPassAbstract + Send + Sync>,
+    framebuffer: Arc<FramebufferAbstract + Send + Sync>,
+    image: Arc<AttachmentImage>,
+    state: DynamicState,
+    d3: d3::Renderer,
+    d2: d2::Renderer,
+}
+
+impl OffscreenTarget {
+    /// Creates an offscreen target of size `dimensions`, in pixels. The sample count and depth
+    /// format of `config` are used. Pixels not drawn to are transparent unless
+    /// `set_clear_color` is called.
+    pub fn new(ctx: &Context, dimensions: [u32; 2], config: &RenderConfig) -> Fallible<Self> {
+        let config = RenderConfig {
+            clear_color: Color::rgba(0.0, 0.0, 0.0, 0.0),
+            ..config.supported_by(ctx.device().physical_device())
+        };
+        let render_pass = create_render_pass(Arc::clone(ctx.device()), FORMAT, &config)?;
+        let (image, framebuffer) = create(ctx, &render_pass, dimensions, &config)?;
+
+        let d3 = d3::Renderer::new(
+            ctx.device(),
+            Subpass::from(Arc::clone(&render_pass), 0).unwrap(),
+        );
+        let d2 = d2::Renderer::new(
+            ctx.device(),
+            Subpass::from(Arc::clone(&render_pass), 1).unwrap(),
+        );
+
+        Ok(OffscreenTarget {
+            camera: Camera::default(),
+            light: Light::default(),
+            config,
+            render_pass,
+            framebuffer,
+            image,
+            state: DynamicState {
+                line_width: None,
+                viewports: Some(vec![viewport(dimensions)]),
+                scissors: None,
+            },
+            d3,
+            d2,
+        })
+    }
+
+    pub fn resize(&mut self, ctx: &Context, dimensions: [u32; 2]) -> Fallible<()> {
+        let (image, framebuffer) = create(ctx, &self.render_pass, dimensions, &self.config)?;
+        self.image = image;
+        self.framebuffer = framebuffer;
+        self.state.viewports = Some(vec![viewport(dimensions)]);
+        Ok(())
+    }
+
+    pub fn set_clear_color(&mut self, color: Color) {
+        self.config.clear_color = color;
+    }
+
+    pub fn dimensions(&self) -> [u32; 2] {
+        [self.framebuffer.width(), self.framebuffer.height()]
+    }
+
+    /// The image rendered to, for drawing with `d2::Renderer::draw_image`. The texture refers to
+    /// the current image, so it should be fetched again after `resize`.
+    pub fn texture(&self) -> Texture {
+        Texture::new(Arc::clone(&self.image), self.dimensions())
+    }
+
+    pub fn d3(&mut self) -> &mut d3::Renderer {
+        &mut self.d3
+    }
+
+    /// The 2D renderer of this target. Fonts must be added to it separately from the main
+    /// renderer. Coordinates are in pixels.
+    pub fn d2(&mut self) -> &mut d2::Renderer {
+        &mut self.d2
+    }
+
+    /// Records the commands to render this target into `cmd`.
+    pub(crate) fn commit(
+        &mut self,
+        cmd: AutoCommandBufferBuilder,
+    ) -> Fallible<AutoCommandBufferBuilder> {
+        let [w, h] = self.dimensions();
+        self.d3.camera = self.camera;
+        self.d3.light = self.light;
+
+        let cmd = cmd.begin_render_pass(
+            Arc::clone(&self.framebuffer),
+            false,
+            clear_values(&self.config),
+        )?;
+        let cmd = self.d3.commit(cmd, &self.state)?.next_subpass(false)?;
+        let cmd = self
+            .d2
+            .commit(cmd, &self.state, [w as f32, h as f32])?
+            .end_render_pass()?;
+        Ok(cmd)
+    }
+}
+
+impl Component for OffscreenTarget {
+    type Storage = HashMapStorage<Self>;
+}
+
+fn create(
+    ctx: &Context,
+    render_pass: &Arc<RenderPassAbstract + Send + Sync>,
+    dimensions: [u32; 2],
+    config: &RenderConfig,
+) -> Fallible<(Arc<AttachmentImage>, Arc<FramebufferAbstract + Send + Sync>)> {
+    let image = AttachmentImage::sampled(Arc::clone(ctx.device()), dimensions, FORMAT)?;
+    let attachments = Attachments::new(ctx, dimensions, FORMAT, config)?;
+    let framebuffer =
+        create_framebuffer(Arc::clone(render_pass), Arc::clone(&image), &attachments)?;
+    Ok((image, framebuffer))
+}
+
+fn viewport([w, h]: [u32; 2]) -> Viewport {
+    Viewport {
+        origin: [0.0, 0.0],
+        dimensions: [w as f32, h as f32],
+        depth_range: 0.0..1.0,
+    }
+}
@@ -0,0 +1,194 @@
+use std::sync::Arc;
+
+use failure::Fallible;
+use vulkano::buffer::CpuAccessibleBuffer;
+use vulkano::command_buffer::AutoCommandBufferBuilder;
+use vulkano::format::Format;
+use vulkano::framebuffer::{FramebufferAbstract, RenderPassAbstract};
+use vulkano::image::SwapchainImage;
+use vulkano::instance::{DeviceExtensions, PhysicalDevice};
+use vulkano::swapchain::{self, PresentMode, Surface, SurfaceTransform, Swapchain};
+use vulkano::sync::GpuFuture;
+use vulkano_win;
+use winit::Window;
+
+use target::{create_framebuffers, create_render_pass, Attachments, Target};
+use {Context, RenderConfig};
+
+pub struct SwapchainTarget {
+    surface: Arc<Surface<Window>>,
+    swapchain: Arc<Swapchain<Window>>,
+    images: Vec<Arc<SwapchainImage<Window>>>,
+    render_pass: Arc<RenderPassAbstract + Send + Sync>,
+    format: Format,
+    config: RenderConfig,
+    framebuffers: Vec<Arc<FramebufferAbstract + Send + Sync>>,
+    index: Option<usize>,
+    dimensions: [u32; 2],
+    hidpi_factor: f32,
+    transfer_source: bool,
+}
+
+impl SwapchainTarget {
+    pub fn window(&self) -> &Window {
+        self.surface.window()
+    }
+}
+
+impl Target for SwapchainTarget {
+    type InitData = Window;
+
+    fn new(
+        phys: PhysicalDevice,
+        window: Self::InitData,
+        config: &RenderConfig,
+    ) -> Fallible<(Self, Context)> {
+        let surface = vulkano_win::create_vk_surface(window, Arc::clone(phys.instance()))?;
+
+        let ctx = Context::new(
+            phys,
+            |fam| surface.is_supported(fam).unwrap_or(false),
+            &DeviceExtensions {
+                khr_swapchain: true,
+                ..DeviceExtensions::none()
+            },
+        )?;
+
+        let dpi_factor = surface.window().get_hidpi_factor();
+        let logical_size = surface.window().get_inner_size().unwrap();
+        let (w, h) = logical_size.to_physical(dpi_factor).into();
+
+        let caps = surface.capabilities(ctx.device().physical_device())?;
+        let &(format, _) = caps
+            .supported_formats
+            .first()
+            .expect("surface has no supported formats");
+
+        let render_pass = create_render_pass(Arc::clone(ctx.device()), format, config)?;
+
+        let caps = surface.capabilities(phys)?;
+        let alpha = caps
+            .supported_composite_alpha
+            .iter()
+            .next()
+            .expect("surface has no supported alpha modes");
+        let present_mode = if caps.present_modes.supports(config.present_mode) {
+            config.present_mode
+        } else {
+            warn!(
+                "Present mode {:?} not supported, falling back to {:?}.",
+                config.present_mode,
+                PresentMode::Fifo
+            );
+            PresentMode::Fifo
+        };
+
+        let (swapchain, images) = Swapchain::new(
+            Arc::clone(ctx.device()),
+            Arc::clone(&surface),
+            caps.min_image_count,
+            format,
+            [w, h],
+            1,
+            caps.supported_usage_flags,
+            ctx.graphics_queue(),
+            SurfaceTransform::Identity,
+            alpha,
+            present_mode,
+            true,
+            None,
+        )?;
+
+        let attachments = Attachments::new(&ctx, [w, h], format, config)?;
+        let framebuffers = create_framebuffers(&render_pass, images.iter().cloned(), &attachments)?;
+
+        Ok((
+            SwapchainTarget {
+                surface,
+                swapchain,
+                images,
+                render_pass,
+                format,
+                config: config.clone(),
+                framebuffers,
+                index: None,
+                dimensions: [w, h],
+                hidpi_factor: dpi_factor as f32,
+                transfer_source: caps.supported_usage_flags.transfer_source,
+            },
+            ctx,
+        ))
+    }
+
+    fn render_pass(&self) -> &Arc<RenderPassAbstract + Send + Sync> {
+        &self.render_pass
+    }
+
+    fn format(&self) -> Format {
+        self.format
+    }
+
+    fn dimensions(&self) -> [u32; 2] {
+        self.dimensions
+    }
+
+    fn hidpi_factor(&self) -> f32 {
+        self.hidpi_factor
+    }
+
+    fn set_hidpi_factor(&mut self, factor: f32) {
+        self.hidpi_factor = factor;
+    }
+
+    fn recreate(&mut self, ctx: &Context) -> Fallible<()> {
+        self.dimensions = self
+            .surface
+            .capabilities(ctx.device().physical_device())?
+            .current_extent
+            .unwrap();
+
+        let (swapchain, images) = self.swapchain.recreate_with_dimension(self.dimensions)?;
+        self.swapchain = swapchain;
+        self.images = images;
+        let attachments = Attachments::new(ctx, self.dimensions, self.format, &self.config)?;
+        self.framebuffers =
+            create_framebuffers(&self.render_pass, self.images.iter().cloned(), &attachments)?;
+        Ok(())
+    }
+
+    fn acquire(
+        &mut self,
+        _: &Context,
+    ) -> Fallible<(
+        Arc<FramebufferAbstract + Send + Sync>,
+        Box<GpuFuture + Send + Sync>,
+    )> {
+        let (index, acquire) = swapchain::acquire_next_image(self.swapchain.clone(), None)?;
+        self.index = Some(index);
+        Ok((Arc::clone(&self.framebuffers[index]), Box::new(acquire)))
+    }
+
+    fn can_copy_frame(&self) -> bool {
+        self.transfer_source
+    }
+
+    fn copy_frame(
+        &self,
+        cmd: AutoCommandBufferBuilder,
+        buffer: Arc<CpuAccessibleBuffer<[u8]>>,
+    ) -> Fallible<AutoCommandBufferBuilder> {
+        let index = self.index.expect("swapchain image not acquired");
+        Ok(cmd.copy_image_to_buffer(Arc::clone(&self.images[index]), buffer)?)
+    }
+
+    fn present<F>(&mut self, ctx: &Context, fut: F) -> Fallible<Box<GpuFuture + Send + Sync>>
+    where
+        F: GpuFuture + Send + Sync + 'static,
+    {
+        Ok(Box::new(fut.then_swapchain_present(
+            Arc::clone(ctx.graphics_queue()),
+            Arc::clone(&self.swapchain),
+            self.index.take().expect("swapchain image not acquired"),
+        )))
+    }
+}
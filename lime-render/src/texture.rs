@@ -0,0 +1,142 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use failure::{err_msg, Fallible};
+use vulkano::image::ImageViewAccess;
+use vulkano::sync::{FenceSignalFuture, FlushError, GpuFuture};
+
+/// An RGBA image on the GPU, created with `Context::create_texture` or rendered by an
+/// `OffscreenTarget`. Cloning a texture is cheap.
+///
+/// With the `software` feature, textures created from pixel data also keep a copy in memory for
+/// the software renderer, and can be created without a GPU using `Texture::from_rgba8`.
+#[derive(Clone)]
+pub struct Texture {
+    image: Option<Arc<ImageViewAccess + Send + Sync>>,
+    #[cfg(feature = "software")]
+    data: Option<Arc<Vec<u8>>>,
+    dimensions: [u32; 2],
+}
+
+impl Texture {
+    pub(crate) fn new(image: Arc<ImageViewAccess + Send + Sync>, dimensions: [u32; 2]) -> Self {
+        Texture {
+            image: Some(image),
+            #[cfg(feature = "software")]
+            data: None,
+            dimensions,
+        }
+    }
+
+    /// Creates a texture held only in memory, which can be drawn by the software renderer but
+    /// not on the GPU. `data` holds 8-bit RGBA pixels in row-major order, in the sRGB color
+    /// space.
+    #[cfg(feature = "software")]
+    pub fn from_rgba8(data: Vec<u8>, dimensions: [u32; 2]) -> Fallible<Self> {
+        check_len(&data, dimensions)?;
+        Ok(Texture {
+            image: None,
+            data: Some(Arc::new(data)),
+            dimensions,
+        })
+    }
+
+    #[cfg(feature = "software")]
+    pub(crate) fn with_data(mut self, data: &[u8]) -> Self {
+        self.data = Some(Arc::new(data.to_vec()));
+        self
+    }
+
+    /// The image on the GPU, or `None` if the texture was created with `from_rgba8`.
+    pub(crate) fn image(&self) -> Option<&Arc<ImageViewAccess + Send + Sync>> {
+        self.image.as_ref()
+    }
+
+    /// The pixels of the texture, or `None` if it was rendered on the GPU.
+    #[cfg(feature = "software")]
+    pub(crate) fn data(&self) -> Option<&[u8]> {
+        self.data.as_ref().map(|data| data.as_slice())
+    }
+
+    pub fn dimensions(&self) -> [u32; 2] {
+        self.dimensions
+    }
+}
+
+impl PartialEq for Texture {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.image, &other.image) {
+            (&Some(ref a), &Some(ref b)) => Arc::ptr_eq(a, b),
+            #[cfg(feature = "software")]
+            (&None, &None) => match (&self.data, &other.data) {
+                (&Some(ref a), &Some(ref b)) => Arc::ptr_eq(a, b),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Debug for Texture {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Texture")
+            .field("dimensions", &self.dimensions)
+            .finish()
+    }
+}
+
+/// A texture which is still being uploaded to the GPU, created with
+/// `Context::begin_create_texture` or `Context::begin_load_texture`.
+pub struct PendingTexture {
+    texture: Texture,
+    fence: FenceSignalFuture<Box<GpuFuture + Send + Sync>>,
+}
+
+impl PendingTexture {
+    pub(crate) fn new(
+        texture: Texture,
+        fence: FenceSignalFuture<Box<GpuFuture + Send + Sync>>,
+    ) -> Self {
+        PendingTexture { texture, fence }
+    }
+
+    /// Whether the upload has finished, without blocking.
+    pub fn is_ready(&self) -> Fallible<bool> {
+        match self.fence.wait(Some(Duration::from_secs(0))) {
+            Ok(()) => Ok(true),
+            Err(FlushError::Timeout) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Blocks until the upload has finished and returns the texture.
+    pub fn wait(self) -> Fallible<Texture> {
+        self.fence.wait(None)?;
+        Ok(self.texture)
+    }
+}
+
+impl fmt::Debug for PendingTexture {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PendingTexture")
+            .field("texture", &self.texture)
+            .finish()
+    }
+}
+
+/// Checks that `data` holds 8-bit RGBA pixels for an image of size `dimensions`.
+pub(crate) fn check_len(data: &[u8], dimensions: [u32; 2]) -> Fallible<()> {
+    let [width, height] = dimensions;
+    let expected = width as usize * height as usize * 4;
+    if data.len() != expected {
+        return Err(err_msg(format!(
+            "texture data has length {}, expected {} for dimensions {}x{}",
+            data.len(),
+            expected,
+            width,
+            height
+        )));
+    }
+    Ok(())
+}
@@ -1,9 +1,13 @@
-use std::fs::File;
+// Each test binary only uses some of these helpers.
+#![allow(dead_code)]
+
 use std::path::{Path, PathBuf};
 
-use image::png::{PNGDecoder, PNGEncoder};
-use image::{ColorType, DecodingResult, ImageDecoder};
-use render::{self, ImageTarget};
+use render::{self, snapshot, RenderConfig, Texture};
+#[cfg(not(feature = "software"))]
+use render::ImageTarget as Target;
+#[cfg(feature = "software")]
+use render::SoftwareTarget as Target;
 use shrev::EventChannel;
 use specs::prelude::*;
 use {env_logger, winit};
@@ -13,6 +17,38 @@ where
     P: AsRef<Path>,
     D3: for<'a> System<'a> + Send,
     D2: for<'a> System<'a> + Send,
+{
+    test_with(dir, d3, d2, dimensions, |_| ())
+}
+
+/// Like `test`, but calls `setup` with the world after the renderer has been initialized.
+pub fn test_with<P, D3, D2>(
+    dir: P,
+    d3: D3,
+    d2: D2,
+    dimensions: [u32; 2],
+    setup: impl FnOnce(&mut World),
+) where
+    P: AsRef<Path>,
+    D3: for<'a> System<'a> + Send,
+    D2: for<'a> System<'a> + Send,
+{
+    let data = render_frame(d3, d2, dimensions, RenderConfig::default(), setup);
+    snapshot::assert_matches(test_data_path(dir), &data, dimensions, snapshot::DEFAULT_TOLERANCE);
+}
+
+/// Renders a single frame and returns the RGBA pixels of the target, for tests which check the
+/// image directly rather than comparing it to a golden file.
+pub fn render_frame<D3, D2>(
+    d3: D3,
+    d2: D2,
+    dimensions: [u32; 2],
+    config: RenderConfig,
+    setup: impl FnOnce(&mut World),
+) -> Vec<u8>
+where
+    D3: for<'a> System<'a> + Send,
+    D2: for<'a> System<'a> + Send,
 {
     env_logger::try_init().ok();
 
@@ -21,41 +57,36 @@ where
         .with(d3, "D3", &[])
         .with(d2, "D2", &[]);
     world.add_resource(EventChannel::<winit::Event>::new());
-    render::init::<ImageTarget>(&mut world, &mut dispatcher, dimensions, "D3", "D2");
+    #[cfg(not(feature = "software"))]
+    render::init::<Target>(&mut world, &mut dispatcher, dimensions, config, "D3", "D2");
+    #[cfg(feature = "software")]
+    render::init_software(&mut world, &mut dispatcher, dimensions, config, "D3", "D2");
     let mut dispatcher = dispatcher.build();
+    setup(&mut world);
 
     dispatcher.run_now(&mut world.res);
     world.maintain();
 
-    let out_path = test_data_path(dir.as_ref()).join("actual.png");
-    let in_path = test_data_path(dir.as_ref()).join("expected.png");
-
     world
-        .write_resource::<ImageTarget>()
-        .read(|data, [width, height]| {
-            write_png(out_path, data, (width, height));
-
-            let (exp_data, exp_dims) = read_png(in_path);
-            assert_eq!(data, &*exp_data);
-            assert_eq!((width, height), exp_dims);
-            Ok(())
-        })
-        .unwrap();
+        .write_resource::<Target>()
+        .read(|data, _| Ok(data.to_vec()))
+        .unwrap()
 }
 
-fn read_png(path: impl AsRef<Path>) -> (Vec<u8>, (u32, u32)) {
-    let mut decoder = PNGDecoder::new(File::open(path).unwrap());
-    let dims = decoder.dimensions().unwrap();
-    match decoder.read_image().unwrap() {
-        DecodingResult::U8(img) => (img, dims),
-        _ => unreachable!(),
-    }
+/// Creates a texture which can be drawn by the renderer under test, after it has been
+/// initialized in `world`.
+#[cfg(not(feature = "software"))]
+pub fn create_texture(world: &World, data: &[u8], dimensions: [u32; 2]) -> Texture {
+    world
+        .read_resource::<render::Context>()
+        .create_texture(data, dimensions)
+        .unwrap()
 }
 
-fn write_png(path: impl AsRef<Path>, img: &[u8], (width, height): (u32, u32)) {
-    PNGEncoder::new(File::create(path).unwrap())
-        .encode(img, width, height, ColorType::RGBA(8))
-        .unwrap()
+/// Creates a texture which can be drawn by the renderer under test.
+#[cfg(feature = "software")]
+pub fn create_texture(_: &World, data: &[u8], dimensions: [u32; 2]) -> Texture {
+    Texture::from_rgba8(data.to_vec(), dimensions).unwrap()
 }
 
 fn test_data_path(dir: impl AsRef<Path>) -> PathBuf {
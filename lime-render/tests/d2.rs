@@ -1,6 +1,6 @@
 extern crate env_logger;
-extern crate image;
 extern crate lime_render as render;
+extern crate rusttype;
 extern crate shrev;
 extern crate specs;
 extern crate winit;
@@ -10,8 +10,8 @@ mod common;
 use std::f32::consts::PI;
 use std::path::Path;
 
-use render::d2::{Point, Renderer};
-use render::Color;
+use render::d2::{Point, Rect, Renderer};
+use render::{Color, RenderConfig};
 use specs::prelude::*;
 
 struct D3;
@@ -22,21 +22,21 @@ impl<'a> System<'a> for D3 {
     fn run(&mut self, (): Self::SystemData) {}
 }
 
-fn test_d2(name: impl AsRef<Path>, dims: [u32; 2], f: impl FnMut(&mut Renderer) + Send) {
-    struct D2<F>(F);
+struct D2Fn<F>(F);
 
-    impl<'a, F> System<'a> for D2<F>
-    where
-        F: FnMut(&mut Renderer) + Send,
-    {
-        type SystemData = WriteExpect<'a, Renderer>;
+impl<'a, F> System<'a> for D2Fn<F>
+where
+    F: FnMut(&mut Renderer) + Send,
+{
+    type SystemData = WriteExpect<'a, Renderer>;
 
-        fn run(&mut self, mut r: Self::SystemData) {
-            (self.0)(&mut r)
-        }
+    fn run(&mut self, mut r: Self::SystemData) {
+        (self.0)(&mut r)
     }
+}
 
-    common::test(Path::new("d2").join(name), D3, D2(f), dims);
+fn test_d2(name: impl AsRef<Path>, dims: [u32; 2], f: impl FnMut(&mut Renderer) + Send) {
+    common::test(Path::new("d2").join(name), D3, D2Fn(f), dims);
 }
 
 #[test]
@@ -53,6 +53,36 @@ fn triangle() {
     })
 }
 
+// The software renderer ignores `samples`, so it would draw hard edges.
+#[test]
+#[cfg(not(feature = "software"))]
+fn multisample() {
+    let config = RenderConfig {
+        clear_color: Color::BLUE,
+        samples: 4,
+        ..RenderConfig::default()
+    };
+    let d2 = D2Fn(|r: &mut Renderer| {
+        r.draw_tris(
+            &[
+                Point(100.0, 100.0),
+                Point(300.0, 100.0),
+                Point(100.0, 300.0),
+            ],
+            Color::RED,
+        )
+    });
+    let data = common::render_frame(D3, d2, [400, 400], config, |_| ());
+
+    // The diagonal edge passes through the centers of these pixels, so with multisampling they
+    // are a mix of the triangle and the background.
+    for x in 100..300 {
+        let i = 4 * ((399 - x) * 400 + x) as usize;
+        let (r, g, b) = (data[i], data[i + 1], data[i + 2]);
+        assert!(0 < r && r < 255 && g == 0 && 0 < b && b < 255, "pixel {} not blended", x);
+    }
+}
+
 #[test]
 fn circle() {
     const N: usize = 17;
@@ -71,3 +101,175 @@ fn circle() {
         r.draw_tris(&vertices, Color::BLUE)
     })
 }
+
+#[test]
+fn clip() {
+    fn fill(r: &mut Renderer, color: Color) {
+        r.draw_tris(
+            &[
+                Point(0.0, 0.0),
+                Point(500.0, 0.0),
+                Point(0.0, 500.0),
+                Point(0.0, 500.0),
+                Point(500.0, 0.0),
+                Point(500.0, 500.0),
+            ],
+            color,
+        )
+    }
+
+    test_d2("clip", [500, 500], |r| {
+        fill(r, Color::RED);
+        r.push_clip(Rect::new(100.0, 100.0, 300.0, 300.0));
+        fill(r, Color::BLUE);
+        r.push_clip(Rect::new(200.0, 50.0, 400.0, 250.0));
+        fill(r, Color::GREEN);
+        r.pop_clip();
+        r.pop_clip();
+    })
+}
+
+#[test]
+fn clip_glyphs() {
+    use render::d2::Font;
+    use rusttype::{point, Scale};
+
+    const FONT: &[u8] = include_bytes!("data/fonts/DejaVuSansMono.ttf");
+
+    struct D2(Option<Font>);
+
+    impl<'a> System<'a> for D2 {
+        type SystemData = WriteExpect<'a, Renderer>;
+
+        fn run(&mut self, mut r: Self::SystemData) {
+            let font = self.0.get_or_insert_with(|| {
+                r.add_font(rusttype::Font::from_bytes(FONT).unwrap())
+            });
+            // A full block, about 50 pixels wide, starting at x = 100.
+            let glyphs: Vec<_> = font
+                .rusttype()
+                .layout("\u{2588}", Scale::uniform(100.0), point(100.0, 200.0))
+                .collect();
+            r.push_clip(Rect::new(0.0, 0.0, 125.0, 500.0));
+            r.draw_glyphs(glyphs, font.id(), Color::WHITE);
+            r.pop_clip();
+            // The same glyph, unclipped, above and to the right of the first.
+            let glyphs: Vec<_> = font
+                .rusttype()
+                .layout("\u{2588}", Scale::uniform(100.0), point(150.0, 100.0))
+                .collect();
+            r.draw_glyphs(glyphs, font.id(), Color::WHITE);
+        }
+    }
+
+    let [w, h] = [250, 250];
+    let data = common::render_frame(D3, D2(None), [w, h], RenderConfig::default(), |_| ());
+    let pixel = |x: u32, y: u32| {
+        let i = 4 * (y * w + x) as usize;
+        &data[i..i + 4]
+    };
+
+    // The clipped glyph is cut at the edge of the clip rather than dropped.
+    assert_eq!(pixel(124, 190), &[255, 255, 255, 255]);
+    assert_eq!(pixel(175, 70), &[255, 255, 255, 255]);
+    for y in 130..h {
+        for x in 125..w {
+            assert_eq!(pixel(x, y), &[0, 0, 0, 255], "pixel ({}, {}) not clipped", x, y);
+        }
+    }
+}
+
+#[test]
+fn image() {
+    use render::Texture;
+
+    struct D2;
+
+    impl<'a> System<'a> for D2 {
+        type SystemData = (ReadExpect<'a, Texture>, WriteExpect<'a, Renderer>);
+
+        fn run(&mut self, (texture, mut r): Self::SystemData) {
+            r.draw_image(
+                Rect::new(100.0, 100.0, 300.0, 200.0),
+                &texture,
+                Rect::new(0.0, 0.0, 1.0, 1.0),
+                Color::GREEN,
+            );
+        }
+    }
+
+    common::test_with(Path::new("d2").join("image"), D3, D2, [500, 500], |world| {
+        let texture = common::create_texture(world, &[255; 16], [2, 2]);
+        world.add_resource(texture);
+    });
+}
+
+// Offscreen targets are rendered through Vulkan, so the software renderer has no pixels to draw
+// for their textures and this golden is only checked on the GPU.
+#[test]
+#[cfg(not(feature = "software"))]
+fn offscreen() {
+    use render::{Context, OffscreenTarget};
+
+    struct D2;
+
+    impl<'a> System<'a> for D2 {
+        type SystemData = (WriteExpect<'a, Renderer>, WriteStorage<'a, OffscreenTarget>);
+
+        fn run(&mut self, (mut r, mut targets): Self::SystemData) {
+            for target in (&mut targets).join() {
+                target.d2().draw_tris(
+                    &[
+                        Point(0.0, 0.0),
+                        Point(50.0, 0.0),
+                        Point(0.0, 100.0),
+                        Point(0.0, 100.0),
+                        Point(50.0, 0.0),
+                        Point(50.0, 100.0),
+                    ],
+                    Color::RED,
+                );
+                r.draw_image(
+                    Rect::new(50.0, 50.0, 150.0, 150.0),
+                    &target.texture(),
+                    Rect::new(0.0, 0.0, 1.0, 1.0),
+                    Color::WHITE,
+                );
+            }
+        }
+    }
+
+    common::test_with(
+        Path::new("d2").join("offscreen"),
+        D3,
+        D2,
+        [200, 200],
+        |world| {
+            let mut target = {
+                let ctx = world.read_resource::<Context>();
+                OffscreenTarget::new(&ctx, [100, 100], &RenderConfig::default()).unwrap()
+            };
+            target.set_clear_color(Color::BLUE);
+            world.create_entity().with(target).build();
+        },
+    );
+}
+
+#[test]
+fn colored() {
+    fn quad(left: f32, right: f32, left_color: Color, right_color: Color) -> Vec<(Point, Color)> {
+        vec![
+            (Point(left, 500.0), left_color),
+            (Point(left, 0.0), left_color),
+            (Point(right, 500.0), right_color),
+            (Point(right, 500.0), right_color),
+            (Point(left, 0.0), left_color),
+            (Point(right, 0.0), right_color),
+        ]
+    }
+
+    let mut vertices = quad(0.0, 250.0, Color::RED, Color::RED);
+    // Fades from green at the left edge to blue at the right edge.
+    vertices.extend(quad(250.0, 500.0, Color::GREEN, Color::BLUE));
+    test_d2("colored", [500, 500], |r| r.draw_tris_colored(&vertices))
+}
@@ -0,0 +1,143 @@
+extern crate env_logger;
+extern crate lime_render as render;
+extern crate shrev;
+extern crate specs;
+extern crate vulkano;
+extern crate winit;
+
+mod common;
+
+use std::path::Path;
+
+use render::cgmath::Vector3;
+use render::d3::{
+    Camera, Material, Mesh, MeshDrawSystem, MeshHandle, Parent, Renderer, Transform, Vector,
+};
+use render::{Color, Context};
+use specs::prelude::*;
+
+struct D2;
+
+impl<'a> System<'a> for D2 {
+    type SystemData = ();
+
+    fn run(&mut self, (): Self::SystemData) {}
+}
+
+fn test_d3(
+    name: impl AsRef<Path>,
+    dims: [u32; 2],
+    camera: Camera,
+    f: impl FnMut(Option<&Context>, &mut Renderer) + Send,
+) {
+    struct D3<F>(Camera, F);
+
+    impl<'a, F> System<'a> for D3<F>
+    where
+        F: FnMut(Option<&Context>, &mut Renderer) + Send,
+    {
+        type SystemData = (
+            Option<ReadExpect<'a, Context>>,
+            WriteExpect<'a, Camera>,
+            WriteExpect<'a, Renderer>,
+        );
+
+        fn run(&mut self, (ctx, mut camera, mut r): Self::SystemData) {
+            *camera = self.0;
+            (self.1)(ctx.as_ref().map(|ctx| &**ctx), &mut r)
+        }
+    }
+
+    common::test(Path::new("d3").join(name), D3(camera, f), D2, dims);
+}
+
+// A unit square in the xy plane, facing the positive z axis.
+fn quad(ctx: Option<&Context>) -> Mesh {
+    let normal = Vector(0.0, 0.0, 1.0);
+    create_mesh(
+        ctx,
+        vec![
+            (Vector(-0.5, -0.5, 0.0), normal),
+            (Vector(0.5, -0.5, 0.0), normal),
+            (Vector(-0.5, 0.5, 0.0), normal),
+            (Vector(0.5, 0.5, 0.0), normal),
+        ],
+        vec![0, 1, 2, 2, 1, 3],
+    )
+}
+
+#[cfg(not(feature = "software"))]
+fn create_mesh(ctx: Option<&Context>, vertices: Vec<(Vector, Vector)>, indices: Vec<u16>) -> Mesh {
+    use vulkano::sync::GpuFuture;
+
+    let (mesh, future) = Mesh::new(ctx.unwrap(), vertices, indices);
+    future
+        .then_signal_fence_and_flush()
+        .unwrap()
+        .wait(None)
+        .unwrap();
+    mesh
+}
+
+#[cfg(feature = "software")]
+fn create_mesh(_: Option<&Context>, vertices: Vec<(Vector, Vector)>, indices: Vec<u16>) -> Mesh {
+    Mesh::software(vertices, indices)
+}
+
+#[test]
+fn transform() {
+    let camera = Camera::orthographic(2.0, 0.1, 100.0);
+    test_d3("transform", [400, 400], camera, |ctx, r| {
+        let transform = Transform::from_translation(Vector3::new(0.25, 0.0, 0.0));
+        r.draw_mesh(quad(ctx), transform.matrix(), &Material::new(Color::RED));
+    });
+}
+
+#[test]
+fn instanced() {
+    let camera = Camera::orthographic(2.0, 0.1, 100.0);
+    test_d3("instanced", [400, 400], camera, |ctx, r| {
+        // A 10 by 10 grid of squares, each 20 pixels wide with 20 pixel gaps.
+        let worlds = (0..100).map(|i| {
+            let (x, y) = ((i % 10) as f32, (i / 10) as f32);
+            Transform::from_translation(Vector3::new(-0.9 + 0.2 * x, -0.9 + 0.2 * y, 0.0))
+                .with_scale(Vector3::new(0.1, 0.1, 1.0))
+                .matrix()
+        });
+        r.draw_mesh_instanced(quad(ctx), worlds, &Material::new(Color::RED));
+    });
+}
+
+#[test]
+fn hierarchy() {
+    common::test_with(
+        Path::new("d3").join("hierarchy"),
+        MeshDrawSystem,
+        D2,
+        [400, 400],
+        |world| {
+            *world.write_resource::<Camera>() = Camera::orthographic(2.0, 0.1, 100.0);
+            let mesh = {
+                let ctx = world.res.try_fetch::<Context>();
+                quad(ctx.as_ref().map(|ctx| &**ctx))
+            };
+
+            let parent = world
+                .create_entity()
+                .with(MeshHandle::new(mesh.clone()))
+                .with(Transform::from_translation(Vector3::new(-0.5, 0.0, 0.0)))
+                .with(Material::new(Color::RED))
+                .build();
+            world
+                .create_entity()
+                .with(MeshHandle::new(mesh))
+                .with(
+                    Transform::from_translation(Vector3::new(1.0, 0.5, 0.0))
+                        .with_scale(Vector3::new(0.5, 0.5, 0.5)),
+                )
+                .with(Material::new(Color::GREEN))
+                .with(Parent::new(parent))
+                .build();
+        },
+    );
+}
@@ -0,0 +1,109 @@
+extern crate lime_render as render;
+
+use std::path::{Path, PathBuf};
+
+use render::d3::{load, Indices, Vector};
+
+const UP: Vector = Vector(0.0, 0.0, 1.0);
+
+#[test]
+fn obj_normals() {
+    let obj = "\
+v 0 0 0
+v 1 0 0
+v 0 1 0
+vn 0 0 1
+f 1//1 2//1 3//1
+";
+    let (vertices, indices) = load::obj(obj.as_bytes()).unwrap();
+    assert_eq!(
+        vertices,
+        vec![
+            (Vector(0.0, 0.0, 0.0), UP),
+            (Vector(1.0, 0.0, 0.0), UP),
+            (Vector(0.0, 1.0, 0.0), UP),
+        ]
+    );
+    assert_eq!(indices, Indices::U16(vec![0, 1, 2]));
+}
+
+#[test]
+fn obj_computed_normals() {
+    let obj = "\
+# A unit square, without normals or texture coordinates.
+o square
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+f 1 2 3 4
+";
+    let (vertices, indices) = load::obj(obj.as_bytes()).unwrap();
+    assert_eq!(vertices.len(), 4);
+    assert!(vertices.iter().all(|&(_, normal)| normal == UP));
+    assert_eq!(indices, Indices::U16(vec![0, 1, 2, 0, 2, 3]));
+}
+
+#[test]
+fn obj_shared_vertices() {
+    let obj = "\
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+f 1/1 2/1 3/1
+f -4/1 -2/1 -1/1
+";
+    let (vertices, indices) = load::obj(obj.as_bytes()).unwrap();
+    assert_eq!(vertices.len(), 4);
+    assert_eq!(indices, Indices::U16(vec![0, 1, 2, 0, 2, 3]));
+}
+
+#[test]
+fn obj_invalid_index() {
+    let obj = "\
+v 0 0 0
+v 1 0 0
+f 1 2 3
+";
+    let err = load::obj(obj.as_bytes()).unwrap_err();
+    assert_eq!(err.to_string(), "line 3: index 3 out of range");
+}
+
+#[test]
+fn compact_indices() {
+    assert_eq!(Indices::compact(vec![0, 1, 2], 3), Indices::U16(vec![0, 1, 2]));
+    assert_eq!(
+        Indices::compact(vec![0, 1, 69_999], 70_000),
+        Indices::U32(vec![0, 1, 69_999])
+    );
+}
+
+#[test]
+fn gltf_binary() {
+    let (vertices, indices) = load::from_path(data_path("triangle.glb")).unwrap();
+    assert_eq!(
+        vertices,
+        vec![
+            (Vector(0.0, 0.0, 1.0), UP),
+            (Vector(1.0, 0.0, 1.0), UP),
+            (Vector(0.0, 1.0, 1.0), UP),
+        ]
+    );
+    assert_eq!(indices, Indices::U16(vec![0, 1, 2]));
+}
+
+#[test]
+fn gltf_invalid_index() {
+    let err = load::from_path(data_path("invalid_index.glb")).unwrap_err();
+    assert_eq!(err.to_string(), "glTF primitive has index 3 out of range for 3 vertices");
+}
+
+fn data_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("data")
+        .join("load")
+        .join(name)
+}
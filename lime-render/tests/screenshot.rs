@@ -0,0 +1,126 @@
+extern crate env_logger;
+extern crate lime_render as render;
+extern crate shrev;
+extern crate specs;
+extern crate winit;
+
+use render::d2::{Point, Renderer};
+#[cfg(not(feature = "software"))]
+use render::ImageTarget as Target;
+#[cfg(feature = "software")]
+use render::SoftwareTarget as Target;
+use render::{Color, RenderConfig, Screenshot, ScreenshotRequest};
+use shrev::EventChannel;
+use specs::prelude::*;
+
+struct D3;
+
+impl<'a> System<'a> for D3 {
+    type SystemData = ();
+
+    fn run(&mut self, (): Self::SystemData) {}
+}
+
+struct D2;
+
+impl<'a> System<'a> for D2 {
+    type SystemData = WriteExpect<'a, Renderer>;
+
+    fn run(&mut self, mut r: Self::SystemData) {
+        r.draw_tris(
+            &[Point(10.0, 10.0), Point(90.0, 10.0), Point(10.0, 90.0)],
+            Color::RED,
+        );
+    }
+}
+
+fn init(dimensions: [u32; 2]) -> (World, Dispatcher<'static, 'static>) {
+    env_logger::try_init().ok();
+
+    let mut world = World::new();
+    let mut dispatcher = DispatcherBuilder::new()
+        .with(D3, "D3", &[])
+        .with(D2, "D2", &[]);
+    world.add_resource(EventChannel::<winit::Event>::new());
+    let config = RenderConfig::default();
+    #[cfg(not(feature = "software"))]
+    render::init::<Target>(&mut world, &mut dispatcher, dimensions, config, "D3", "D2");
+    #[cfg(feature = "software")]
+    render::init_software(&mut world, &mut dispatcher, dimensions, config, "D3", "D2");
+    (world, dispatcher.build())
+}
+
+#[test]
+fn capture() {
+    let dimensions = [100, 100];
+    let (mut world, mut dispatcher) = init(dimensions);
+
+    let mut reader = world
+        .write_resource::<EventChannel<Screenshot>>()
+        .register_reader();
+    world.write_resource::<ScreenshotRequest>().request();
+
+    dispatcher.dispatch(&world.res);
+    world.maintain();
+    let expected = world
+        .write_resource::<Target>()
+        .read(|data, dimensions| {
+            Ok(Screenshot {
+                data: data.to_vec(),
+                dimensions,
+            })
+        })
+        .unwrap();
+
+    // The screenshot is delivered once the GPU has finished the frame.
+    let mut screenshots = Vec::new();
+    for _ in 0..3 {
+        screenshots.extend(
+            world
+                .read_resource::<EventChannel<Screenshot>>()
+                .read(&mut reader)
+                .cloned(),
+        );
+        if !screenshots.is_empty() {
+            break;
+        }
+        dispatcher.dispatch(&world.res);
+        world.maintain();
+    }
+
+    assert_eq!(screenshots, vec![expected]);
+}
+
+#[test]
+fn capture_consecutive() {
+    let (mut world, mut dispatcher) = init([100, 100]);
+
+    let mut reader = world
+        .write_resource::<EventChannel<Screenshot>>()
+        .register_reader();
+
+    // The second request is made before the first screenshot can have been delivered.
+    for _ in 0..2 {
+        world.write_resource::<ScreenshotRequest>().request();
+        dispatcher.dispatch(&world.res);
+        world.maintain();
+    }
+
+    let mut screenshots = Vec::new();
+    for _ in 0..4 {
+        screenshots.extend(
+            world
+                .read_resource::<EventChannel<Screenshot>>()
+                .read(&mut reader)
+                .cloned(),
+        );
+        if screenshots.len() == 2 {
+            break;
+        }
+        dispatcher.dispatch(&world.res);
+        world.maintain();
+    }
+
+    assert_eq!(screenshots.len(), 2);
+    assert_eq!(screenshots[0], screenshots[1]);
+}
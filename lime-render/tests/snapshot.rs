@@ -0,0 +1,34 @@
+extern crate lime_render as render;
+
+use render::snapshot::compare;
+
+#[test]
+fn identical() {
+    let image = [10, 20, 30, 255, 40, 50, 60, 255];
+    let diff = compare(&image, &image, [2, 1], 0);
+    assert!(diff.is_match());
+    assert_eq!(diff.max_difference, 0);
+    assert_eq!(diff.first, None);
+}
+
+#[test]
+fn within_tolerance() {
+    let expected = [10, 20, 30, 255, 40, 50, 60, 255];
+    let actual = [11, 20, 28, 255, 40, 50, 60, 254];
+    let diff = compare(&expected, &actual, [2, 1], 2);
+    assert!(diff.is_match());
+    assert_eq!(diff.max_difference, 2);
+}
+
+#[test]
+fn mismatch() {
+    let expected = [0; 16];
+    let mut actual = [0; 16];
+    actual[13] = 100;
+    let diff = compare(&expected, &actual, [2, 2], 2);
+    assert_eq!(diff.mismatched, 1);
+    assert_eq!(diff.max_difference, 100);
+    assert_eq!(diff.first, Some([1, 1]));
+    assert_eq!(&diff.image[12..16], &[255, 0, 0, 255]);
+    assert_eq!(&diff.image[..4], &[0, 0, 0, 255]);
+}
@@ -13,7 +13,7 @@ use cassowary::strength::*;
 use render::Color;
 use shrev::EventChannel;
 use specs::prelude::*;
-use ui::draw::{Brush, Style};
+use ui::draw::{BorderStyle, Brush, Style};
 use ui::layout::Position;
 use ui::tree::{Node, Root};
 use ui::widget::button::{Button, ButtonStyle};
@@ -39,6 +39,12 @@ fn main() {
         normal: Brush::Color(Color::RED),
         focused: Brush::Color(Color::GREEN),
         pressed: Brush::Color(Color::BLUE),
+        border: Some(BorderStyle {
+            radius: [8.0; 4],
+            width: 2.0,
+            color: Some(Color::rgb(1.0, 1.0, 1.0)),
+            ..Default::default()
+        }),
     };
 
     let style = world.create_entity().with(btn_style).build();
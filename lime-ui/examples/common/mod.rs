@@ -37,6 +37,7 @@ pub fn init(events_loop: &EventsLoop) -> (World, Dispatcher<'static, 'static>) {
         &mut world,
         &mut dispatcher,
         window,
+        render::RenderConfig::default(),
         D3::NAME,
         DrawSystem::NAME,
     );
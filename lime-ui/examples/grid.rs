@@ -18,23 +18,20 @@ use specs::prelude::*;
 use ui::draw::Brush;
 use ui::layout::{Constraints, Position};
 use ui::tree::{Node, Root};
-use ui::widget::grid::{Grid, Size};
+use ui::widget::grid::{Col, Grid, Row, Size};
 use winit::{Event, EventsLoop, WindowEvent};
 
 fn create_rect(world: &mut World, parent: Entity, col: u32, row: u32, color: Color) -> Entity {
     let pos = Position::new();
-    let mut cons = pos.constraints_builder()
+    let cons = pos.constraints_builder()
         .min_size((100.0, 100.0), STRONG)
         .build();
-    world
-        .read_storage::<Grid>()
-        .get(parent)
-        .unwrap()
-        .insert(col, row, &pos, &mut cons);
 
     Node::with_parent(world.create_entity(), parent)
         .with(pos)
         .with(cons)
+        .with(Col(col))
+        .with(Row(row))
         .with(Brush::Color(color))
         .build()
 }
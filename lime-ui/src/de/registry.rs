@@ -63,7 +63,10 @@ impl Registry {
         reg.register_with_deserialize_and_insert::<layout::Position>("Position");
         reg.register_with_deserialize::<tree::Node>("Children");
         reg.register::<draw::Brush>("Brush");
+        reg.register::<draw::BorderStyle>("BorderStyle");
         reg.register_with_deserialize_and_insert::<draw::Style>("Style");
+        reg.register::<event::Focusable>("Focusable");
+        reg.register::<event::StopPropagation>("StopPropagation");
         reg.register::<widget::button::Button>("Button");
         reg.register::<widget::button::ButtonStyle>("ButtonStyle");
         reg.register::<widget::button::ToggleButton>("ToggleButton");
@@ -72,8 +75,17 @@ impl Registry {
         reg.register_with_deserialize::<widget::button::RadioButtonGroup>("RadioButtonGroup");
         reg.register::<widget::button::RadioButtonStyle>("RadioButtonStyle");
         reg.register_with_deserialize::<widget::grid::Grid>("Grid");
-        reg.register_with_insert::<widget::grid::de::Row>("Row");
-        reg.register_with_insert::<widget::grid::de::Col>("Col");
+        reg.register_with_insert::<widget::grid::Row>("Row");
+        reg.register_with_insert::<widget::grid::Col>("Col");
+        reg.register::<widget::grid::RowSpan>("RowSpan");
+        reg.register::<widget::grid::ColSpan>("ColSpan");
+        reg.register::<widget::grid::HorizontalAlignment>("HorizontalAlignment");
+        reg.register::<widget::grid::VerticalAlignment>("VerticalAlignment");
+        reg.register::<widget::grid::Margin>("Margin");
+        reg.register_with_deserialize::<widget::scroll::ScrollView>("ScrollView");
+        reg.register_with_deserialize::<widget::stack::Stack>("Stack");
+        reg.register_with_deserialize::<widget::text::Text>("Text");
+        reg.register_with_deserialize_and_insert::<widget::text::TextInput>("TextInput");
 
         reg
     }
@@ -19,7 +19,9 @@ pub struct Seed<'de: 'a, 'a> {
 
 impl<'de, 'a> Seed<'de, 'a> {
     pub fn get_entity(&mut self, name: Cow<'de, str>) -> Result<Entity, DeserializeError> {
-        if is_valid_name(name.as_ref()) {
+        if is_reserved_name(name.as_ref()) {
+            Err(reserved_name(name.as_ref()))
+        } else if is_valid_name(name.as_ref()) {
             Ok(get_entity(name, &mut self.names, &*self.res.fetch()))
         } else {
             Err(DeserializeError(format!("invalid name '{}'", name)))
@@ -123,6 +125,9 @@ impl<'de: 'a, 'a> serde::DeserializeSeed<'de> for UiSeed<'de, 'a> {
                 A: serde::MapAccess<'de>,
             {
                 while let Some(name) = map.next_key::<Cow<str>>()? {
+                    if is_reserved_name(name.as_ref()) {
+                        return Err(serde::Error::custom(reserved_name(name.as_ref())));
+                    }
                     let entity = get_entity(name, &mut self.0.names, &*self.0.res.fetch());
                     map.next_value_seed(EntitySeed(Seed {
                         res: self.0.res,
@@ -204,3 +209,14 @@ fn get_entity<'de, 'a>(
 fn is_valid_name(s: &str) -> bool {
     s.chars().all(|c| c == '_' || c.is_ascii_alphanumeric())
 }
+
+// Names with a meaning of their own in constraints, which would be ambiguous as entity names.
+const RESERVED_NAMES: &[&str] = &["parent", "weak", "medium", "strong", "required"];
+
+fn is_reserved_name(s: &str) -> bool {
+    RESERVED_NAMES.contains(&s)
+}
+
+fn reserved_name(name: &str) -> DeserializeError {
+    DeserializeError(format!("'{}' is reserved and cannot be used as an entity name", name))
+}
@@ -174,3 +174,21 @@ fn name() {
         assert_eq!(comp2s.get(name_map["ent4"]), Some(&Comp2(ents[0])));
     }
 }
+
+#[test]
+fn reserved_name() {
+    #[derive(Clone, Debug, Component, Deserialize)]
+    struct Comp1(i32);
+
+    let mut world = World::new();
+    let mut registry = Registry::new();
+    world.register::<Comp1>();
+    registry.register::<Comp1>("comp1");
+    Root::create(&mut world);
+
+    for data in &[r#"{ "parent": { "comp1": 1 } }"#, r#"{ "strong": { "comp1": 1 } }"#] {
+        let err = deserialize(&mut json::Deserializer::from_str(data), &registry, &mut world.res)
+            .unwrap_err();
+        assert!(err.to_string().contains("is reserved"), "{}", err);
+    }
+}
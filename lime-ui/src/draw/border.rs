@@ -0,0 +1,158 @@
+use std::f32::consts::FRAC_PI_2;
+
+use render::d2::{Point, Rect};
+use render::Color;
+use specs::prelude::*;
+
+use draw::gradient::fan;
+
+/// Rounded corners, an outline and a drop shadow for the brush of an entity.
+#[derive(Clone, Component, Debug, Default, Deserialize, PartialEq)]
+#[storage(HashMapStorage)]
+pub struct BorderStyle {
+    /// Radii of the top left, top right, bottom right and bottom left corners.
+    #[serde(default)]
+    pub radius: [f32; 4],
+    #[serde(default)]
+    pub width: f32,
+    #[serde(default)]
+    pub color: Option<Color>,
+    #[serde(default)]
+    pub shadow: Option<Shadow>,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
+pub struct Shadow {
+    #[serde(default = "Point::origin")]
+    pub offset: Point,
+    /// Width of the region over which the shadow fades out.
+    #[serde(default)]
+    pub blur: f32,
+    pub color: Color,
+}
+
+impl BorderStyle {
+    pub fn is_rounded(&self) -> bool {
+        self.radius.iter().any(|&r| r > 0.0)
+    }
+}
+
+/// Sets or removes the border style of an entity, as chosen by a widget style.
+pub(crate) fn set_border(
+    borders: &mut WriteStorage<BorderStyle>,
+    entity: Entity,
+    border: Option<&BorderStyle>,
+) {
+    match border {
+        Some(border) => {
+            borders.insert(entity, border.clone()).ok();
+        }
+        None => {
+            borders.remove(entity);
+        }
+    }
+}
+
+// Number of segments approximating each rounded corner.
+const CORNER_SEGMENTS: usize = 8;
+
+/// The outline of a rectangle with rounded corners, as a convex polygon running clockwise from
+/// the top left corner. Radii are clamped to fit inside `area`. Every outline has the same
+/// number of points, so outlines of different sizes can be joined into a ring.
+pub(crate) fn rounded_rect(area: Rect, radius: [f32; 4]) -> Vec<Point> {
+    let max = (area.width().min(area.height()) / 2.0).max(0.0);
+    let r = |i: usize| radius[i].max(0.0).min(max);
+    let corners = [
+        (Point(area.left + r(0), area.top + r(0)), r(0), 2.0),
+        (Point(area.right - r(1), area.top + r(1)), r(1), 3.0),
+        (Point(area.right - r(2), area.bottom - r(2)), r(2), 0.0),
+        (Point(area.left + r(3), area.bottom - r(3)), r(3), 1.0),
+    ];
+
+    let mut points = Vec::with_capacity(4 * (CORNER_SEGMENTS + 1));
+    for &(center, corner, quadrant) in &corners {
+        for i in 0..=CORNER_SEGMENTS {
+            let angle = (quadrant + i as f32 / CORNER_SEGMENTS as f32) * FRAC_PI_2;
+            let (sin, cos) = angle.sin_cos();
+            points.push(Point(center.0 + corner * cos, center.1 + corner * sin));
+        }
+    }
+    points
+}
+
+/// Triangulates the region between two outlines from `rounded_rect`, with colors for the outer
+/// and inner edges.
+pub(crate) fn ring(
+    outer: &[Point],
+    inner: &[Point],
+    outer_color: Color,
+    inner_color: Color,
+) -> Vec<(Point, Color)> {
+    debug_assert_eq!(outer.len(), inner.len());
+    let mut tris = Vec::with_capacity(outer.len() * 6);
+    for i in 0..outer.len() {
+        let j = (i + 1) % outer.len();
+        tris.extend_from_slice(&[
+            (outer[i], outer_color),
+            (outer[j], outer_color),
+            (inner[i], inner_color),
+            (inner[i], inner_color),
+            (outer[j], outer_color),
+            (inner[j], inner_color),
+        ]);
+    }
+    tris
+}
+
+/// Triangulates the outline of `area` with the border's width and color.
+pub(crate) fn border(area: Rect, style: &BorderStyle) -> Vec<(Point, Color)> {
+    match style.color {
+        Some(color) if style.width > 0.0 => {
+            let outer = rounded_rect(area, style.radius);
+            let inner = rounded_rect(inset(area, style.width), offset(style.radius, -style.width));
+            ring(&outer, &inner, color, color)
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Triangulates the drop shadow of `area`, fading from the shadow color to transparent over
+/// the blur width.
+pub(crate) fn shadow(area: Rect, radius: [f32; 4], shadow: &Shadow) -> Vec<(Point, Color)> {
+    let area = Rect::new(
+        area.left + shadow.offset.0,
+        area.top + shadow.offset.1,
+        area.right + shadow.offset.0,
+        area.bottom + shadow.offset.1,
+    );
+    let half = shadow.blur.max(0.0) / 2.0;
+    let inner = rounded_rect(inset(area, half), offset(radius, -half));
+
+    let mut tris = Vec::new();
+    fan(&inner, |_| shadow.color, &mut tris);
+    if half > 0.0 {
+        let outer = rounded_rect(inset(area, -half), offset(radius, half));
+        let transparent = Color::rgba(shadow.color.r, shadow.color.g, shadow.color.b, 0.0);
+        tris.extend(ring(&outer, &inner, transparent, shadow.color));
+    }
+    tris
+}
+
+fn inset(area: Rect, by: f32) -> Rect {
+    let (x, y) = ((area.left + area.right) / 2.0, (area.top + area.bottom) / 2.0);
+    Rect::new(
+        (area.left + by).min(x),
+        (area.top + by).min(y),
+        (area.right - by).max(x),
+        (area.bottom - by).max(y),
+    )
+}
+
+fn offset(radius: [f32; 4], by: f32) -> [f32; 4] {
+    [
+        (radius[0] + by).max(0.0),
+        (radius[1] + by).max(0.0),
+        (radius[2] + by).max(0.0),
+        (radius[3] + by).max(0.0),
+    ]
+}
@@ -0,0 +1,209 @@
+use std::f32::consts::PI;
+
+use render::d2::{Point, Rect};
+use render::Color;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+
+/// A color at a position along a gradient, from `0.0` at the start to `1.0` at the end.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
+pub struct GradientStop {
+    pub offset: f32,
+    pub color: Color,
+}
+
+impl GradientStop {
+    pub fn new(offset: f32, color: Color) -> Self {
+        GradientStop { offset, color }
+    }
+}
+
+/// Deserializes the stops of a gradient brush, sorted by offset. Lists with no stops, or with
+/// an offset that is not a number, are rejected.
+pub(in draw) fn de_stops<'de, D>(deserializer: D) -> Result<Vec<GradientStop>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut stops = Vec::<GradientStop>::deserialize(deserializer)?;
+    if stops.is_empty() {
+        return Err(D::Error::custom("a gradient must have at least one stop"));
+    }
+    if stops.iter().any(|stop| stop.offset.is_nan()) {
+        return Err(D::Error::custom("gradient stop offsets must be numbers"));
+    }
+    stops.sort_by(|a, b| a.offset.partial_cmp(&b.offset).unwrap());
+    Ok(stops)
+}
+
+// Number of sides of the polygon approximating the ellipses of a radial gradient.
+const RADIAL_SEGMENTS: usize = 64;
+
+/// Triangulates a linear gradient filling `shape`, a convex polygon inside `area`. The gradient
+/// runs in the direction given by `angle`, in degrees clockwise from left to right, starting
+/// and ending at the corners of `area`.
+pub(crate) fn linear_gradient(
+    area: Rect,
+    shape: &[Point],
+    stops: &[GradientStop],
+    angle: f32,
+) -> Vec<(Point, Color)> {
+    let mut tris = Vec::new();
+    if stops.is_empty() || area.is_empty() {
+        return tris;
+    }
+
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let corners = corners(area);
+    let project = |p: Point| p.0 * cos + p.1 * sin;
+    let min = corners.iter().cloned().map(project).fold(f32::INFINITY, f32::min);
+    let max = corners.iter().cloned().map(project).fold(f32::NEG_INFINITY, f32::max);
+    let param = |p: Point| (project(p) - min) / (max - min);
+
+    // Split the area into bands between consecutive stops, so that colors only need to be
+    // interpolated linearly across each triangle.
+    let mut bounds: Vec<f32> = stops
+        .iter()
+        .map(|stop| stop.offset)
+        .filter(|&offset| 0.0 < offset && offset < 1.0)
+        .collect();
+    bounds.insert(0, 0.0);
+    bounds.push(1.0);
+
+    for band in bounds.windows(2) {
+        let poly = clip_polygon(shape, |p| param(p) - band[0]);
+        let poly = clip_polygon(&poly, |p| band[1] - param(p));
+        fan(&poly, |p| color_at(stops, param(p)), &mut tris);
+    }
+    tris
+}
+
+/// Triangulates a radial gradient filling `shape`, a convex polygon inside `area`. The gradient
+/// starts at the center of `area` and ends on the ellipse passing through its corners.
+pub(crate) fn radial_gradient(
+    area: Rect,
+    shape: &[Point],
+    stops: &[GradientStop],
+) -> Vec<(Point, Color)> {
+    let mut tris = Vec::new();
+    if stops.is_empty() || area.is_empty() {
+        return tris;
+    }
+
+    let center = Point(
+        (area.left + area.right) / 2.0,
+        (area.top + area.bottom) / 2.0,
+    );
+    let radii = (
+        area.width() * 0.5 * 2f32.sqrt(),
+        area.height() * 0.5 * 2f32.sqrt(),
+    );
+    let param = |p: Point| {
+        let x = (p.0 - center.0) / radii.0;
+        let y = (p.1 - center.1) / radii.1;
+        (x * x + y * y).sqrt()
+    };
+
+    // The outermost ring is pushed out so the polygon covers the whole ellipse.
+    let mut rings: Vec<f32> = stops
+        .iter()
+        .map(|stop| stop.offset)
+        .filter(|&offset| 0.0 < offset && offset < 1.0)
+        .collect();
+    rings.push(1.0 / (PI / RADIAL_SEGMENTS as f32).cos());
+
+    let point = |r: f32, i: usize| {
+        let (sin, cos) = (2.0 * PI * i as f32 / RADIAL_SEGMENTS as f32).sin_cos();
+        Point(center.0 + r * radii.0 * cos, center.1 + r * radii.1 * sin)
+    };
+    for i in 0..RADIAL_SEGMENTS {
+        let mut inner = 0.0;
+        for (j, &outer) in rings.iter().enumerate() {
+            let quad = if j == 0 {
+                vec![center, point(outer, i), point(outer, i + 1)]
+            } else {
+                vec![
+                    point(inner, i),
+                    point(outer, i),
+                    point(outer, i + 1),
+                    point(inner, i + 1),
+                ]
+            };
+            let poly = clip_to(&quad, shape);
+            fan(&poly, |p| color_at(stops, param(p)), &mut tris);
+            inner = outer;
+        }
+    }
+    tris
+}
+
+/// The corners of `area`, clockwise from the top left.
+pub(crate) fn corners(area: Rect) -> Vec<Point> {
+    vec![
+        Point(area.left, area.top),
+        Point(area.right, area.top),
+        Point(area.right, area.bottom),
+        Point(area.left, area.bottom),
+    ]
+}
+
+/// Clips a convex polygon to `shape`, a convex polygon with clockwise winding.
+fn clip_to(poly: &[Point], shape: &[Point]) -> Vec<Point> {
+    let mut poly = poly.to_vec();
+    for (i, &a) in shape.iter().enumerate() {
+        let b = shape[(i + 1) % shape.len()];
+        poly = clip_polygon(&poly, |p| (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0));
+    }
+    poly
+}
+
+/// Clips a convex polygon to the half-plane where `dist` is non-negative. `dist` must be linear.
+fn clip_polygon(poly: &[Point], dist: impl Fn(Point) -> f32) -> Vec<Point> {
+    let mut result = Vec::with_capacity(poly.len() + 1);
+    for (i, &cur) in poly.iter().enumerate() {
+        let prev = poly[(i + poly.len() - 1) % poly.len()];
+        let (dp, dc) = (dist(prev), dist(cur));
+        if (dp < 0.0) != (dc < 0.0) {
+            let t = dp / (dp - dc);
+            result.push(Point(
+                prev.0 + (cur.0 - prev.0) * t,
+                prev.1 + (cur.1 - prev.1) * t,
+            ));
+        }
+        if dc >= 0.0 {
+            result.push(cur);
+        }
+    }
+    result
+}
+
+/// Triangulates a convex polygon, coloring each vertex with `color`.
+pub(in draw) fn fan(
+    poly: &[Point],
+    color: impl Fn(Point) -> Color,
+    tris: &mut Vec<(Point, Color)>,
+) {
+    for i in 2..poly.len() {
+        for &p in &[poly[0], poly[i - 1], poly[i]] {
+            tris.push((p, color(p)));
+        }
+    }
+}
+
+/// The color of the gradient at `t`. Positions before the first stop or after the last take
+/// the color of that stop. `stops` must be non-empty and sorted by offset.
+fn color_at(stops: &[GradientStop], t: f32) -> Color {
+    match stops.iter().position(|stop| t < stop.offset) {
+        Some(0) => stops[0].color,
+        Some(i) => {
+            let (prev, next) = (stops[i - 1], stops[i]);
+            let s = (t - prev.offset) / (next.offset - prev.offset);
+            Color::rgba(
+                prev.color.r + (next.color.r - prev.color.r) * s,
+                prev.color.g + (next.color.g - prev.color.g) * s,
+                prev.color.b + (next.color.b - prev.color.b) * s,
+                prev.color.a + (next.color.a - prev.color.a) * s,
+            )
+        }
+        None => stops[stops.len() - 1].color,
+    }
+}
@@ -0,0 +1,235 @@
+use std::mem;
+use std::path::{Path, PathBuf};
+
+use fnv::FnvHashMap;
+use render::d2::Rect;
+use render::{Color, Context, PendingTexture, Texture};
+use serde::{Deserialize, Deserializer};
+
+/// An image brush. The texture is loaded from `path` the first time the brush is drawn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    path: PathBuf,
+    uv: Rect,
+    tint: Color,
+}
+
+impl Image {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Image {
+            path: path.into(),
+            uv: Rect::new(0.0, 0.0, 1.0, 1.0),
+            tint: Color::rgb(1.0, 1.0, 1.0),
+        }
+    }
+
+    /// Draw only part of the image, given in texture coordinates from `0.0` to `1.0`.
+    pub fn with_uv(mut self, uv: Rect) -> Self {
+        self.uv = uv;
+        self
+    }
+
+    pub fn with_tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn uv(&self) -> Rect {
+        self.uv
+    }
+
+    pub fn tint(&self) -> Color {
+        self.tint
+    }
+}
+
+/// Images are deserialized from either a path, or a struct with a `path` and optional `uv` and
+/// `tint`.
+impl<'de> Deserialize<'de> for Image {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum ImageDe {
+            Path(PathBuf),
+            Struct {
+                path: PathBuf,
+                #[serde(default)]
+                uv: Option<Rect>,
+                #[serde(default)]
+                tint: Option<Color>,
+            },
+        }
+
+        Ok(match ImageDe::deserialize(deserializer)? {
+            ImageDe::Path(path) => Image::new(path),
+            ImageDe::Struct { path, uv, tint } => {
+                let mut image = Image::new(path);
+                if let Some(uv) = uv {
+                    image = image.with_uv(uv);
+                }
+                if let Some(tint) = tint {
+                    image = image.with_tint(tint);
+                }
+                image
+            }
+        })
+    }
+}
+
+/// Widths of the borders of a nine-slice image, in pixels of the texture. Borders are drawn
+/// at their original size and the center is stretched to fill the rest of the area.
+#[derive(Copy, Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct Insets {
+    #[serde(default)]
+    pub left: f32,
+    #[serde(default)]
+    pub top: f32,
+    #[serde(default)]
+    pub right: f32,
+    #[serde(default)]
+    pub bottom: f32,
+}
+
+impl Insets {
+    pub fn uniform(width: f32) -> Self {
+        Insets {
+            left: width,
+            top: width,
+            right: width,
+            bottom: width,
+        }
+    }
+}
+
+/// Splits `area` into up to nine quads for drawing a nine-slice image, returning pairs of
+/// destination rectangles and texture coordinates. `uv` is the region of the texture holding
+/// the image and `size` is the size of that region in pixels. Borders are scaled down when
+/// `area` is too small to hold them.
+pub(crate) fn nine_slice(
+    area: Rect,
+    insets: Insets,
+    uv: Rect,
+    size: [f32; 2],
+) -> Vec<(Rect, Rect)> {
+    let xs = slice(area.left, area.right, insets.left, insets.right);
+    let ys = slice(area.top, area.bottom, insets.top, insets.bottom);
+    let us = [
+        uv.left,
+        uv.left + uv.width() * insets.left / size[0],
+        uv.right - uv.width() * insets.right / size[0],
+        uv.right,
+    ];
+    let vs = [
+        uv.top,
+        uv.top + uv.height() * insets.top / size[1],
+        uv.bottom - uv.height() * insets.bottom / size[1],
+        uv.bottom,
+    ];
+
+    let mut quads = Vec::with_capacity(9);
+    for row in 0..3 {
+        for col in 0..3 {
+            let rect = Rect::new(xs[col], ys[row], xs[col + 1], ys[row + 1]);
+            if !rect.is_empty() {
+                let uv = Rect::new(us[col], vs[row], us[col + 1], vs[row + 1]);
+                quads.push((rect, uv));
+            }
+        }
+    }
+    quads
+}
+
+fn slice(start: f32, end: f32, lo: f32, hi: f32) -> [f32; 4] {
+    let scale = if lo + hi > end - start {
+        (end - start) / (lo + hi)
+    } else {
+        1.0
+    };
+    [start, start + lo * scale, end - hi * scale, end]
+}
+
+/// Cache of textures loaded for image brushes, keyed by path.
+#[derive(Debug, Default)]
+pub struct Textures {
+    map: FnvHashMap<PathBuf, TextureState>,
+}
+
+#[derive(Debug)]
+enum TextureState {
+    Loading(PendingTexture),
+    Loaded(Texture),
+    Failed,
+}
+
+impl Textures {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds a texture for `path`, so it will not be loaded from disk.
+    pub fn insert(&mut self, path: impl Into<PathBuf>, texture: Texture) {
+        self.map.insert(path.into(), TextureState::Loaded(texture));
+    }
+
+    /// Get the texture for `path`, starting to load it if necessary. Returns `None` until the
+    /// texture has been uploaded, so drawing doesn't wait for the GPU. Images that fail to load
+    /// are logged once and then ignored.
+    pub fn get(&mut self, ctx: &Context, path: &Path) -> Option<&Texture> {
+        let state = self
+            .map
+            .entry(path.to_owned())
+            .or_insert_with(|| match ctx.begin_load_texture(path) {
+                Ok(pending) => TextureState::Loading(pending),
+                Err(err) => {
+                    error!("Failed to load image '{}': {}.", path.display(), err);
+                    TextureState::Failed
+                }
+            });
+
+        let ready = match *state {
+            TextureState::Loading(ref pending) => Some(pending.is_ready()),
+            _ => None,
+        };
+        match ready {
+            Some(Ok(false)) | None => (),
+            Some(Ok(true)) => {
+                // The upload has finished, so this does not block.
+                *state = match mem::replace(state, TextureState::Failed) {
+                    TextureState::Loading(pending) => match pending.wait() {
+                        Ok(texture) => TextureState::Loaded(texture),
+                        Err(err) => {
+                            error!("Failed to upload image '{}': {}.", path.display(), err);
+                            TextureState::Failed
+                        }
+                    },
+                    _ => unreachable!(),
+                };
+            }
+            Some(Err(err)) => {
+                error!("Failed to upload image '{}': {}.", path.display(), err);
+                *state = TextureState::Failed;
+            }
+        }
+
+        match *state {
+            TextureState::Loaded(ref texture) => Some(texture),
+            _ => None,
+        }
+    }
+
+    /// Get the texture for `path` if it has been inserted or has finished loading, without
+    /// starting to load it.
+    pub fn get_loaded(&self, path: &Path) -> Option<&Texture> {
+        match self.map.get(path) {
+            Some(&TextureState::Loaded(ref texture)) => Some(texture),
+            _ => None,
+        }
+    }
+}
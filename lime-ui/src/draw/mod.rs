@@ -1,26 +1,48 @@
+mod border;
+mod gradient;
+mod image;
 mod style;
+#[cfg(test)]
+mod tests;
 
+pub use self::border::{BorderStyle, Shadow};
+pub(crate) use self::border::set_border;
+pub use self::gradient::GradientStop;
+pub use self::image::{Image, Insets, Textures};
 pub use self::style::{Style, StyleEvent};
 
-use render::d2::Renderer;
-use render::Color;
+use self::border::{border, rounded_rect, shadow};
+use self::gradient::{corners, fan, linear_gradient, radial_gradient};
+use self::image::nine_slice;
+use render::d2::{Point, Rect, Renderer};
+use render::{Color, Context, Texture};
 use specs::prelude::*;
 
+use event::KeyboardFocus;
 use layout::Position;
-use tree::{self, Node, Root, WalkPostResult, WalkPreResult};
+use tree::{Node, Root};
+use widget::scroll::ScrollView;
+use widget::text::{Text, TextInput};
 use State;
 
-#[derive(Clone, Component, Debug, Deserialize)]
+#[derive(Clone, Component, Debug, Deserialize, PartialEq)]
 pub enum Brush {
     Color(Color),
-}
-
-impl PartialEq for Brush {
-    fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Brush::Color(lhs), Brush::Color(rhs)) => lhs == rhs,
-        }
-    }
+    Image(Image),
+    NineSlice { image: Image, insets: Insets },
+    /// A linear gradient between `stops`, which must be sorted by offset. Stops are sorted when
+    /// the brush is deserialized.
+    LinearGradient {
+        #[serde(deserialize_with = "gradient::de_stops")]
+        stops: Vec<GradientStop>,
+        #[serde(default)]
+        angle: f32,
+    },
+    /// A radial gradient between `stops`, which must be sorted by offset.
+    RadialGradient {
+        #[serde(deserialize_with = "gradient::de_stops")]
+        stops: Vec<GradientStop>,
+    },
 }
 
 pub struct DrawSystem;
@@ -32,30 +54,235 @@ impl DrawSystem {
 impl<'a> System<'a> for DrawSystem {
     type SystemData = (
         WriteExpect<'a, Renderer>,
+        Option<ReadExpect<'a, Context>>,
+        WriteExpect<'a, Textures>,
         ReadExpect<'a, Root>,
+        ReadExpect<'a, KeyboardFocus>,
         ReadStorage<'a, Node>,
         ReadStorage<'a, Brush>,
+        ReadStorage<'a, BorderStyle>,
         ReadStorage<'a, State>,
         ReadStorage<'a, Position>,
+        ReadStorage<'a, Text>,
+        ReadStorage<'a, TextInput>,
+        ReadStorage<'a, ScrollView>,
     );
 
-    fn run(&mut self, (mut renderer, root, nodes, brushes, states, poss): Self::SystemData) {
-        tree::walk::<(), _, _>(
-            root.entity(),
-            &nodes,
-            &mut |ent| {
-                if states.get(ent).map(State::needs_draw).unwrap_or(true) {
-                    if let Some(brush) = brushes.get(ent) {
-                        match *brush {
-                            Brush::Color(color) => if let Some(pos) = poss.get(ent) {
-                                renderer.draw_tris(&pos.tris(), color)
-                            },
-                        }
+    fn run(
+        &mut self,
+        (
+            mut renderer,
+            ctx,
+            mut textures,
+            root,
+            kb_focus,
+            nodes,
+            brushes,
+            borders,
+            states,
+            poss,
+            texts,
+            inputs,
+            scrolls,
+        ): Self::SystemData,
+    ) {
+        Painter {
+            renderer: &mut renderer,
+            ctx: ctx.as_ref().map(|ctx| &**ctx),
+            textures: &mut textures,
+            focus: kb_focus.entity(),
+            nodes: &nodes,
+            brushes: &brushes,
+            borders: &borders,
+            states: &states,
+            poss: &poss,
+            texts: &texts,
+            inputs: &inputs,
+            scrolls: &scrolls,
+        }.paint(root.entity());
+    }
+}
+
+struct Painter<'r, 'a: 'r> {
+    renderer: &'r mut Renderer,
+    // Absent when rendering in software, in which case only inserted textures are drawn.
+    ctx: Option<&'r Context>,
+    textures: &'r mut Textures,
+    focus: Entity,
+    nodes: &'r ReadStorage<'a, Node>,
+    brushes: &'r ReadStorage<'a, Brush>,
+    borders: &'r ReadStorage<'a, BorderStyle>,
+    states: &'r ReadStorage<'a, State>,
+    poss: &'r ReadStorage<'a, Position>,
+    texts: &'r ReadStorage<'a, Text>,
+    inputs: &'r ReadStorage<'a, TextInput>,
+    scrolls: &'r ReadStorage<'a, ScrollView>,
+}
+
+impl<'r, 'a> Painter<'r, 'a> {
+    fn paint(&mut self, ent: Entity) {
+        let (nodes, poss, scrolls) = (self.nodes, self.poss, self.scrolls);
+        let node = match nodes.get(ent) {
+            Some(node) => node,
+            None => {
+                error!("Dead node in tree: {:?}.", ent);
+                return;
+            }
+        };
+
+        let pos = poss.get(ent);
+        let draw = self.states.get(ent).map(State::needs_draw).unwrap_or(true);
+        if let (true, Some(pos)) = (draw, pos) {
+            let visible = match self.renderer.clip() {
+                Some(clip) => clip.intersect(&bounds(pos)).is_some(),
+                None => true,
+            };
+            if visible {
+                self.paint_entity(ent, pos);
+            }
+        }
+
+        let scroll = scrolls.get(ent);
+        if let (Some(_), Some(pos)) = (scroll, pos) {
+            self.renderer.push_clip(bounds(pos));
+        }
+
+        for &child in node.children() {
+            self.paint(child);
+        }
+
+        if let (Some(scroll), Some(pos)) = (scroll, pos) {
+            self.renderer.pop_clip();
+            if draw {
+                if let Some(content_pos) = poss.get(scroll.content()) {
+                    for bar in scroll.scrollbars(pos, content_pos) {
+                        self.fill(bar, None, scroll.scrollbar().unwrap());
                     }
                 }
-                WalkPreResult::Continue
-            },
-            &mut |_| WalkPostResult::Continue,
-        );
+            }
+        }
+    }
+
+    fn paint_entity(&mut self, ent: Entity, pos: &Position) {
+        let area = bounds(pos);
+        let style = self.borders.get(ent);
+        if let Some(style) = style {
+            if let Some(ref shad) = style.shadow {
+                self.renderer
+                    .draw_tris_colored(&shadow(area, style.radius, shad));
+            }
+        }
+        if let Some(brush) = self.brushes.get(ent) {
+            self.fill(area, style, brush);
+        }
+        if let Some(style) = style {
+            self.renderer.draw_tris_colored(&border(area, style));
+        }
+
+        if let Some(text) = self.texts.get(ent) {
+            let input = self.inputs.get(ent).filter(|_| self.focus == ent);
+            if let Some(input) = input {
+                draw_selection(self.renderer, input, text, pos);
+            }
+            self.renderer
+                .draw_glyphs(text.glyphs(pos), text.font().id(), text.color());
+            if let Some(input) = input {
+                draw_caret(self.renderer, input, text, pos);
+            }
+        }
+    }
+
+    /// Fills `area` with a brush. Image brushes ignore rounded corners.
+    fn fill(&mut self, area: Rect, style: Option<&BorderStyle>, brush: &Brush) {
+        let shape = match style {
+            Some(style) if style.is_rounded() => rounded_rect(area, style.radius),
+            _ => corners(area),
+        };
+
+        match *brush {
+            Brush::Color(color) => {
+                let mut tris = Vec::new();
+                fan(&shape, |_| color, &mut tris);
+                self.renderer.draw_tris_colored(&tris);
+            }
+            Brush::Image(ref image) => {
+                if let Some(texture) = texture(self.textures, self.ctx, image) {
+                    self.renderer
+                        .draw_image(area, texture, image.uv(), image.tint());
+                }
+            }
+            Brush::NineSlice {
+                ref image,
+                insets,
+            } => {
+                if let Some(texture) = texture(self.textures, self.ctx, image) {
+                    let [width, height] = texture.dimensions();
+                    let size = [
+                        width as f32 * image.uv().width(),
+                        height as f32 * image.uv().height(),
+                    ];
+                    for (rect, uv) in nine_slice(area, insets, image.uv(), size) {
+                        self.renderer.draw_image(rect, texture, uv, image.tint());
+                    }
+                }
+            }
+            Brush::LinearGradient { ref stops, angle } => self
+                .renderer
+                .draw_tris_colored(&linear_gradient(area, &shape, stops, angle)),
+            Brush::RadialGradient { ref stops } => self
+                .renderer
+                .draw_tris_colored(&radial_gradient(area, &shape, stops)),
+        }
+    }
+}
+
+fn bounds(pos: &Position) -> Rect {
+    Rect::new(pos.left(), pos.top(), pos.right(), pos.bottom())
+}
+
+fn draw_selection(renderer: &mut Renderer, input: &TextInput, text: &Text, pos: &Position) {
+    let sel = input.selection();
+    if sel.start != sel.end {
+        let offsets = text.caret_offsets(pos);
+        let start = input.text()[..sel.start].chars().count();
+        let end = start + input.text()[sel].chars().count();
+        if let (Some(&left), Some(&right)) = (offsets.get(start), offsets.get(end)) {
+            let (top, bottom) = text.line_extent(pos);
+            let color = Color::rgba(text.color().r, text.color().g, text.color().b, 0.35);
+            renderer.draw_tris(&rect(left, top, right, bottom), color);
+        }
+    }
+}
+
+fn draw_caret(renderer: &mut Renderer, input: &TextInput, text: &Text, pos: &Position) {
+    let offsets = text.caret_offsets(pos);
+    let caret = input.text()[..input.caret()].chars().count();
+    if let Some(&left) = offsets.get(caret) {
+        let (top, bottom) = text.line_extent(pos);
+        let left = left.round();
+        renderer.draw_tris(&rect(left, top, left + 1.0, bottom), text.color());
+    }
+}
+
+pub(crate) fn rect(left: f32, top: f32, right: f32, bottom: f32) -> [Point; 6] {
+    [
+        Point(left, bottom),
+        Point(left, top),
+        Point(right, bottom),
+        Point(right, bottom),
+        Point(left, top),
+        Point(right, top),
+    ]
+}
+
+fn texture<'t>(
+    textures: &'t mut Textures,
+    ctx: Option<&Context>,
+    image: &Image,
+) -> Option<&'t Texture> {
+    // Without a `Context`, as in the software renderer, only inserted textures can be drawn.
+    match ctx {
+        Some(ctx) => textures.get(ctx, image.path()),
+        None => textures.get_loaded(image.path()),
     }
 }
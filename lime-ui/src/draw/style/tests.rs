@@ -1,11 +1,12 @@
 use fnv::FnvHashMap;
+use render::d2::Rect;
 use render::Color;
 use serde_json as json;
 use specs::prelude::*;
 
 use super::*;
 use de;
-use draw::Brush;
+use draw::{Brush, GradientStop, Image, Insets};
 use tree::Root;
 use widget::button::{ButtonStyle, RadioButtonStyle, ToggleButtonStyle};
 
@@ -115,3 +116,190 @@ fn de() {
     assert_eq!(brushes.get(name_map["ent2"]), None);
     assert_eq!(brushes.get(name_map["style1"]), None);
 }
+
+#[test]
+fn de_image() {
+    const DATA: &'static str = r##"
+    {
+        "root": {
+            "Brush": {
+                "Image": "icons/close.png"
+            }
+        },
+        "icon": {
+            "Brush": {
+                "Image": {
+                    "path": "icons/all.png",
+                    "uv": { "left": 0.5, "top": 0, "right": 1, "bottom": 0.5 },
+                    "tint": "#FF0000"
+                }
+            }
+        }
+    }
+    "##;
+
+    let mut world = World::new();
+    let registry = de::Registry::new();
+    world.register::<Brush>();
+
+    let mut name_map = FnvHashMap::default();
+
+    Root::create(&mut world);
+    de::deserialize_with_names(
+        &mut json::Deserializer::from_str(DATA),
+        &registry,
+        &mut world.res,
+        &mut name_map,
+    ).unwrap();
+
+    let brushes = world.read_storage::<Brush>();
+    assert_eq!(
+        brushes.get(name_map["root"]),
+        Some(&Brush::Image(Image::new("icons/close.png")))
+    );
+    assert_eq!(
+        brushes.get(name_map["icon"]),
+        Some(&Brush::Image(
+            Image::new("icons/all.png")
+                .with_uv(Rect::new(0.5, 0.0, 1.0, 0.5))
+                .with_tint(Color::RED)
+        ))
+    );
+}
+
+#[test]
+fn de_nine_slice() {
+    const DATA: &'static str = r##"
+    {
+        "style": {
+            "ButtonStyle": {
+                "disabled": { "Color": "#808080" },
+                "normal": {
+                    "NineSlice": {
+                        "image": "panel.png",
+                        "insets": { "left": 6, "top": 6, "right": 6, "bottom": 6 }
+                    }
+                },
+                "focused": { "Image": "panel_focused.png" },
+                "pressed": { "Color": "#0000FF" }
+            }
+        }
+    }
+    "##;
+
+    let mut world = World::new();
+    let registry = de::Registry::new();
+    world.register::<ButtonStyle>();
+
+    let mut name_map = FnvHashMap::default();
+
+    Root::create(&mut world);
+    de::deserialize_with_names(
+        &mut json::Deserializer::from_str(DATA),
+        &registry,
+        &mut world.res,
+        &mut name_map,
+    ).unwrap();
+
+    let styles = world.read_storage::<ButtonStyle>();
+    let style = styles.get(name_map["style"]).unwrap();
+    assert_eq!(
+        style.normal,
+        Brush::NineSlice {
+            image: Image::new("panel.png"),
+            insets: Insets::uniform(6.0),
+        }
+    );
+    assert_eq!(style.focused, Brush::Image(Image::new("panel_focused.png")));
+}
+
+#[test]
+fn de_gradient() {
+    const DATA: &'static str = r##"
+    {
+        "linear": {
+            "Brush": {
+                "LinearGradient": {
+                    "stops": [
+                        { "offset": 0, "color": "#FF0000" },
+                        { "offset": 1, "color": "#0000FF" }
+                    ],
+                    "angle": 45
+                }
+            }
+        },
+        "radial": {
+            "Brush": {
+                "RadialGradient": {
+                    "stops": [
+                        { "offset": 0, "color": "#00FF00" },
+                        { "offset": 1, "color": "#0000FF" }
+                    ]
+                }
+            }
+        }
+    }
+    "##;
+
+    let mut world = World::new();
+    let registry = de::Registry::new();
+    world.register::<Brush>();
+
+    let mut name_map = FnvHashMap::default();
+
+    Root::create(&mut world);
+    de::deserialize_with_names(
+        &mut json::Deserializer::from_str(DATA),
+        &registry,
+        &mut world.res,
+        &mut name_map,
+    ).unwrap();
+
+    let brushes = world.read_storage::<Brush>();
+    assert_eq!(
+        brushes.get(name_map["linear"]),
+        Some(&Brush::LinearGradient {
+            stops: vec![
+                GradientStop::new(0.0, Color::RED),
+                GradientStop::new(1.0, Color::BLUE),
+            ],
+            angle: 45.0,
+        })
+    );
+    assert_eq!(
+        brushes.get(name_map["radial"]),
+        Some(&Brush::RadialGradient {
+            stops: vec![
+                GradientStop::new(0.0, Color::GREEN),
+                GradientStop::new(1.0, Color::BLUE),
+            ],
+        })
+    );
+}
+
+#[test]
+fn de_gradient_stops() {
+    let brush: Brush = json::from_str(
+        r##"{
+            "RadialGradient": {
+                "stops": [
+                    { "offset": 1, "color": "#0000FF" },
+                    { "offset": 0, "color": "#FF0000" },
+                    { "offset": 0.5, "color": "#00FF00" }
+                ]
+            }
+        }"##,
+    ).unwrap();
+    assert_eq!(
+        brush,
+        Brush::RadialGradient {
+            stops: vec![
+                GradientStop::new(0.0, Color::RED),
+                GradientStop::new(0.5, Color::GREEN),
+                GradientStop::new(1.0, Color::BLUE),
+            ],
+        }
+    );
+
+    assert!(json::from_str::<Brush>(r#"{ "LinearGradient": { "stops": [] } }"#).is_err());
+}
@@ -0,0 +1,200 @@
+use render::d2::{Point, Rect};
+use render::Color;
+use serde_json as json;
+
+use super::*;
+
+#[test]
+fn nine_slice_quads() {
+    let insets = Insets {
+        left: 4.0,
+        top: 8.0,
+        right: 4.0,
+        bottom: 8.0,
+    };
+    let quads = nine_slice(
+        Rect::new(10.0, 20.0, 110.0, 70.0),
+        insets,
+        Rect::new(0.0, 0.0, 1.0, 1.0),
+        [16.0, 32.0],
+    );
+
+    assert_eq!(quads.len(), 9);
+    assert_eq!(quads[0].0, Rect::new(10.0, 20.0, 14.0, 28.0));
+    assert_eq!(quads[0].1, Rect::new(0.0, 0.0, 0.25, 0.25));
+    assert_eq!(quads[4].0, Rect::new(14.0, 28.0, 106.0, 62.0));
+    assert_eq!(quads[4].1, Rect::new(0.25, 0.25, 0.75, 0.75));
+    assert_eq!(quads[8].0, Rect::new(106.0, 62.0, 110.0, 70.0));
+    assert_eq!(quads[8].1, Rect::new(0.75, 0.75, 1.0, 1.0));
+}
+
+#[test]
+fn nine_slice_small_area() {
+    let quads = nine_slice(
+        Rect::new(0.0, 0.0, 4.0, 100.0),
+        Insets::uniform(4.0),
+        Rect::new(0.5, 0.0, 1.0, 0.5),
+        [8.0, 8.0],
+    );
+
+    // The horizontal borders are halved to fit, leaving no room for the middle column.
+    assert_eq!(quads.len(), 6);
+    assert_eq!(quads[0].0, Rect::new(0.0, 0.0, 2.0, 4.0));
+    assert_eq!(quads[0].1, Rect::new(0.5, 0.0, 0.75, 0.25));
+    assert_eq!(quads[1].0, Rect::new(2.0, 0.0, 4.0, 4.0));
+    assert_eq!(quads[1].1, Rect::new(0.75, 0.0, 1.0, 0.25));
+}
+
+fn tri_area(tris: &[(Point, Color)]) -> f32 {
+    tris.chunks(3)
+        .map(|t| {
+            let (a, b, c) = (t[0].0, t[1].0, t[2].0);
+            ((b.0 - a.0) * (c.1 - a.1) - (c.0 - a.0) * (b.1 - a.1)).abs() / 2.0
+        }).sum()
+}
+
+#[test]
+fn linear_gradient_colors() {
+    let stops = [
+        GradientStop::new(0.0, Color::RED),
+        GradientStop::new(0.5, Color::GREEN),
+        GradientStop::new(1.0, Color::BLUE),
+    ];
+    let rect = Rect::new(0.0, 0.0, 100.0, 50.0);
+    let tris = linear_gradient(rect, &corners(rect), &stops, 0.0);
+
+    assert_eq!(tris.len() % 3, 0);
+    assert_ulps_eq!(tri_area(&tris), 5000.0);
+    for &(point, color) in &tris {
+        let expected = if point.0 <= 50.0 {
+            let t = point.0 / 50.0;
+            Color::rgb(1.0 - t, t, 0.0)
+        } else {
+            let t = (point.0 - 50.0) / 50.0;
+            Color::rgb(0.0, 1.0 - t, t)
+        };
+        assert_eq!(color, expected);
+    }
+    assert!(
+        tris.iter()
+            .any(|&(point, color)| point == Point(50.0, 0.0) && color == Color::GREEN)
+    );
+}
+
+#[test]
+fn linear_gradient_angle() {
+    let stops = [
+        GradientStop::new(0.0, Color::RED),
+        GradientStop::new(1.0, Color::BLUE),
+    ];
+    let rect = Rect::new(0.0, 0.0, 100.0, 50.0);
+    let tris = linear_gradient(rect, &corners(rect), &stops, 90.0);
+
+    assert_relative_eq!(tri_area(&tris), 5000.0, epsilon = 1e-2);
+    for &(point, color) in &tris {
+        let t = point.1 / 50.0;
+        assert_relative_eq!(color.r, 1.0 - t, epsilon = 1e-5);
+        assert_relative_eq!(color.b, t, epsilon = 1e-5);
+    }
+}
+
+#[test]
+fn radial_gradient_covers_area() {
+    let stops = [
+        GradientStop::new(0.0, Color::RED),
+        GradientStop::new(0.5, Color::GREEN),
+        GradientStop::new(1.0, Color::BLUE),
+    ];
+    let rect = Rect::new(10.0, 10.0, 110.0, 60.0);
+    let tris = radial_gradient(rect, &corners(rect), &stops);
+
+    assert_eq!(tris.len() % 3, 0);
+    assert_relative_eq!(tri_area(&tris), 5000.0, epsilon = 0.1);
+    for &(point, _) in &tris {
+        assert!(rect.left - 1e-3 <= point.0 && point.0 <= rect.right + 1e-3);
+        assert!(rect.top - 1e-3 <= point.1 && point.1 <= rect.bottom + 1e-3);
+    }
+    assert!(
+        tris.iter()
+            .any(|&(point, color)| point == Point(60.0, 35.0) && color == Color::RED)
+    );
+}
+
+#[test]
+fn gradient_no_stops() {
+    let rect = Rect::new(0.0, 0.0, 100.0, 100.0);
+    assert!(linear_gradient(rect, &corners(rect), &[], 45.0).is_empty());
+    assert!(radial_gradient(rect, &corners(rect), &[]).is_empty());
+}
+
+#[test]
+fn rounded_rect_outline() {
+    let area = Rect::new(0.0, 0.0, 100.0, 40.0);
+    let square = rounded_rect(area, [0.0; 4]);
+    let rounded = rounded_rect(area, [10.0, 0.0, 50.0, 0.0]);
+    assert_eq!(square.len(), rounded.len());
+
+    for &p in &square {
+        assert!(corners(area).contains(&p));
+    }
+    // Radii larger than half the shortest side are clamped.
+    assert!(rounded.contains(&Point(0.0, 10.0)));
+    assert!(rounded.contains(&Point(10.0, 0.0)));
+    assert!(rounded.contains(&Point(100.0, 20.0)));
+    assert!(rounded.contains(&Point(80.0, 40.0)));
+    assert!(!rounded.contains(&Point(100.0, 40.0)));
+}
+
+#[test]
+fn border_and_shadow_area() {
+    let area = Rect::new(0.0, 0.0, 100.0, 50.0);
+    let style = BorderStyle {
+        width: 2.0,
+        color: Some(Color::RED),
+        shadow: Some(Shadow {
+            offset: Point(4.0, 4.0),
+            blur: 0.0,
+            color: Color::rgba(0.0, 0.0, 0.0, 0.5),
+        }),
+        ..Default::default()
+    };
+
+    let outline = border(area, &style);
+    assert_relative_eq!(tri_area(&outline), 100.0 * 50.0 - 96.0 * 46.0, epsilon = 1e-2);
+    assert!(outline.iter().all(|&(_, color)| color == Color::RED));
+
+    let shadow = shadow(area, style.radius, style.shadow.as_ref().unwrap());
+    assert_relative_eq!(tri_area(&shadow), 5000.0, epsilon = 1e-2);
+    assert!(shadow.iter().all(|&(p, _)| p.0 >= 4.0 && p.1 >= 4.0));
+
+    let no_color = BorderStyle {
+        width: 2.0,
+        ..Default::default()
+    };
+    assert!(border(area, &no_color).is_empty());
+}
+
+#[test]
+fn de_border_style() {
+    let style: BorderStyle = json::from_str(
+        r##"{
+            "radius": [4, 4, 0, 0],
+            "width": 1,
+            "color": "#FF0000",
+            "shadow": { "offset": [0, 2], "blur": 4, "color": "#000000" }
+        }"##,
+    ).unwrap();
+    assert_eq!(
+        style,
+        BorderStyle {
+            radius: [4.0, 4.0, 0.0, 0.0],
+            width: 1.0,
+            color: Some(Color::RED),
+            shadow: Some(Shadow {
+                offset: Point(0.0, 2.0),
+                blur: 4.0,
+                color: Color::rgb(0.0, 0.0, 0.0),
+            }),
+        }
+    );
+}
@@ -1,31 +1,85 @@
 use specs::prelude::*;
 use winit::{ElementState, KeyboardInput, ModifiersState, VirtualKeyCode};
 
-use tree::Root;
+use tree::{self, Node, Root, WalkPostResult, WalkPreResult};
+use State;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum KeyboardEvent {
     KeyUp(VirtualKeyCode, ModifiersState),
     KeyDown(VirtualKeyCode, ModifiersState),
     Char(char),
+    FocusGained,
+    FocusLost,
 }
 
 pub struct KeyboardFocus {
     pub(in event) entity: Entity,
+    // The entity which was last sent a `FocusGained` event.
+    pub(in event) current: Entity,
 }
 
+/// Marks an entity as able to receive keyboard focus when cycling with Tab and Shift-Tab.
+#[derive(Copy, Clone, Component, Debug, Default, Deserialize)]
+#[storage(NullStorage)]
+pub struct Focusable;
+
 impl KeyboardFocus {
     pub(crate) fn new(root: &Root) -> Self {
         KeyboardFocus {
             entity: root.entity(),
+            current: root.entity(),
         }
     }
 
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// Move focus to `entity`. The `FocusLost` and `FocusGained` events are sent the next time
+    /// the `EventSystem` runs.
     pub fn set_entity(&mut self, entity: Entity) {
         self.entity = entity;
     }
 }
 
+/// Find the next focusable entity after `cur` in tree order, wrapping around at the end.
+pub(in event) fn next_focus(
+    root: Entity,
+    cur: Entity,
+    reverse: bool,
+    nodes: &ReadStorage<Node>,
+    focusables: &ReadStorage<Focusable>,
+    states: &ReadStorage<State>,
+) -> Option<Entity> {
+    let mut ents = Vec::new();
+    tree::walk::<(), _, _>(
+        root,
+        nodes,
+        &mut |ent| {
+            if focusables.contains(ent) && states.get(ent).map(State::needs_events).unwrap_or(true)
+            {
+                ents.push(ent);
+            }
+            WalkPreResult::Continue
+        },
+        &mut |_| WalkPostResult::Continue,
+    );
+
+    if ents.is_empty() {
+        return None;
+    }
+
+    let len = ents.len();
+    let idx = match ents.iter().position(|&ent| ent == cur) {
+        Some(idx) if reverse => (idx + len - 1) % len,
+        Some(idx) => (idx + 1) % len,
+        None if reverse => len - 1,
+        None => 0,
+    };
+    Some(ents[idx])
+}
+
 impl KeyboardEvent {
     pub(in event) fn from_input(input: KeyboardInput) -> Option<Self> {
         if let Some(keycode) = input.virtual_keycode {
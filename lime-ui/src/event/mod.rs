@@ -3,10 +3,12 @@ pub mod tests;
 
 mod keyboard;
 mod mouse;
+mod propagation;
 mod sys;
 
-pub use self::keyboard::{KeyboardEvent, KeyboardFocus};
+pub use self::keyboard::{Focusable, KeyboardEvent, KeyboardFocus};
 pub use self::mouse::{MouseEvent, MouseFocus};
+pub use self::propagation::{Propagation, StopPropagation};
 pub use self::sys::EventSystem;
 
 use specs::prelude::*;
@@ -15,6 +17,21 @@ use specs::prelude::*;
 pub struct Event {
     kind: EventKind,
     entity: Entity,
+    target: Entity,
+    phase: EventPhase,
+    // Identifies the dispatch this event was sent in, and its position in the propagation path.
+    // Events which do not propagate have a `dispatch` of zero.
+    dispatch: u64,
+    step: u32,
+}
+
+/// The stage of propagation an event is in. Events are first captured by each ancestor of the
+/// target, starting at the root, then delivered to the target, then bubble back up to the root.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EventPhase {
+    Capture,
+    Target,
+    Bubble,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -24,10 +41,20 @@ pub enum EventKind {
 }
 
 impl Event {
+    /// The entity the event is currently being delivered to.
     pub fn entity(&self) -> Entity {
         self.entity
     }
 
+    /// The entity the event was originally sent to.
+    pub fn target(&self) -> Entity {
+        self.target
+    }
+
+    pub fn phase(&self) -> EventPhase {
+        self.phase
+    }
+
     pub fn kind(&self) -> EventKind {
         self.kind
     }
@@ -35,14 +62,45 @@ impl Event {
     fn keyboard(entity: Entity, event: KeyboardEvent) -> Self {
         Event {
             entity,
+            target: entity,
+            phase: EventPhase::Target,
             kind: EventKind::Keyboard(event),
+            dispatch: 0,
+            step: 0,
         }
     }
 
     fn mouse(entity: Entity, event: MouseEvent) -> Self {
         Event {
             entity,
+            target: entity,
+            phase: EventPhase::Target,
             kind: EventKind::Mouse(event),
+            dispatch: 0,
+            step: 0,
+        }
+    }
+
+    fn with_phase(self, entity: Entity, phase: EventPhase, step: u32) -> Self {
+        Event {
+            entity,
+            phase,
+            step,
+            ..self
+        }
+    }
+}
+
+impl EventKind {
+    /// Whether the event has capture and bubble phases. Focus changes and the mouse entering or
+    /// leaving an entity are only sent to the target.
+    pub fn bubbles(&self) -> bool {
+        match *self {
+            EventKind::Keyboard(KeyboardEvent::FocusGained)
+            | EventKind::Keyboard(KeyboardEvent::FocusLost)
+            | EventKind::Mouse(MouseEvent::Enter)
+            | EventKind::Mouse(MouseEvent::Exit) => false,
+            _ => true,
         }
     }
 }
@@ -1,11 +1,15 @@
 use render::d2::Point;
 use specs::prelude::*;
-use winit::{ElementState, ModifiersState, MouseButton};
+use winit::{ElementState, ModifiersState, MouseButton, MouseScrollDelta};
 
 use layout::Position;
 use tree::{self, Node, WalkPostResult, WalkPreResult};
+use widget::scroll::ScrollView;
 use State;
 
+// The distance to scroll for each line reported by the mouse wheel.
+const LINE_HEIGHT: f32 = 40.0;
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum MouseEvent {
     Enter,
@@ -14,6 +18,9 @@ pub enum MouseEvent {
     MoveRaw(f64, f64),
     ButtonUp(MouseButton, ModifiersState),
     ButtonDown(MouseButton, ModifiersState),
+    /// Horizontal and vertical scroll distance in logical pixels. Positive values mean the wheel
+    /// was moved left or up.
+    Scroll(f32, f32, ModifiersState),
 }
 
 pub struct MouseFocus {
@@ -32,6 +39,10 @@ impl MouseFocus {
     pub fn entity(&self) -> Option<Entity> {
         self.entity
     }
+
+    pub fn point(&self) -> Point {
+        self.point
+    }
 }
 
 impl MouseEvent {
@@ -45,6 +56,17 @@ impl MouseEvent {
             ElementState::Released => MouseEvent::ButtonUp(button, modifiers),
         }
     }
+
+    pub(in event) fn from_wheel(delta: MouseScrollDelta, modifiers: ModifiersState) -> Self {
+        match delta {
+            MouseScrollDelta::LineDelta(x, y) => {
+                MouseEvent::Scroll(x * LINE_HEIGHT, y * LINE_HEIGHT, modifiers)
+            }
+            MouseScrollDelta::PixelDelta(pos) => {
+                MouseEvent::Scroll(pos.x as f32, pos.y as f32, modifiers)
+            }
+        }
+    }
 }
 
 pub(in event) fn hit_test(
@@ -53,15 +75,31 @@ pub(in event) fn hit_test(
     nodes: &ReadStorage<Node>,
     poss: &ReadStorage<Position>,
     states: &ReadStorage<State>,
+    scrolls: &ReadStorage<ScrollView>,
 ) -> Option<Entity> {
-    tree::walk_rev::<Entity, _, _>(root, nodes, &mut |_| WalkPreResult::Continue, &mut |ent| {
-        if states.get(ent).map(State::needs_draw).unwrap_or(true) {
-            if let Some(pos) = poss.get(ent) {
-                if pos.contains(point) {
-                    return WalkPostResult::Break(ent);
+    tree::walk_rev::<Entity, _, _>(
+        root,
+        nodes,
+        &mut |ent| {
+            // Children of scroll views are clipped to the view.
+            if scrolls.contains(ent) {
+                if let Some(pos) = poss.get(ent) {
+                    if !pos.contains(point) {
+                        return WalkPreResult::Skip;
+                    }
                 }
             }
-        }
-        WalkPostResult::Continue
-    })
+            WalkPreResult::Continue
+        },
+        &mut |ent| {
+            if states.get(ent).map(State::needs_draw).unwrap_or(true) {
+                if let Some(pos) = poss.get(ent) {
+                    if pos.contains(point) {
+                        return WalkPostResult::Break(ent);
+                    }
+                }
+            }
+            WalkPostResult::Continue
+        },
+    )
 }
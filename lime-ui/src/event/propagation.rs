@@ -0,0 +1,122 @@
+use fnv::FnvHashMap;
+use shrev::EventChannel;
+use specs::prelude::*;
+
+use event::{Event, EventKind, EventPhase};
+use tree::Node;
+use State;
+
+/// Stops events from propagating past an entity.
+///
+/// If `capture` is set, events captured by this entity are not passed on to its descendants. If
+/// `bubble` is set, events bubbling up through this entity are not passed on to its ancestors.
+/// Handlers can also stop individual events with the `Propagation` resource.
+#[derive(Copy, Clone, Component, Debug, Default, Deserialize, Eq, PartialEq)]
+#[storage(HashMapStorage)]
+pub struct StopPropagation {
+    #[serde(default)]
+    pub capture: bool,
+    #[serde(default)]
+    pub bubble: bool,
+}
+
+/// A resource which lets event handlers stop an event from propagating any further.
+///
+/// Every phase of an event is written to the `EventChannel<Event>` at once, so a handler which
+/// stops an event cannot take back the later phases. Instead, handlers should skip events for
+/// which `is_stopped` returns true. Other handlers of the phase which called `stop` still receive
+/// the event, as do handlers which read it before `stop` was called.
+#[derive(Debug, Default)]
+pub struct Propagation {
+    next_dispatch: u64,
+    // The step each stopped dispatch was stopped at.
+    stopped: FnvHashMap<u64, u32>,
+}
+
+impl Propagation {
+    pub fn new() -> Self {
+        Propagation::default()
+    }
+
+    /// Stop `event` from propagating past the entity it is currently being delivered to.
+    pub fn stop(&mut self, event: &Event) {
+        if event.dispatch != 0 {
+            let step = self.stopped.entry(event.dispatch).or_insert(event.step);
+            *step = (*step).min(event.step);
+        }
+    }
+
+    /// Whether an earlier phase of `event` was stopped.
+    pub fn is_stopped(&self, event: &Event) -> bool {
+        self.stopped
+            .get(&event.dispatch)
+            .map_or(false, |&step| event.step > step)
+    }
+
+    pub(in event) fn clear(&mut self) {
+        self.stopped.clear();
+    }
+}
+
+/// Send an event to `target`, preceded by the capture phase from the root down to its parent and
+/// followed by the bubble phase back up to the root.
+pub(in event) fn dispatch(
+    target: Entity,
+    kind: EventKind,
+    events: &mut EventChannel<Event>,
+    nodes: &ReadStorage<Node>,
+    states: &ReadStorage<State>,
+    stops: &ReadStorage<StopPropagation>,
+    propagation: &mut Propagation,
+) {
+    propagation.next_dispatch += 1;
+    let event = Event {
+        kind,
+        entity: target,
+        target,
+        phase: EventPhase::Target,
+        dispatch: propagation.next_dispatch,
+        step: 0,
+    };
+
+    if !kind.bubbles() {
+        events.single_write(event);
+        return;
+    }
+
+    let mut path = Vec::new();
+    let mut cur = nodes.get(target).and_then(Node::parent);
+    while let Some(ent) = cur {
+        path.push(ent);
+        cur = nodes.get(ent).and_then(Node::parent);
+    }
+
+    let needs_events = |ent: Entity| states.get(ent).map(State::needs_events).unwrap_or(true);
+    let stop = |ent: Entity| stops.get(ent).cloned().unwrap_or_default();
+
+    let mut step = 0;
+    for &ent in path.iter().rev() {
+        if needs_events(ent) {
+            events.single_write(event.with_phase(ent, EventPhase::Capture, step));
+        }
+        if stop(ent).capture {
+            return;
+        }
+        step += 1;
+    }
+
+    events.single_write(event.with_phase(target, EventPhase::Target, step));
+    if stop(target).bubble {
+        return;
+    }
+
+    for &ent in path.iter() {
+        step += 1;
+        if needs_events(ent) {
+            events.single_write(event.with_phase(ent, EventPhase::Bubble, step));
+        }
+        if stop(ent).bubble {
+            return;
+        }
+    }
+}
@@ -1,13 +1,23 @@
+use std::mem;
+
 use render::d2::Point;
+use render::DpiFactor;
 use shrev::{EventChannel, ReaderId};
 use specs::prelude::*;
 use specs_mirror::{StorageExt, StorageMutExt};
 
-use winit::{self, DeviceEvent, ElementState, ModifiersState, MouseButton, WindowEvent};
+use winit::{
+    self, DeviceEvent, ElementState, ModifiersState, MouseButton, MouseScrollDelta,
+    VirtualKeyCode, WindowEvent,
+};
 
-use event::{mouse, Event, KeyboardEvent, KeyboardFocus, MouseEvent, MouseFocus};
+use event::{
+    keyboard, mouse, propagation, Event, EventKind, Focusable, KeyboardEvent, KeyboardFocus,
+    MouseEvent, MouseFocus, Propagation, StopPropagation,
+};
 use layout::Position;
 use tree::{Node, Root};
+use widget::scroll::ScrollView;
 use {State, StateEvent};
 
 pub struct EventSystem {
@@ -27,29 +37,66 @@ impl EventSystem {
     }
 }
 
-fn keyboard_focus(root: &Root, ents: &Entities, kb_focus: &KeyboardFocus) -> Entity {
-    if ents.is_alive(kb_focus.entity) {
-        kb_focus.entity
-    } else {
+fn keyboard_focus(
+    root: &Root,
+    ents: &Entities,
+    kb_focus: &mut KeyboardFocus,
+    events: &mut EventChannel<Event>,
+) -> Entity {
+    if !ents.is_alive(kb_focus.entity) {
         let root_ent = root.entity();
         debug_assert!(ents.is_alive(root_ent));
-        root_ent
+        kb_focus.entity = root_ent;
+    }
+
+    if kb_focus.current != kb_focus.entity {
+        let old = mem::replace(&mut kb_focus.current, kb_focus.entity);
+        if ents.is_alive(old) {
+            events.single_write(Event::keyboard(old, KeyboardEvent::FocusLost));
+        }
+        events.single_write(Event::keyboard(kb_focus.entity, KeyboardEvent::FocusGained));
     }
+
+    kb_focus.entity
 }
 
 fn keyboard_input(
     input: winit::KeyboardInput,
     root: &Root,
     ents: &Entities,
-    kb_focus: &KeyboardFocus,
+    kb_focus: &mut KeyboardFocus,
     events: &mut EventChannel<Event>,
+    nodes: &ReadStorage<Node>,
+    focusables: &ReadStorage<Focusable>,
     states: &ReadStorage<State>,
+    stops: &ReadStorage<StopPropagation>,
+    propagation: &mut Propagation,
 ) {
-    if let Some(event) = KeyboardEvent::from_input(input) {
-        let ent = keyboard_focus(root, ents, kb_focus);
-        if states.get(ent).map(State::needs_events).unwrap_or(true) {
-            events.single_write(Event::keyboard(ent, event));
+    match KeyboardEvent::from_input(input) {
+        Some(KeyboardEvent::KeyDown(VirtualKeyCode::Tab, modifiers)) => {
+            let cur = keyboard_focus(root, ents, kb_focus, events);
+            let next = keyboard::next_focus(
+                root.entity(),
+                cur,
+                modifiers.shift,
+                nodes,
+                focusables,
+                states,
+            );
+            if let Some(next) = next {
+                kb_focus.set_entity(next);
+                keyboard_focus(root, ents, kb_focus, events);
+            }
+        }
+        Some(KeyboardEvent::KeyUp(VirtualKeyCode::Tab, _)) => (),
+        Some(event) => {
+            let ent = keyboard_focus(root, ents, kb_focus, events);
+            if states.get(ent).map(State::needs_events).unwrap_or(true) {
+                let kind = EventKind::Keyboard(event);
+                propagation::dispatch(ent, kind, events, nodes, states, stops, propagation);
+            }
         }
+        None => (),
     }
 }
 
@@ -57,13 +104,17 @@ fn received_character(
     chr: char,
     root: &Root,
     ents: &Entities,
-    kb_focus: &KeyboardFocus,
+    kb_focus: &mut KeyboardFocus,
     events: &mut EventChannel<Event>,
+    nodes: &ReadStorage<Node>,
     states: &ReadStorage<State>,
+    stops: &ReadStorage<StopPropagation>,
+    propagation: &mut Propagation,
 ) {
-    let ent = keyboard_focus(root, ents, kb_focus);
+    let ent = keyboard_focus(root, ents, kb_focus, events);
     if states.get(ent).map(State::needs_events).unwrap_or(true) {
-        events.single_write(Event::keyboard(ent, KeyboardEvent::Char(chr)));
+        let kind = EventKind::Keyboard(KeyboardEvent::Char(chr));
+        propagation::dispatch(ent, kind, events, nodes, states, stops, propagation);
     }
 }
 
@@ -74,8 +125,9 @@ fn cursor_update(
     nodes: &ReadStorage<Node>,
     poss: &ReadStorage<Position>,
     states: &ReadStorage<State>,
+    scrolls: &ReadStorage<ScrollView>,
 ) {
-    let entity = mouse::hit_test(root.entity(), hover.point, &nodes, &poss, &states);
+    let entity = mouse::hit_test(root.entity(), hover.point, nodes, poss, states, scrolls);
 
     if hover.entity != entity {
         if let Some(old) = hover.entity {
@@ -101,12 +153,16 @@ fn cursor_moved(
     nodes: &ReadStorage<Node>,
     poss: &ReadStorage<Position>,
     states: &ReadStorage<State>,
+    scrolls: &ReadStorage<ScrollView>,
+    stops: &ReadStorage<StopPropagation>,
+    propagation: &mut Propagation,
 ) {
     hover.point = point;
-    cursor_update(root, hover, events, nodes, poss, states);
+    cursor_update(root, hover, events, nodes, poss, states, scrolls);
     if let Some(ent) = hover.entity {
         if states.get(ent).map(State::needs_events).unwrap_or(true) {
-            events.single_write(Event::mouse(ent, MouseEvent::Move(hover.point, modifiers)));
+            let kind = EventKind::Mouse(MouseEvent::Move(hover.point, modifiers));
+            propagation::dispatch(ent, kind, events, nodes, states, stops, propagation);
         }
     }
 }
@@ -130,54 +186,134 @@ fn mouse_input(
     modifiers: ModifiersState,
     hover: &mut MouseFocus,
     events: &mut EventChannel<Event>,
+    nodes: &ReadStorage<Node>,
+    states: &ReadStorage<State>,
+    stops: &ReadStorage<StopPropagation>,
+    propagation: &mut Propagation,
 ) {
     if let Some(ent) = hover.entity {
-        events.single_write(Event::mouse(
-            ent,
-            MouseEvent::from_input(state, button, modifiers),
-        ));
+        let kind = EventKind::Mouse(MouseEvent::from_input(state, button, modifiers));
+        propagation::dispatch(ent, kind, events, nodes, states, stops, propagation);
     }
 }
 
-fn mouse_motion((x, y): (f64, f64), hover: &mut MouseFocus, events: &mut EventChannel<Event>) {
+fn mouse_wheel(
+    delta: MouseScrollDelta,
+    modifiers: ModifiersState,
+    hover: &mut MouseFocus,
+    events: &mut EventChannel<Event>,
+    nodes: &ReadStorage<Node>,
+    states: &ReadStorage<State>,
+    stops: &ReadStorage<StopPropagation>,
+    propagation: &mut Propagation,
+) {
     if let Some(ent) = hover.entity {
-        events.single_write(Event::mouse(ent, MouseEvent::MoveRaw(x, y)));
+        if states.get(ent).map(State::needs_events).unwrap_or(true) {
+            let kind = EventKind::Mouse(MouseEvent::from_wheel(delta, modifiers));
+            propagation::dispatch(ent, kind, events, nodes, states, stops, propagation);
+        }
+    }
+}
+
+fn mouse_motion(
+    (x, y): (f64, f64),
+    hover: &mut MouseFocus,
+    events: &mut EventChannel<Event>,
+    nodes: &ReadStorage<Node>,
+    states: &ReadStorage<State>,
+    stops: &ReadStorage<StopPropagation>,
+    propagation: &mut Propagation,
+) {
+    if let Some(ent) = hover.entity {
+        let kind = EventKind::Mouse(MouseEvent::MoveRaw(x, y));
+        propagation::dispatch(ent, kind, events, nodes, states, stops, propagation);
     }
 }
 
 impl<'a> System<'a> for EventSystem {
     type SystemData = (
         ReadExpect<'a, EventChannel<winit::Event>>,
+        ReadExpect<'a, DpiFactor>,
         ReadExpect<'a, Root>,
         Entities<'a>,
-        ReadExpect<'a, KeyboardFocus>,
+        WriteExpect<'a, KeyboardFocus>,
         WriteExpect<'a, MouseFocus>,
         WriteExpect<'a, EventChannel<Event>>,
         ReadStorage<'a, Node>,
         ReadStorage<'a, Position>,
         ReadStorage<'a, State>,
+        ReadStorage<'a, Focusable>,
+        ReadStorage<'a, StopPropagation>,
+        ReadStorage<'a, ScrollView>,
+        WriteExpect<'a, Propagation>,
     );
 
     fn run(
         &mut self,
-        (win_events, root, ents, kb_focus, mut hover, mut events, nodes, poss, states): Self::SystemData,
-){
+        (
+            win_events,
+            dpi_factor,
+            root,
+            ents,
+            mut kb_focus,
+            mut hover,
+            mut events,
+            nodes,
+            poss,
+            states,
+            focusables,
+            stops,
+            scrolls,
+            mut propagation,
+        ): Self::SystemData,
+    ) {
+        // Handlers have read every event dispatched in the previous frame.
+        propagation.clear();
+        keyboard_focus(&root, &ents, &mut kb_focus, &mut events);
+
         if states
             .read_events(&mut self.state_rx)
             .any(|ev| ev.needs_draw_changed().is_some())
         {
-            cursor_update(&root, &mut hover, &mut events, &nodes, &poss, &states)
+            cursor_update(
+                &root,
+                &mut hover,
+                &mut events,
+                &nodes,
+                &poss,
+                &states,
+                &scrolls,
+            )
         }
 
+        // The renderer updates the DPI factor after this system has read this frame's events.
+        let mut dpi_factor = dpi_factor.get();
         for win_event in win_events.read(&mut self.event_rx) {
             match win_event {
                 winit::Event::WindowEvent { ref event, .. } => match *event {
-                    WindowEvent::KeyboardInput { input, .. } => {
-                        keyboard_input(input, &root, &ents, &kb_focus, &mut events, &states)
-                    }
-                    WindowEvent::ReceivedCharacter(chr) => {
-                        received_character(chr, &root, &ents, &kb_focus, &mut events, &states)
-                    }
+                    WindowEvent::KeyboardInput { input, .. } => keyboard_input(
+                        input,
+                        &root,
+                        &ents,
+                        &mut kb_focus,
+                        &mut events,
+                        &nodes,
+                        &focusables,
+                        &states,
+                        &stops,
+                        &mut propagation,
+                    ),
+                    WindowEvent::ReceivedCharacter(chr) => received_character(
+                        chr,
+                        &root,
+                        &ents,
+                        &mut kb_focus,
+                        &mut events,
+                        &nodes,
+                        &states,
+                        &stops,
+                        &mut propagation,
+                    ),
                     WindowEvent::CursorMoved {
                         position,
                         modifiers,
@@ -192,21 +328,68 @@ impl<'a> System<'a> for EventSystem {
                             &nodes,
                             &poss,
                             &states,
+                            &scrolls,
+                            &stops,
+                            &mut propagation,
                         );
                     }
                     WindowEvent::CursorLeft { .. } => cursor_left(&mut hover, &mut events, &states),
+                    WindowEvent::HiDpiFactorChanged(factor) => {
+                        // The cursor stays at the same physical position, so its logical
+                        // position changes.
+                        let scale = (dpi_factor / factor) as f32;
+                        dpi_factor = factor;
+                        hover.point = Point(hover.point.0 * scale, hover.point.1 * scale);
+                        cursor_update(
+                            &root,
+                            &mut hover,
+                            &mut events,
+                            &nodes,
+                            &poss,
+                            &states,
+                            &scrolls,
+                        );
+                    }
+                    WindowEvent::MouseWheel {
+                        delta, modifiers, ..
+                    } => mouse_wheel(
+                        delta,
+                        modifiers,
+                        &mut hover,
+                        &mut events,
+                        &nodes,
+                        &states,
+                        &stops,
+                        &mut propagation,
+                    ),
                     WindowEvent::MouseInput {
                         state,
                         button,
                         modifiers,
                         ..
-                    } => mouse_input(state, button, modifiers, &mut hover, &mut events),
+                    } => mouse_input(
+                        state,
+                        button,
+                        modifiers,
+                        &mut hover,
+                        &mut events,
+                        &nodes,
+                        &states,
+                        &stops,
+                        &mut propagation,
+                    ),
                     _ => (),
                 },
                 winit::Event::DeviceEvent { ref event, .. } => match *event {
-                    DeviceEvent::MouseMotion { delta, .. } => {
-                        mouse_motion(delta, &mut hover, &mut events)
-                    }
+                    DeviceEvent::MouseMotion { delta, .. } => mouse_motion(
+                        delta,
+                        &mut hover,
+                        &mut events,
+                        &nodes,
+                        &states,
+                        &stops,
+                        &mut propagation,
+                    ),
                     _ => (),
                 },
                 _ => (),
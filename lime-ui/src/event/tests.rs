@@ -3,14 +3,19 @@ use std::mem;
 use cassowary::strength::REQUIRED;
 use cassowary::WeightedRelation::EQ;
 use render::d2::Point;
+use render::DpiFactor;
 use shrev::EventChannel;
+use specs_mirror::StorageMutExt;
 use winit::dpi::LogicalPosition;
-use winit::{self, ModifiersState, WindowEvent};
+use winit::{
+    self, ElementState, KeyboardInput, ModifiersState, MouseButton, VirtualKeyCode, WindowEvent,
+};
 
 use super::*;
 use layout::Position;
 use tests::init_test;
 use tree::{Node, Root};
+use State;
 
 pub fn emit_mouse_event(world: &mut World, entity: Entity, event: MouseEvent) {
     world
@@ -244,3 +249,309 @@ fn mouse_focus() {
         (1500.0, 1500.0),
     );
 }
+
+#[test]
+fn mouse_focus_dpi_changed() {
+    let (mut world, mut dispatcher) = init_test();
+    let mut rdr = world
+        .write_resource::<EventChannel<Event>>()
+        .register_reader();
+    let root = world.read_resource::<Root>().entity();
+
+    let rect = create_rect(&mut world, root, 500.0, 500.0, 500.0, 500.0).build();
+
+    run_window_event(
+        &mut world,
+        &mut dispatcher,
+        WindowEvent::Resized((1000, 1000).into()),
+    );
+    assert_mouse_focus(
+        &mut world,
+        &mut dispatcher,
+        &mut rdr,
+        None,
+        Some(rect),
+        (800.0, 800.0),
+    );
+
+    run_window_event(
+        &mut world,
+        &mut dispatcher,
+        WindowEvent::HiDpiFactorChanged(2.0),
+    );
+    let events: Vec<_> = {
+        world
+            .read_resource::<EventChannel<Event>>()
+            .read(&mut rdr)
+            .cloned()
+            .collect()
+    };
+    assert!(find_mouse_event(&events, rect, MouseEvent::Exit));
+    assert!(find_mouse_event(&events, root, MouseEvent::Enter));
+    assert_eq!(world.read_resource::<MouseFocus>().point(), Point(400.0, 400.0));
+
+    // The factor is read from the resource, which the renderer keeps up to date, rather than
+    // from previous events.
+    world.add_resource(DpiFactor::new(4.0));
+    run_window_event(
+        &mut world,
+        &mut dispatcher,
+        WindowEvent::HiDpiFactorChanged(2.0),
+    );
+    assert_eq!(world.read_resource::<MouseFocus>().point(), Point(800.0, 800.0));
+}
+
+fn press_tab(world: &mut World, dispatcher: &mut Dispatcher, shift: bool) {
+    for &state in &[ElementState::Pressed, ElementState::Released] {
+        run_window_event(
+            world,
+            dispatcher,
+            WindowEvent::KeyboardInput {
+                device_id: unsafe { mem::zeroed() },
+                input: KeyboardInput {
+                    scancode: 0,
+                    state,
+                    virtual_keycode: Some(VirtualKeyCode::Tab),
+                    modifiers: ModifiersState {
+                        shift,
+                        ..ModifiersState::default()
+                    },
+                },
+            },
+        );
+    }
+}
+
+fn assert_keyboard_focus(
+    world: &mut World,
+    reader: &mut ReaderId<Event>,
+    old: Entity,
+    new: Entity,
+) {
+    let events: Vec<_> = world
+        .read_resource::<EventChannel<Event>>()
+        .read(reader)
+        .cloned()
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            Event::keyboard(old, KeyboardEvent::FocusLost),
+            Event::keyboard(new, KeyboardEvent::FocusGained),
+        ]
+    );
+    assert_eq!(world.read_resource::<KeyboardFocus>().entity(), new);
+}
+
+#[test]
+fn keyboard_focus() {
+    let (mut world, mut dispatcher) = init_test();
+    let mut rdr = world
+        .write_resource::<EventChannel<Event>>()
+        .register_reader();
+    let root = world.read_resource::<Root>().entity();
+
+    let e1 = Node::with_parent(world.create_entity(), root)
+        .with(Focusable)
+        .build();
+    let e2 = Node::with_parent(world.create_entity(), e1)
+        .with(Focusable)
+        .with(State::default())
+        .build();
+    let e3 = Node::with_parent(world.create_entity(), root)
+        .with(Focusable)
+        .build();
+    let e4 = Node::with_parent(world.create_entity(), root).build();
+
+    {
+        let mut states = world.write_storage::<State>();
+        let (state, chan) = states.modify(e2).unwrap();
+        state.set_needs_events(e2, false, chan);
+    }
+
+    press_tab(&mut world, &mut dispatcher, false);
+    assert_keyboard_focus(&mut world, &mut rdr, root, e1);
+    press_tab(&mut world, &mut dispatcher, false);
+    assert_keyboard_focus(&mut world, &mut rdr, e1, e3);
+    press_tab(&mut world, &mut dispatcher, false);
+    assert_keyboard_focus(&mut world, &mut rdr, e3, e1);
+    press_tab(&mut world, &mut dispatcher, true);
+    assert_keyboard_focus(&mut world, &mut rdr, e1, e3);
+
+    {
+        let mut states = world.write_storage::<State>();
+        let (state, chan) = states.modify(e2).unwrap();
+        state.set_needs_events(e2, true, chan);
+    }
+    press_tab(&mut world, &mut dispatcher, true);
+    assert_keyboard_focus(&mut world, &mut rdr, e3, e2);
+
+    world.write_resource::<KeyboardFocus>().set_entity(e4);
+    dispatcher.dispatch(&world.res);
+    assert_keyboard_focus(&mut world, &mut rdr, e2, e4);
+
+    world.delete_entity(e4).unwrap();
+    dispatcher.dispatch(&world.res);
+    let events: Vec<_> = world
+        .read_resource::<EventChannel<Event>>()
+        .read(&mut rdr)
+        .cloned()
+        .collect();
+    assert_eq!(
+        events,
+        vec![Event::keyboard(root, KeyboardEvent::FocusGained)]
+    );
+}
+
+fn click(
+    world: &mut World,
+    dispatcher: &mut Dispatcher,
+    reader: &mut ReaderId<Event>,
+) -> Vec<Event> {
+    run_window_event(
+        world,
+        dispatcher,
+        WindowEvent::MouseInput {
+            device_id: unsafe { mem::zeroed() },
+            state: ElementState::Pressed,
+            button: MouseButton::Left,
+            modifiers: ModifiersState::default(),
+        },
+    );
+    world
+        .read_resource::<EventChannel<Event>>()
+        .read(reader)
+        .filter(|ev| match ev.kind() {
+            EventKind::Mouse(MouseEvent::ButtonDown(_, _)) => true,
+            _ => false,
+        })
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn propagation() {
+    use self::EventPhase::*;
+
+    let (mut world, mut dispatcher) = init_test();
+    let mut rdr = world
+        .write_resource::<EventChannel<Event>>()
+        .register_reader();
+    let root = world.read_resource::<Root>().entity();
+
+    let panel = create_rect(&mut world, root, 0.0, 0.0, 500.0, 500.0).build();
+    let btn = create_rect(&mut world, panel, 100.0, 100.0, 100.0, 100.0).build();
+
+    run_window_event(
+        &mut world,
+        &mut dispatcher,
+        WindowEvent::Resized((1000, 1000).into()),
+    );
+    run_window_event(
+        &mut world,
+        &mut dispatcher,
+        WindowEvent::CursorMoved {
+            position: LogicalPosition::new(150.0, 150.0),
+            modifiers: ModifiersState::default(),
+            device_id: unsafe { mem::zeroed() },
+        },
+    );
+
+    let phases = |events: Vec<Event>| -> Vec<(Entity, EventPhase)> {
+        events
+            .iter()
+            .inspect(|ev| assert_eq!(ev.target(), btn))
+            .map(|ev| (ev.entity(), ev.phase()))
+            .collect()
+    };
+
+    let events = click(&mut world, &mut dispatcher, &mut rdr);
+    assert_eq!(
+        phases(events),
+        vec![
+            (root, Capture),
+            (panel, Capture),
+            (btn, Target),
+            (panel, Bubble),
+            (root, Bubble),
+        ]
+    );
+
+    world
+        .write_storage()
+        .insert(
+            panel,
+            StopPropagation {
+                capture: false,
+                bubble: true,
+            },
+        )
+        .unwrap();
+    let events = click(&mut world, &mut dispatcher, &mut rdr);
+    assert_eq!(
+        phases(events),
+        vec![
+            (root, Capture),
+            (panel, Capture),
+            (btn, Target),
+            (panel, Bubble),
+        ]
+    );
+
+    world
+        .write_storage()
+        .insert(
+            root,
+            StopPropagation {
+                capture: true,
+                bubble: false,
+            },
+        )
+        .unwrap();
+    let events = click(&mut world, &mut dispatcher, &mut rdr);
+    assert_eq!(phases(events), vec![(root, Capture)]);
+}
+
+#[test]
+fn propagation_stopped() {
+    use self::EventPhase::*;
+
+    let (mut world, mut dispatcher) = init_test();
+    let mut rdr = world
+        .write_resource::<EventChannel<Event>>()
+        .register_reader();
+    let root = world.read_resource::<Root>().entity();
+
+    let panel = create_rect(&mut world, root, 0.0, 0.0, 500.0, 500.0).build();
+    create_rect(&mut world, panel, 100.0, 100.0, 100.0, 100.0).build();
+
+    run_window_event(
+        &mut world,
+        &mut dispatcher,
+        WindowEvent::Resized((1000, 1000).into()),
+    );
+    run_window_event(
+        &mut world,
+        &mut dispatcher,
+        WindowEvent::CursorMoved {
+            position: LogicalPosition::new(150.0, 150.0),
+            modifiers: ModifiersState::default(),
+            device_id: unsafe { mem::zeroed() },
+        },
+    );
+
+    // A handler stops the click while it is captured by `panel`, so the later phases are skipped.
+    let events = click(&mut world, &mut dispatcher, &mut rdr);
+    let mut propagation = world.write_resource::<Propagation>();
+    for event in &events {
+        if event.entity() == panel && event.phase() == Capture {
+            propagation.stop(event);
+        }
+    }
+    let handled: Vec<_> = events
+        .iter()
+        .filter(|ev| !propagation.is_stopped(ev))
+        .map(|ev| (ev.entity(), ev.phase()))
+        .collect();
+    assert_eq!(handled, vec![(root, Capture), (panel, Capture)]);
+}
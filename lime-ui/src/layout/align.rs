@@ -0,0 +1,47 @@
+use cassowary::strength::REQUIRED;
+use cassowary::WeightedRelation::*;
+use cassowary::{Constraint, Expression, Variable};
+
+/// The placement of an entity along one axis of the space given to it by its container.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Alignment {
+    Start,
+    Center,
+    End,
+    Stretch,
+}
+
+impl Default for Alignment {
+    fn default() -> Self {
+        Alignment::Start
+    }
+}
+
+/// Constraints placing the span from `child_start` to `child_end` between `start` and `end`.
+pub(crate) fn align(
+    alignment: Alignment,
+    start: Expression,
+    end: Expression,
+    child_start: Variable,
+    child_end: Variable,
+) -> Vec<Constraint> {
+    match alignment {
+        Alignment::Start => vec![
+            child_start | EQ(REQUIRED) | start,
+            child_end | LE(REQUIRED) | end,
+        ],
+        Alignment::Center => vec![
+            child_start - start.clone() | EQ(REQUIRED) | end - child_end,
+            child_start | GE(REQUIRED) | start,
+        ],
+        Alignment::End => vec![
+            child_end | EQ(REQUIRED) | end,
+            child_start | GE(REQUIRED) | start,
+        ],
+        Alignment::Stretch => vec![
+            child_start | EQ(REQUIRED) | start,
+            child_end | EQ(REQUIRED) | end,
+        ],
+    }
+}
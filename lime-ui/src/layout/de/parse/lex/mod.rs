@@ -3,38 +3,105 @@ mod tests;
 
 use std::borrow::Cow;
 use std::fmt;
-use std::str::SplitWhitespace;
 
 use cassowary::strength;
 
 use layout::de::parse::ParseError;
 
-pub(in layout::de::parse) enum Lexer<'de: 'src, 'src> {
-    Borrowed(SplitWhitespace<'de>),
-    Owned(SplitWhitespace<'src>),
+/// Splits a constraint into tokens. Tokens may be separated by whitespace, but it is only needed
+/// between adjacent names and numbers.
+///
+/// Names may only contain ASCII letters, digits and `_`, so `a-b` is a subtraction. A number may
+/// start with a `.`, as in `.5`.
+pub(in layout::de::parse) struct Lexer<'de: 'src, 'src> {
+    src: &'src Cow<'de, str>,
+    pos: usize,
 }
 
 impl<'de, 'src> Lexer<'de, 'src> {
     pub(in layout::de::parse) fn new(src: &'src Cow<'de, str>) -> Self {
-        match src {
-            Cow::Borrowed(s) => Lexer::Borrowed(s.split_whitespace()),
-            Cow::Owned(s) => Lexer::Owned(s.split_whitespace()),
-        }
+        Lexer { src, pos: 0 }
     }
 
-    pub(in layout::de::parse) fn next(&mut self) -> Result<Token<'de, 'src>, ParseError> {
-        match self {
-            Lexer::Borrowed(split) => Token::next(split),
-            Lexer::Owned(split) => Token::next(split).map(Token::to_owned),
+    /// Returns the next token, along with its byte offset in the source.
+    pub(in layout::de::parse) fn next(&mut self) -> Result<(usize, Token<'de>), ParseError> {
+        let src: &'src str = self.src;
+        let start = match src[self.pos..].find(|c: char| !c.is_whitespace()) {
+            Some(skip) => self.pos + skip,
+            None => {
+                self.pos = src.len();
+                return Ok((src.len(), Token::Eos));
+            }
+        };
+        let rest = &src[start..];
+
+        let (len, tok) = match rest.as_bytes()[0] {
+            b'+' => (1, Token::Sum(Sum::Add)),
+            b'-' => (1, Token::Sum(Sum::Sub)),
+            b'*' => (1, Token::Prod(Prod::Mul)),
+            b'/' => (1, Token::Prod(Prod::Div)),
+            b'(' => (1, Token::Open),
+            b')' => (1, Token::Close),
+            b'.' if !rest[1..].starts_with(|c: char| c.is_ascii_digit()) => (1, Token::Dot),
+            b'=' if rest.starts_with("==") => (2, Token::Rel(Rel::Eq)),
+            b'<' if rest.starts_with("<=") => (2, Token::Rel(Rel::Le)),
+            b'>' if rest.starts_with(">=") => (2, Token::Rel(Rel::Ge)),
+            b if b == b'.' || b.is_ascii_digit() => {
+                let len = token_len(rest, |c| c == '.' || c.is_ascii_digit());
+                match rest[..len].parse() {
+                    Ok(num) => (len, Token::Number(num)),
+                    Err(_) => return Err(invalid_token(&rest[..len], start)),
+                }
+            }
+            b if b == b'_' || b.is_ascii_alphabetic() => {
+                let len = token_len(rest, |c| c == '_' || c.is_ascii_alphanumeric());
+                let tok = match &rest[..len] {
+                    "weak" => Token::Strength(strength::WEAK),
+                    "medium" => Token::Strength(strength::MEDIUM),
+                    "strong" => Token::Strength(strength::STRONG),
+                    "required" => Token::Strength(strength::REQUIRED),
+                    _ => Token::Name(self.slice(start, start + len)),
+                };
+                (len, tok)
+            }
+            _ => {
+                let len = rest.chars().next().unwrap().len_utf8();
+                return Err(invalid_token(&rest[..len], start));
+            }
+        };
+
+        self.pos = start + len;
+        Ok((start, tok))
+    }
+
+    /// Borrow from the source for as long as possible.
+    fn slice(&self, start: usize, end: usize) -> Cow<'de, str> {
+        match *self.src {
+            Cow::Borrowed(src) => Cow::Borrowed(&src[start..end]),
+            Cow::Owned(ref src) => Cow::Owned(src[start..end].to_owned()),
         }
     }
 }
 
+fn invalid_token(token: &str, offset: usize) -> ParseError {
+    ParseError::InvalidToken {
+        token: token.to_owned(),
+        offset,
+    }
+}
+
+fn token_len(s: &str, pred: impl Fn(char) -> bool) -> usize {
+    s.find(|c: char| !pred(c)).unwrap_or(s.len())
+}
+
 #[derive(Clone, Debug)]
-pub(in layout::de::parse) enum Token<'de, 'src> {
-    Coefficient(f64),
+pub(in layout::de::parse) enum Token<'de> {
+    Number(f64),
     Strength(f64),
-    Variable(Cow<'de, str>, &'src str),
+    Name(Cow<'de, str>),
+    Dot,
+    Open,
+    Close,
     Rel(Rel),
     Sum(Sum),
     Prod(Prod),
@@ -60,64 +127,10 @@ pub(in layout::de::parse) enum Rel {
     Ge,
 }
 
-impl<'src> Token<'src, 'src> {
-    /// Parse token from a non-empty string.
-    fn next(s: &mut SplitWhitespace<'src>) -> Result<Self, ParseError> {
-        let s = match s.next() {
-            None => return Ok(Token::Eos),
-            Some(s) => s,
-        };
-
-        match s {
-            "+" => return Ok(Token::Sum(Sum::Add)),
-            "-" => return Ok(Token::Sum(Sum::Sub)),
-            "*" => return Ok(Token::Prod(Prod::Mul)),
-            "/" => return Ok(Token::Prod(Prod::Div)),
-            "==" => return Ok(Token::Rel(Rel::Eq)),
-            "<=" => return Ok(Token::Rel(Rel::Le)),
-            ">=" => return Ok(Token::Rel(Rel::Ge)),
-            "weak" => return Ok(Token::Strength(strength::WEAK)),
-            "medium" => return Ok(Token::Strength(strength::MEDIUM)),
-            "strong" => return Ok(Token::Strength(strength::STRONG)),
-            "required" => return Ok(Token::Strength(strength::REQUIRED)),
-            _ => (),
-        }
-
-        if is_numeric_first_byte(s.as_bytes()[0]) {
-            if let Ok(f) = s.parse() {
-                return Ok(Token::Coefficient(f));
-            }
-        }
-
-        let mut split = s.split('.');
-        if let (Some(first), Some(second), None) = (split.next(), split.next(), split.next()) {
-            return Ok(Token::Variable(Cow::Borrowed(first), second));
-        }
-
-        Err(ParseError::InvalidToken(s.to_owned()))
-    }
-
-    fn to_owned<'de: 'src>(self) -> Token<'de, 'src> {
-        match self {
-            Token::Coefficient(f) => Token::Coefficient(f),
-            Token::Strength(f) => Token::Strength(f),
-            Token::Variable(ent, var) => Token::Variable(Cow::Owned(ent.into_owned()), var),
-            Token::Rel(rel) => Token::Rel(rel),
-            Token::Sum(sum) => Token::Sum(sum),
-            Token::Prod(prod) => Token::Prod(prod),
-            Token::Eos => Token::Eos,
-        }
-    }
-}
-
-fn is_numeric_first_byte(b: u8) -> bool {
-    b == b'+' || b == b'-' || b.is_ascii_digit()
-}
-
-impl<'de, 'src> fmt::Display for Token<'de, 'src> {
+impl<'de> fmt::Display for Token<'de> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Token::Coefficient(c) => c.fmt(f),
+            Token::Number(n) => n.fmt(f),
             Token::Strength(s) => if s == &strength::WEAK {
                 "weak"
             } else if s == &strength::MEDIUM {
@@ -129,7 +142,10 @@ impl<'de, 'src> fmt::Display for Token<'de, 'src> {
             } else {
                 unreachable!()
             }.fmt(f),
-            Token::Variable(ent, var) => write!(f, "{}.{}", ent, var),
+            Token::Name(name) => name.fmt(f),
+            Token::Dot => ".".fmt(f),
+            Token::Open => "(".fmt(f),
+            Token::Close => ")".fmt(f),
             Token::Rel(rel) => rel.fmt(f),
             Token::Sum(sum) => sum.fmt(f),
             Token::Prod(prod) => prod.fmt(f),
@@ -2,18 +2,90 @@ use super::*;
 
 use std::fmt::Write;
 
+fn lex(src: &str) -> Result<Vec<(usize, String)>, ParseError> {
+    let src = Cow::Borrowed(src);
+    let mut lexer = Lexer::new(&src);
+    let mut out = Vec::new();
+    loop {
+        match lexer.next()? {
+            (_, Token::Eos) => return Ok(out),
+            (offset, tok) => out.push((offset, tok.to_string())),
+        }
+    }
+}
+
 #[test]
-fn lex() {
-    let src = "weak medium strong required 12 -5.2 45 == <= >= entity.variable ";
+fn lex_whitespace() {
+    let src = "weak medium strong required 12 5.2 45 == <= >= entity . variable ( ) + - * / ";
 
-    let mut split = src.split_whitespace();
     let mut out = String::new();
-    loop {
-        let tok = Token::next(&mut split).unwrap();
-        if let Token::Eos = tok {
-            break;
-        }
+    for (_, tok) in lex(src).unwrap() {
         write!(&mut out, "{} ", tok).unwrap();
     }
     assert_eq!(src, out);
 }
+
+#[test]
+fn lex_compact() {
+    let toks = lex("left==parent.left+(parent.width-width)/2").unwrap();
+    let expected = vec![
+        (0, "left"),
+        (4, "=="),
+        (6, "parent"),
+        (12, "."),
+        (13, "left"),
+        (17, "+"),
+        (18, "("),
+        (19, "parent"),
+        (25, "."),
+        (26, "width"),
+        (31, "-"),
+        (32, "width"),
+        (37, ")"),
+        (38, "/"),
+        (39, "2"),
+    ];
+    assert_eq!(toks.len(), expected.len());
+    for (tok, &(offset, s)) in toks.iter().zip(&expected) {
+        assert_eq!(tok.0, offset);
+        assert_eq!(tok.1, s);
+    }
+}
+
+#[test]
+fn lex_leading_dot() {
+    let toks = lex("width==.5*parent.width").unwrap();
+    let expected = vec![
+        (0, "width"),
+        (5, "=="),
+        (7, "0.5"),
+        (9, "*"),
+        (10, "parent"),
+        (16, "."),
+        (17, "width"),
+    ];
+    assert_eq!(toks.len(), expected.len());
+    for (tok, &(offset, s)) in toks.iter().zip(&expected) {
+        assert_eq!(tok.0, offset);
+        assert_eq!(tok.1, s);
+    }
+}
+
+#[test]
+fn lex_invalid() {
+    match lex("left == 1.2.3") {
+        Err(ParseError::InvalidToken { token, offset }) => {
+            assert_eq!(token, "1.2.3");
+            assert_eq!(offset, 8);
+        }
+        res => panic!("unexpected result {:?}", res),
+    }
+
+    match lex("left = 5") {
+        Err(ParseError::InvalidToken { token, offset }) => {
+            assert_eq!(token, "=");
+            assert_eq!(offset, 5);
+        }
+        res => panic!("unexpected result {:?}", res),
+    }
+}
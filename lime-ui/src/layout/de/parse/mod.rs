@@ -1,10 +1,13 @@
 mod lex;
+#[cfg(test)]
+mod tests;
 
 use std::borrow::Cow;
 use std::error::Error;
 use std::{fmt, mem};
 
-use cassowary::{Constraint, Expression, Term, WeightedRelation};
+use cassowary::strength;
+use cassowary::{Constraint, Expression, Term, Variable, WeightedRelation};
 use specs::prelude::*;
 
 use de::{DeserializeError, Seed};
@@ -22,12 +25,32 @@ pub(in layout::de) fn parse_expression<'de, 'src>(
     seed: Seed<'de, 'src>,
     src: Cow<'de, str>,
 ) -> Result<Expression, impl Error> {
-    Parser::new(seed, &src)?.parse_expression()
+    Parser::new(seed, &src).and_then(|mut parser| {
+        let expr = parser.parse_expression()?;
+        parser.parse_eos()?;
+        Ok(expr)
+    })
 }
 
+/// A recursive descent parser for constraints, using the following grammar:
+///
+/// ```text
+/// constraint := [strength] expression ('==' | '<=' | '>=') expression
+/// strength   := ('weak' | 'medium' | 'strong' | 'required') (('*' | '/') number)*
+/// expression := term (('+' | '-') term)*
+/// term       := unary (('*' | '/') unary)*
+/// unary      := ('+' | '-') unary | atom
+/// atom       := number | '(' expression ')' | [name '.'] property
+/// ```
+///
+/// A property without a name refers to the entity being deserialized, and `parent` refers to its
+/// parent. Like entity names elsewhere, names may only contain ASCII letters, digits and `_`.
+/// `parent` and the strength keywords are reserved, and cannot be used as entity names.
 struct Parser<'de: 'src, 'src> {
     lexer: Lexer<'de, 'src>,
-    peek: Token<'de, 'src>,
+    peek: Token<'de>,
+    // The byte offset of `peek`.
+    offset: usize,
     seed: Seed<'de, 'src>,
 }
 
@@ -36,14 +59,17 @@ impl<'de, 'src> Parser<'de, 'src> {
         let mut parser = Parser {
             lexer: Lexer::new(src),
             peek: Token::Eos,
+            offset: 0,
             seed,
         };
         parser.bump()?;
         Ok(parser)
     }
 
-    fn bump(&mut self) -> Result<Token<'de, 'src>, ParseError> {
-        Ok(mem::replace(&mut self.peek, self.lexer.next()?))
+    fn bump(&mut self) -> Result<(usize, Token<'de>), ParseError> {
+        let (offset, tok) = self.lexer.next()?;
+        let offset = mem::replace(&mut self.offset, offset);
+        Ok((offset, mem::replace(&mut self.peek, tok)))
     }
 
     fn parse_constraint(&mut self) -> Result<Constraint, ParseError> {
@@ -51,60 +77,133 @@ impl<'de, 'src> Parser<'de, 'src> {
         let lhs = self.parse_expression()?;
         let rel = self.parse_relation(strength)?;
         let rhs = self.parse_expression()?;
+        self.parse_eos()?;
         Ok(lhs | rel | rhs)
     }
 
     fn parse_strength(&mut self) -> Result<f64, ParseError> {
-        match self.bump()? {
-            Token::Strength(strength) => Ok(strength),
-            tok => Err(ParseError::UnexpectedToken {
-                expected: "one of 'weak', 'medium', 'strong' or 'required'",
-                found: tok.to_string(),
-            }),
+        let mut strength = match self.peek {
+            Token::Strength(strength) => {
+                self.bump()?;
+                strength
+            }
+            _ => return Ok(strength::REQUIRED),
+        };
+
+        while let Some(prod) = self.try_parse_prod()? {
+            let c = self.parse_number()?;
+            match prod {
+                Prod::Mul => strength *= c,
+                Prod::Div => strength /= c,
+            }
         }
+        Ok(strength::clip(strength))
     }
 
     fn parse_expression(&mut self) -> Result<Expression, ParseError> {
-        let mut expr = Expression::from_constant(0.0);
-        let mut op = Sum::Add;
-        loop {
-            let toc = self.parse_term_or_const()?;
-
-            match (op, toc) {
-                (Sum::Add, TermOrConst::Term(term)) => expr += term,
-                (Sum::Add, TermOrConst::Const(c)) => expr += c,
-                (Sum::Sub, TermOrConst::Term(term)) => expr -= term,
-                (Sum::Sub, TermOrConst::Const(c)) => expr -= c,
-            }
+        let mut expr = self.parse_term()?;
+        while let Some(sum) = self.try_parse_sum()? {
+            let rhs = self.parse_term()?;
+            expr = match sum {
+                Sum::Add => expr + rhs,
+                Sum::Sub => expr - rhs,
+            };
+        }
+        Ok(expr)
+    }
 
-            if let Some(sum) = self.try_parse_sum()? {
-                op = sum;
-            } else {
-                return Ok(expr);
+    fn parse_term(&mut self) -> Result<Expression, ParseError> {
+        let mut expr = self.parse_unary()?;
+        while let Some(prod) = self.try_parse_prod()? {
+            let offset = self.offset;
+            let rhs = self.parse_unary()?;
+            expr = product(expr, prod, rhs).ok_or(ParseError::NonAffineExpression { offset })?;
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, ParseError> {
+        match self.try_parse_sum()? {
+            Some(Sum::Add) => self.parse_unary(),
+            Some(Sum::Sub) => Ok(self.parse_unary()? * -1.0),
+            None => self.parse_atom(),
+        }
+    }
+
+    fn parse_atom(&mut self) -> Result<Expression, ParseError> {
+        match self.bump()? {
+            (_, Token::Number(n)) => Ok(Expression::from_constant(n)),
+            (_, Token::Open) => {
+                let expr = self.parse_expression()?;
+                match self.bump()? {
+                    (_, Token::Close) => Ok(expr),
+                    (offset, tok) => Err(unexpected_token("')'", tok, offset)),
+                }
             }
+            (offset, Token::Name(name)) => self.parse_variable(name, offset),
+            (offset, tok) => Err(unexpected_token("number, variable or '('", tok, offset)),
         }
     }
 
-    fn parse_term_or_const(&mut self) -> Result<TermOrConst, ParseError> {
-        let mut lhs = TermOrConst::Const(1.0);
-        let mut op = Prod::Mul;
-        loop {
-            let rhs = match self.bump()? {
-                Token::Coefficient(f) => TermOrConst::Const(f),
-                Token::Variable(ent, var) => TermOrConst::Term(self.resolve_variable(ent, var)?),
-                tok => {
+    fn parse_variable(
+        &mut self,
+        name: Cow<'de, str>,
+        offset: usize,
+    ) -> Result<Expression, ParseError> {
+        let (entity, prop, prop_offset) = match self.peek {
+            Token::Dot => {
+                self.bump()?;
+                let entity = if name == "parent" {
+                    self.seed.parent.ok_or(ParseError::NoParent { offset })?
+                } else {
+                    self.seed
+                        .get_entity(name)
+                        .map_err(|err| ParseError::De { err, offset })?
+                };
+                match self.bump()? {
+                    (prop_offset, Token::Name(prop)) => (entity, prop, prop_offset),
+                    (offset, tok) => return Err(unexpected_token("property", tok, offset)),
+                }
+            }
+            _ => {
+                if !is_property(&name) {
                     return Err(ParseError::UnexpectedToken {
-                        expected: "coefficient or variable",
-                        found: tok.to_string(),
-                    })
+                        expected: "a property, or an entity name followed by '.' (names may only \
+                                   contain letters, digits and '_')",
+                        found: name.into_owned(),
+                        offset,
+                    });
                 }
-            };
-            lhs = lhs.prod(op, rhs)?;
-            if let Some(prod) = self.try_parse_prod()? {
-                op = prod;
-            } else {
-                return Ok(lhs);
+                (self.seed.entity, name, offset)
+            }
+        };
+
+        let mut poss = WriteStorage::<Position>::fetch(self.seed.res);
+        let pos = poss.entry(entity).unwrap().or_insert_with(Default::default);
+        Ok(match prop.as_ref() {
+            "left" => variable(pos.left_var()),
+            "top" => variable(pos.top_var()),
+            "right" => variable(pos.right_var()),
+            "bottom" => variable(pos.bottom_var()),
+            "width" => pos.width_var(),
+            "height" => pos.height_var(),
+            "center_x" => (variable(pos.left_var()) + pos.right_var()) / 2.0,
+            "center_y" => (variable(pos.top_var()) + pos.bottom_var()) / 2.0,
+            prop => {
+                return Err(ParseError::UnexpectedToken {
+                    expected: "one of 'left', 'top', 'right', 'bottom', 'width', 'height', \
+                               'center_x' or 'center_y'",
+                    found: prop.to_string(),
+                    offset: prop_offset,
+                })
             }
+        })
+    }
+
+    fn parse_number(&mut self) -> Result<f64, ParseError> {
+        match self.bump()? {
+            (_, Token::Number(n)) => Ok(n),
+            (offset, tok) => Err(unexpected_token("number", tok, offset)),
         }
     }
 
@@ -128,111 +227,98 @@ impl<'de, 'src> Parser<'de, 'src> {
         }
     }
 
-    fn resolve_variable(
-        &mut self,
-        name: Cow<'de, str>,
-        var: &'src str,
-    ) -> Result<Term, ParseError> {
-        let entity = self.seed.get_entity(name)?;
-        let mut poss = WriteStorage::<Position>::fetch(self.seed.res);
-        let pos = poss.entry(entity).unwrap().or_insert_with(Default::default);
-        let variable = match var {
-            "left" => pos.left_var(),
-            "top" => pos.top_var(),
-            "right" => pos.right_var(),
-            "bottom" => pos.bottom_var(),
-            // TODO: width and height
-            var => {
-                return Err(ParseError::UnexpectedToken {
-                    expected: "one of 'left', 'top', 'right' or 'bottom'",
-                    found: var.to_string(),
-                })
-            }
-        };
-        Ok(Term {
-            variable,
-            coefficient: 1.0,
-        })
+    fn parse_relation(&mut self, strength: f64) -> Result<WeightedRelation, ParseError> {
+        match self.bump()? {
+            (_, Token::Rel(Rel::Eq)) => Ok(WeightedRelation::EQ(strength)),
+            (_, Token::Rel(Rel::Le)) => Ok(WeightedRelation::LE(strength)),
+            (_, Token::Rel(Rel::Ge)) => Ok(WeightedRelation::GE(strength)),
+            (offset, tok) => Err(unexpected_token("one of '==', '<=' or '>='", tok, offset)),
+        }
     }
 
-    fn parse_relation(&mut self, strength: f64) -> Result<WeightedRelation, ParseError> {
+    fn parse_eos(&mut self) -> Result<(), ParseError> {
         match self.bump()? {
-            Token::Rel(Rel::Eq) => Ok(WeightedRelation::EQ(strength)),
-            Token::Rel(Rel::Le) => Ok(WeightedRelation::LE(strength)),
-            Token::Rel(Rel::Ge) => Ok(WeightedRelation::GE(strength)),
-            tok => Err(ParseError::UnexpectedToken {
-                expected: "one of '==', '<=' or '>='",
-                found: tok.to_string(),
-            }),
+            (_, Token::Eos) => Ok(()),
+            (offset, tok) => Err(unexpected_token("end of string", tok, offset)),
         }
     }
 }
 
-#[derive(Copy, Clone, Debug)]
-enum TermOrConst {
-    Term(Term),
-    Const(f64),
+fn is_property(name: &str) -> bool {
+    match name {
+        "left" | "top" | "right" | "bottom" | "width" | "height" | "center_x" | "center_y" => true,
+        _ => false,
+    }
 }
 
-impl TermOrConst {
-    fn prod(self, op: Prod, other: Self) -> Result<Self, ParseError> {
-        match (self, op, other) {
-            (TermOrConst::Term(_), Prod::Mul, TermOrConst::Term(_)) => {
-                Err(ParseError::NonAffineExpression)
-            }
-            (TermOrConst::Term(t), Prod::Mul, TermOrConst::Const(c)) => {
-                Ok(TermOrConst::Term(t * c))
-            }
-            (TermOrConst::Term(_), Prod::Div, TermOrConst::Term(_)) => {
-                Err(ParseError::NonAffineExpression)
-            }
-            (TermOrConst::Term(t), Prod::Div, TermOrConst::Const(c)) => {
-                Ok(TermOrConst::Term(t / c))
-            }
-            (TermOrConst::Const(c), Prod::Mul, TermOrConst::Term(t)) => {
-                Ok(TermOrConst::Term(c * t))
-            }
-            (TermOrConst::Const(l), Prod::Mul, TermOrConst::Const(r)) => {
-                Ok(TermOrConst::Const(l * r))
-            }
-            (TermOrConst::Const(_), Prod::Div, TermOrConst::Term(_)) => {
-                Err(ParseError::NonAffineExpression)
-            }
-            (TermOrConst::Const(l), Prod::Div, TermOrConst::Const(r)) => {
-                Ok(TermOrConst::Const(l / r))
-            }
-        }
+fn variable(variable: Variable) -> Expression {
+    Expression::from_term(Term {
+        variable,
+        coefficient: 1.0,
+    })
+}
+
+/// Multiply or divide two expressions, or return `None` if the result would not be affine.
+fn product(lhs: Expression, op: Prod, rhs: Expression) -> Option<Expression> {
+    match op {
+        Prod::Mul if lhs.terms.is_empty() => Some(rhs * lhs.constant),
+        Prod::Mul if rhs.terms.is_empty() => Some(lhs * rhs.constant),
+        Prod::Div if rhs.terms.is_empty() => Some(lhs / rhs.constant),
+        _ => None,
+    }
+}
+
+fn unexpected_token(expected: &'static str, found: Token, offset: usize) -> ParseError {
+    ParseError::UnexpectedToken {
+        expected,
+        found: found.to_string(),
+        offset,
     }
 }
 
 #[derive(Debug)]
 enum ParseError {
-    De(DeserializeError),
-    NonAffineExpression,
+    De {
+        err: DeserializeError,
+        offset: usize,
+    },
+    NoParent {
+        offset: usize,
+    },
+    NonAffineExpression {
+        offset: usize,
+    },
     UnexpectedToken {
         expected: &'static str,
         found: String,
+        offset: usize,
+    },
+    InvalidToken {
+        token: String,
+        offset: usize,
     },
-    InvalidToken(String),
 }
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            ParseError::De(err) => write!(f, "{}", err),
-            ParseError::NonAffineExpression => write!(f, "non-affine constraint expression"),
-            ParseError::UnexpectedToken { expected, found } => {
-                write!(f, "expected {}, found '{}'", expected, found)
+            ParseError::De { err, offset } => write!(f, "{} at offset {}", err, offset),
+            ParseError::NoParent { offset } => {
+                write!(f, "reference to missing parent at offset {}", offset)
+            }
+            ParseError::NonAffineExpression { offset } => {
+                write!(f, "non-affine constraint expression at offset {}", offset)
+            }
+            ParseError::UnexpectedToken {
+                expected,
+                found,
+                offset,
+            } => write!(f, "expected {}, found '{}' at offset {}", expected, found, offset),
+            ParseError::InvalidToken { token, offset } => {
+                write!(f, "invalid token '{}' at offset {}", token, offset)
             }
-            ParseError::InvalidToken(tok) => write!(f, "invalid token '{}'", tok),
         }
     }
 }
 
 impl Error for ParseError {}
-
-impl From<DeserializeError> for ParseError {
-    fn from(err: DeserializeError) -> Self {
-        ParseError::De(err)
-    }
-}
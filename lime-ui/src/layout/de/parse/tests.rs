@@ -0,0 +1,107 @@
+use fnv::FnvHashMap;
+use serde_json as json;
+use specs::prelude::*;
+use winit::WindowEvent::Resized;
+
+use de;
+use event::tests::run_window_event;
+use layout::Position;
+use tests::init_test;
+
+fn deserialize(world: &mut World, data: &str) -> Result<FnvHashMap<String, Entity>, String> {
+    let registry = de::Registry::new();
+    let mut name_map = FnvHashMap::default();
+    de::deserialize_with_names(
+        &mut json::Deserializer::from_str(data),
+        &registry,
+        &mut world.res,
+        &mut name_map,
+    ).map_err(|err| err.to_string())?;
+    Ok(name_map
+        .into_iter()
+        .map(|(name, ent)| (name.into_owned(), ent))
+        .collect())
+}
+
+#[test]
+fn parse() {
+    const DATA: &'static str = r#"
+    {
+        "box": {
+            "Constraints": [
+                "width == 200",
+                "height == parent.height * .5",
+                "left == parent.left + (parent.width - width) / 2",
+                "strong*2 top==parent.top+-(-10)"
+            ]
+        }
+    }
+    "#;
+
+    let (mut world, mut dispatcher) = init_test();
+    let names = deserialize(&mut world, DATA).unwrap();
+
+    run_window_event(&mut world, &mut dispatcher, Resized((1000, 750).into()));
+
+    let poss = world.read_storage::<Position>();
+    let pos = poss.get(names["box"]).unwrap();
+    assert_ulps_eq!(pos.left(), 400.0);
+    assert_ulps_eq!(pos.top(), 10.0);
+    assert_ulps_eq!(pos.width(), 200.0);
+    assert_ulps_eq!(pos.height(), 375.0);
+}
+
+#[test]
+fn parse_center() {
+    const DATA: &'static str = r#"
+    {
+        "a": {
+            "Constraints": [
+                "left == 100",
+                "top == 50",
+                "width == 300",
+                "height == 2 * 50"
+            ]
+        },
+        "b": {
+            "Constraints": [
+                "width == a.width / 3",
+                "height == 20",
+                "center_x == a.center_x",
+                "center_y == a.center_y"
+            ]
+        }
+    }
+    "#;
+
+    let (mut world, mut dispatcher) = init_test();
+    let names = deserialize(&mut world, DATA).unwrap();
+
+    run_window_event(&mut world, &mut dispatcher, Resized((1000, 750).into()));
+
+    let poss = world.read_storage::<Position>();
+    let pos = poss.get(names["b"]).unwrap();
+    assert_ulps_eq!(pos.left(), 200.0);
+    assert_ulps_eq!(pos.top(), 90.0);
+    assert_ulps_eq!(pos.right(), 300.0);
+    assert_ulps_eq!(pos.bottom(), 110.0);
+}
+
+#[test]
+fn parse_errors() {
+    fn error(con: &str) -> String {
+        let (mut world, _) = init_test();
+        let data = format!(r#"{{ "box": {{ "Constraints": ["{}"] }} }}"#, con);
+        deserialize(&mut world, &data).unwrap_err()
+    }
+
+    assert!(error("left == (parent.left + 10").contains("expected ')'"));
+    assert!(error("left == parent.width * width").contains("non-affine"));
+    assert!(error("left == parent.middle").contains("found 'middle' at offset 15"));
+    assert!(error("left == parent.left + 10)").contains("offset 24"));
+    assert!(error("left == parent.left + (10 $ 2)").contains("invalid token '$' at offset 26"));
+    assert!(
+        error("left == my-button.left")
+            .contains("names may only contain letters, digits and '_'), found 'my' at offset 8")
+    );
+}
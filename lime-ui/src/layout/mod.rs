@@ -1,8 +1,14 @@
+mod align;
 mod cons;
 mod de;
 mod pos;
+mod size;
 mod sys;
 
+pub use self::align::Alignment;
 pub use self::cons::{Constraints, ConstraintsBuilder};
 pub use self::pos::Position;
+pub use self::size::IntrinsicSize;
 pub use self::sys::LayoutSystem;
+
+pub(crate) use self::align::align;
@@ -0,0 +1,44 @@
+use cassowary::strength::MEDIUM;
+use cassowary::Constraint;
+use cassowary::WeightedRelation::*;
+use specs::prelude::*;
+
+use layout::{Constraints, Position};
+
+/// The minimum size needed to show the content of an entity, such as the extent of its text.
+///
+/// The size is kept in the entity's `Constraints` at `MEDIUM` strength, so it gives way to
+/// stronger constraints, but containers such as a `Grid` with `Size::Auto` rows and columns grow
+/// to fit it.
+#[derive(Component, Debug, Default)]
+pub struct IntrinsicSize {
+    size: (f64, f64),
+    cons: Vec<Constraint>,
+}
+
+impl IntrinsicSize {
+    pub fn new() -> Self {
+        IntrinsicSize::default()
+    }
+
+    pub fn size(&self) -> (f64, f64) {
+        self.size
+    }
+
+    pub fn set(&mut self, size: (f64, f64), pos: &Position, cons: &mut Constraints) {
+        if size == self.size && !self.cons.is_empty() {
+            return;
+        }
+
+        for con in self.cons.drain(..) {
+            cons.remove(con);
+        }
+        let (width, height) = size;
+        self.cons = vec![
+            pos.width_var() | GE(MEDIUM) | width,
+            pos.height_var() | GE(MEDIUM) | height,
+        ];
+        cons.extend(self.cons.iter().cloned());
+        self.size = size;
+    }
+}
@@ -62,6 +62,9 @@ impl LayoutSystem {
         dispatcher.add_thread_local(sys);
     }
 
+    // Window sizes are given in logical pixels, which is the unit used for layout. When the DPI
+    // factor changes, the window is resized to keep its logical size, so there is nothing else
+    // to handle here.
     fn handle_resize(&mut self, events_tx: &EventChannel<winit::Event>) {
         let resize = events_tx
             .read(&mut self.events_rx)
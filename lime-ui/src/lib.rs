@@ -16,6 +16,7 @@ extern crate specs_derive;
 extern crate specs_mirror;
 #[macro_use]
 extern crate log;
+extern crate rusttype;
 extern crate winit;
 
 #[cfg(test)]
@@ -39,16 +40,21 @@ mod tests;
 
 pub use state::{State, StateEvent};
 
+use render::DpiFactor;
 use shrev::EventChannel;
 use specs::{DispatcherBuilder, World};
 
 pub fn init(world: &mut World, dispatcher: &mut DispatcherBuilder<'_, '_>) {
     world.register::<State>();
     world.register::<layout::Constraints>();
+    world.register::<layout::IntrinsicSize>();
     world.register::<layout::Position>();
     world.register::<tree::Node>();
     world.register::<draw::Brush>();
+    world.register::<draw::BorderStyle>();
     world.register::<draw::Style>();
+    world.register::<event::Focusable>();
+    world.register::<event::StopPropagation>();
     world.register::<widget::button::Button>();
     world.register::<widget::button::ButtonStyle>();
     world.register::<widget::button::ToggleButton>();
@@ -57,11 +63,29 @@ pub fn init(world: &mut World, dispatcher: &mut DispatcherBuilder<'_, '_>) {
     world.register::<widget::button::RadioButtonGroup>();
     world.register::<widget::button::RadioButtonStyle>();
     world.register::<widget::grid::Grid>();
+    world.register::<widget::grid::Row>();
+    world.register::<widget::grid::Col>();
+    world.register::<widget::grid::RowSpan>();
+    world.register::<widget::grid::ColSpan>();
+    world.register::<widget::grid::HorizontalAlignment>();
+    world.register::<widget::grid::VerticalAlignment>();
+    world.register::<widget::grid::Margin>();
+    world.register::<widget::scroll::ScrollView>();
+    world.register::<widget::stack::Stack>();
+    world.register::<widget::text::Text>();
+    world.register::<widget::text::TextInput>();
+
+    if !world.res.has_value::<DpiFactor>() {
+        world.add_resource(DpiFactor::default());
+    }
 
     let root = tree::Root::create(world);
     world.add_resource(event::KeyboardFocus::new(&root));
     world.add_resource(event::MouseFocus::new());
+    world.add_resource(event::Propagation::new());
     world.add_resource(EventChannel::<event::Event>::new());
+    world.add_resource(draw::Textures::new());
+    world.add_resource(widget::text::Fonts::new());
 
     event::EventSystem::add(world, dispatcher);
     layout::LayoutSystem::add(world, dispatcher);
@@ -71,4 +95,9 @@ pub fn init(world: &mut World, dispatcher: &mut DispatcherBuilder<'_, '_>) {
     widget::button::ButtonStyleSystem::add(world, dispatcher);
     widget::button::ToggleButtonStyleSystem::add(world, dispatcher);
     widget::button::RadioButtonStyleSystem::add(world, dispatcher);
+    widget::text::TextInputSystem::add(world, dispatcher);
+    widget::text::TextSizeSystem::add(world, dispatcher);
+    widget::grid::GridSystem::add(world, dispatcher);
+    widget::scroll::ScrollSystem::add(world, dispatcher);
+    widget::stack::StackSystem::add(world, dispatcher);
 }
@@ -13,6 +13,12 @@ use specs_mirror::{Mirrored, MirroredStorage};
 #[derive(Clone, Debug, Deserialize)]
 pub struct Button {
     state: ButtonState,
+    // Whether the button is held down by the mouse or the keyboard. The button is pressed while
+    // either is.
+    #[serde(skip)]
+    mouse_pressed: bool,
+    #[serde(skip)]
+    key_pressed: bool,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize)]
@@ -38,6 +44,8 @@ impl Button {
             } else {
                 ButtonState::Disabled
             },
+            mouse_pressed: false,
+            key_pressed: false,
         }
     }
 
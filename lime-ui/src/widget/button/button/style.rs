@@ -2,7 +2,7 @@ use shrev::ReaderId;
 use specs::prelude::*;
 use specs_mirror::{StorageExt, StorageMutExt};
 
-use draw::{Brush, Style, StyleEvent};
+use draw::{set_border, BorderStyle, Brush, Style, StyleEvent};
 use widget::button::{Button, ButtonEvent, ButtonState, ButtonSystem};
 
 #[derive(Component, Deserialize)]
@@ -12,6 +12,8 @@ pub struct ButtonStyle {
     pub normal: Brush,
     pub focused: Brush,
     pub pressed: Brush,
+    #[serde(default)]
+    pub border: Option<BorderStyle>,
 }
 
 impl ButtonStyle {
@@ -50,9 +52,10 @@ impl<'a> System<'a> for ButtonStyleSystem {
         ReadStorage<'a, Style>,
         ReadStorage<'a, ButtonStyle>,
         WriteStorage<'a, Brush>,
+        WriteStorage<'a, BorderStyle>,
     );
 
-    fn run(&mut self, (btns, styles, btn_styles, mut brushes): Self::SystemData) {
+    fn run(&mut self, (btns, styles, btn_styles, mut brushes, mut borders): Self::SystemData) {
         for event in btns.read_events(&mut self.btn_rx) {
             if let Some(style) = styles.get(event.entity) {
                 if style.is::<ButtonStyle>() {
@@ -73,6 +76,7 @@ impl<'a> System<'a> for ButtonStyleSystem {
                     brushes
                         .insert(event.entity, btn_style.brush(btn.state()).clone())
                         .ok();
+                    set_border(&mut borders, event.entity, btn_style.border.as_ref());
                 }
             }
         }
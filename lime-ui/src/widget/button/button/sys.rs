@@ -1,9 +1,11 @@
 use shrev::{EventChannel, ReaderId};
 use specs::prelude::*;
 use specs_mirror::StorageMutExt;
-use winit::MouseButton;
+use winit::{MouseButton, VirtualKeyCode};
 
-use event::{Event, EventKind, EventSystem, MouseEvent};
+use event::{
+    Event, EventKind, EventPhase, EventSystem, KeyboardEvent, MouseEvent, MouseFocus,
+};
 use widget::button::{Button, ButtonState};
 
 pub struct ButtonSystem {
@@ -28,11 +30,16 @@ impl ButtonSystem {
 impl<'a> System<'a> for ButtonSystem {
     type SystemData = (
         ReadExpect<'a, EventChannel<Event>>,
+        ReadExpect<'a, MouseFocus>,
         WriteStorage<'a, Button>,
     );
 
-    fn run(&mut self, (events, mut btns): Self::SystemData) {
+    fn run(&mut self, (events, hover, mut btns): Self::SystemData) {
         for &event in events.read(&mut self.reader) {
+            if event.phase() != EventPhase::Target {
+                continue;
+            }
+
             match event.kind() {
                 EventKind::Mouse(MouseEvent::Move(_, _)) => continue,
                 EventKind::Mouse(MouseEvent::MoveRaw(_, _)) => continue,
@@ -46,21 +53,57 @@ impl<'a> System<'a> for ButtonSystem {
 
                 let state = match event.kind() {
                     EventKind::Mouse(MouseEvent::Enter) => {
+                        if btn.key_pressed {
+                            continue;
+                        }
                         debug_assert_eq!(btn.state, ButtonState::Normal);
                         ButtonState::Focused
                     }
                     EventKind::Mouse(MouseEvent::Exit) => {
                         debug_assert_ne!(btn.state, ButtonState::Normal);
+                        btn.mouse_pressed = false;
+                        if btn.key_pressed {
+                            continue;
+                        }
                         ButtonState::Normal
                     }
                     EventKind::Mouse(MouseEvent::ButtonUp(MouseButton::Left, _)) => {
                         debug_assert_ne!(btn.state, ButtonState::Normal);
+                        btn.mouse_pressed = false;
+                        if btn.key_pressed {
+                            continue;
+                        }
                         ButtonState::Focused
                     }
                     EventKind::Mouse(MouseEvent::ButtonDown(MouseButton::Left, _)) => {
-                        debug_assert_eq!(btn.state, ButtonState::Focused);
+                        if btn.key_pressed {
+                            debug_assert_eq!(btn.state, ButtonState::Pressed);
+                        } else {
+                            debug_assert_eq!(btn.state, ButtonState::Focused);
+                        }
+                        btn.mouse_pressed = true;
+                        ButtonState::Pressed
+                    }
+                    EventKind::Keyboard(KeyboardEvent::KeyDown(key, _)) if is_activate_key(key) => {
+                        btn.key_pressed = true;
                         ButtonState::Pressed
                     }
+                    EventKind::Keyboard(KeyboardEvent::KeyUp(key, _))
+                        if is_activate_key(key) && btn.key_pressed =>
+                    {
+                        btn.key_pressed = false;
+                        if btn.mouse_pressed {
+                            continue;
+                        }
+                        hover_state(event.entity(), &hover)
+                    }
+                    EventKind::Keyboard(KeyboardEvent::FocusLost) if btn.key_pressed => {
+                        btn.key_pressed = false;
+                        if btn.mouse_pressed {
+                            continue;
+                        }
+                        hover_state(event.entity(), &hover)
+                    }
                     _ => continue,
                 };
 
@@ -69,3 +112,15 @@ impl<'a> System<'a> for ButtonSystem {
         }
     }
 }
+
+fn is_activate_key(key: VirtualKeyCode) -> bool {
+    key == VirtualKeyCode::Space || key == VirtualKeyCode::Return
+}
+
+fn hover_state(entity: Entity, hover: &MouseFocus) -> ButtonState {
+    if hover.entity() == Some(entity) {
+        ButtonState::Focused
+    } else {
+        ButtonState::Normal
+    }
+}
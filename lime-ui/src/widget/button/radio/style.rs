@@ -2,7 +2,7 @@ use shrev::ReaderId;
 use specs::prelude::*;
 use specs_mirror::{StorageExt, StorageMutExt};
 
-use draw::{Brush, Style, StyleEvent};
+use draw::{set_border, BorderStyle, Brush, Style, StyleEvent};
 use widget::button::{
     Button, ButtonEvent, ButtonState, RadioButtonSystem, ToggleButton, ToggleButtonEvent,
     ToggleButtonStyle,
@@ -18,6 +18,10 @@ impl RadioButtonStyle {
     pub fn brush(&self, state: (ButtonState, bool)) -> &Brush {
         self.style.brush(state)
     }
+
+    pub fn border(&self, state: bool) -> Option<&BorderStyle> {
+        self.style.border(state)
+    }
 }
 
 pub struct RadioButtonStyleSystem {
@@ -52,9 +56,13 @@ impl<'a> System<'a> for RadioButtonStyleSystem {
         ReadStorage<'a, Style>,
         ReadStorage<'a, RadioButtonStyle>,
         WriteStorage<'a, Brush>,
+        WriteStorage<'a, BorderStyle>,
     );
 
-    fn run(&mut self, (btns, tgls, styles, rad_styles, mut brushes): Self::SystemData) {
+    fn run(
+        &mut self,
+        (btns, tgls, styles, rad_styles, mut brushes, mut borders): Self::SystemData,
+    ) {
         for event in btns.read_events(&mut self.btn_rx) {
             if let Some(style) = styles.get(event.entity) {
                 if style.is::<RadioButtonStyle>() {
@@ -84,6 +92,7 @@ impl<'a> System<'a> for RadioButtonStyleSystem {
                                 rad_style.brush((btn.state(), event.state)).clone(),
                             )
                             .ok();
+                        set_border(&mut borders, event.entity, rad_style.border(event.state));
                     }
                 }
             }
@@ -102,6 +111,7 @@ impl<'a> System<'a> for RadioButtonStyleSystem {
                             rad_style.brush((btn.state(), tgl.state())).clone(),
                         )
                         .ok();
+                    set_border(&mut borders, event.entity, rad_style.border(tgl.state()));
                 }
             }
         }
@@ -2,11 +2,12 @@ use fnv::FnvHashMap;
 use serde_json as json;
 use specs::prelude::*;
 use specs_mirror::{StorageExt, StorageMutExt};
-use winit::{ModifiersState, MouseButton};
+use winit::{ModifiersState, MouseButton, VirtualKeyCode};
 
 use super::*;
 use de;
-use event::tests::emit_mouse_event;
+use event::tests::{emit_keyboard_event, emit_mouse_event};
+use event::{KeyboardEvent, MouseEvent};
 use tests::init_test;
 use tree::{Node, Root};
 
@@ -313,6 +314,129 @@ fn radio_button() {
     assert_eq!(was_toggled(&mut world, btn3, &mut tgl_rdr3), None);
 }
 
+#[test]
+fn button_keyboard() {
+    use event::MouseEvent::*;
+    use widget::button::ButtonState::*;
+
+    let (mut world, mut dispatcher) = init_test();
+    let mut reader = world.write_storage::<Button>().register_reader();
+    let root = world.read_resource::<Root>().entity();
+
+    let btn = Node::with_parent(world.create_entity(), root)
+        .with(Button::new(true))
+        .build();
+
+    for &key in &[VirtualKeyCode::Space, VirtualKeyCode::Return] {
+        emit_keyboard_event(
+            &mut world,
+            btn,
+            KeyboardEvent::KeyDown(key, ModifiersState::default()),
+        );
+        dispatcher.dispatch(&world.res);
+        check_button_state(&mut world, btn, Pressed);
+        assert!(was_clicked(&mut world, btn, &mut reader));
+
+        emit_keyboard_event(
+            &mut world,
+            btn,
+            KeyboardEvent::KeyDown(key, ModifiersState::default()),
+        );
+        dispatcher.dispatch(&world.res);
+        check_button_state(&mut world, btn, Pressed);
+        assert!(!was_clicked(&mut world, btn, &mut reader));
+
+        emit_keyboard_event(
+            &mut world,
+            btn,
+            KeyboardEvent::KeyUp(key, ModifiersState::default()),
+        );
+        dispatcher.dispatch(&world.res);
+        check_button_state(&mut world, btn, Normal);
+        assert!(!was_clicked(&mut world, btn, &mut reader));
+    }
+
+    emit_keyboard_event(
+        &mut world,
+        btn,
+        KeyboardEvent::KeyDown(VirtualKeyCode::A, ModifiersState::default()),
+    );
+    dispatcher.dispatch(&world.res);
+    check_button_state(&mut world, btn, Normal);
+
+    emit_keyboard_event(
+        &mut world,
+        btn,
+        KeyboardEvent::KeyDown(VirtualKeyCode::Space, ModifiersState::default()),
+    );
+    emit_mouse_event(&mut world, btn, Enter);
+    dispatcher.dispatch(&world.res);
+    check_button_state(&mut world, btn, Pressed);
+    emit_keyboard_event(&mut world, btn, KeyboardEvent::FocusLost);
+    dispatcher.dispatch(&world.res);
+    check_button_state(&mut world, btn, Normal);
+}
+
+#[test]
+fn button_mouse_and_keyboard() {
+    use event::MouseEvent::*;
+    use widget::button::ButtonState::*;
+
+    let (mut world, mut dispatcher) = init_test();
+    let root = world.read_resource::<Root>().entity();
+
+    let btn = Node::with_parent(world.create_entity(), root)
+        .with(Button::new(true))
+        .build();
+    let space = |down| {
+        if down {
+            KeyboardEvent::KeyDown(VirtualKeyCode::Space, ModifiersState::default())
+        } else {
+            KeyboardEvent::KeyUp(VirtualKeyCode::Space, ModifiersState::default())
+        }
+    };
+
+    emit_mouse_event(&mut world, btn, Enter);
+    emit_mouse_event(
+        &mut world,
+        btn,
+        ButtonDown(MouseButton::Left, ModifiersState::default()),
+    );
+    emit_keyboard_event(&mut world, btn, space(true));
+    emit_keyboard_event(&mut world, btn, space(false));
+    dispatcher.dispatch(&world.res);
+    // The mouse button is still held.
+    check_button_state(&mut world, btn, Pressed);
+
+    emit_mouse_event(
+        &mut world,
+        btn,
+        ButtonUp(MouseButton::Left, ModifiersState::default()),
+    );
+    dispatcher.dispatch(&world.res);
+    check_button_state(&mut world, btn, Focused);
+
+    emit_keyboard_event(&mut world, btn, space(true));
+    emit_mouse_event(
+        &mut world,
+        btn,
+        ButtonDown(MouseButton::Left, ModifiersState::default()),
+    );
+    emit_mouse_event(
+        &mut world,
+        btn,
+        ButtonUp(MouseButton::Left, ModifiersState::default()),
+    );
+    dispatcher.dispatch(&world.res);
+    // The key is still held.
+    check_button_state(&mut world, btn, Pressed);
+
+    // Emitted events do not update `MouseFocus`, so the button is not hovered.
+    emit_keyboard_event(&mut world, btn, space(false));
+    dispatcher.dispatch(&world.res);
+    check_button_state(&mut world, btn, Normal);
+}
+
 #[test]
 fn de() {
     const DATA: &'static str = r##"
@@ -374,3 +498,49 @@ fn de() {
     assert!(rad_grps.get(name_map["rb2"]).is_none());
     assert!(rad_grps.get(name_map["rb3"]).is_none());
 }
+
+#[test]
+fn toggle_button_style_border() {
+    use draw::{BorderStyle, Brush, Style};
+    use render::Color;
+
+    let (mut world, mut dispatcher) = init_test();
+    let root = world.read_resource::<Root>().entity();
+
+    let brush = Brush::Color(Color::RED);
+    let btn_style = |border| ButtonStyle {
+        disabled: brush.clone(),
+        normal: brush.clone(),
+        focused: brush.clone(),
+        pressed: brush.clone(),
+        border,
+    };
+    let border = BorderStyle {
+        radius: [4.0; 4],
+        ..Default::default()
+    };
+    let style = world
+        .create_entity()
+        .with(ToggleButtonStyle {
+            on: btn_style(Some(border.clone())),
+            off: btn_style(None),
+        }).build();
+
+    let btn = Node::with_parent(world.create_entity(), root)
+        .with(Button::new(true))
+        .with(ToggleButton::new(false))
+        .build();
+    Style::insert::<ToggleButtonStyle>(btn, style, &mut world.write_storage()).unwrap();
+
+    dispatcher.dispatch(&world.res);
+    assert_eq!(world.read_storage::<BorderStyle>().get(btn), None);
+
+    emit_mouse_event(&mut world, btn, MouseEvent::Enter);
+    emit_mouse_event(
+        &mut world,
+        btn,
+        MouseEvent::ButtonDown(MouseButton::Left, ModifiersState::default()),
+    );
+    dispatcher.dispatch(&world.res);
+    assert_eq!(world.read_storage::<BorderStyle>().get(btn), Some(&border));
+}
@@ -2,7 +2,7 @@ use shrev::ReaderId;
 use specs::prelude::*;
 use specs_mirror::{StorageExt, StorageMutExt};
 
-use draw::{Brush, Style, StyleEvent};
+use draw::{set_border, BorderStyle, Brush, Style, StyleEvent};
 use widget::button::{
     Button, ButtonEvent, ButtonState, ButtonStyle, ToggleButton, ToggleButtonEvent,
     ToggleButtonSystem,
@@ -22,6 +22,13 @@ impl ToggleButtonStyle {
             true => self.on.brush(state.0),
         }
     }
+
+    pub fn border(&self, state: bool) -> Option<&BorderStyle> {
+        match state {
+            false => self.off.border.as_ref(),
+            true => self.on.border.as_ref(),
+        }
+    }
 }
 
 pub struct ToggleButtonStyleSystem {
@@ -56,9 +63,13 @@ impl<'a> System<'a> for ToggleButtonStyleSystem {
         ReadStorage<'a, Style>,
         ReadStorage<'a, ToggleButtonStyle>,
         WriteStorage<'a, Brush>,
+        WriteStorage<'a, BorderStyle>,
     );
 
-    fn run(&mut self, (btns, tgls, styles, tgl_styles, mut brushes): Self::SystemData) {
+    fn run(
+        &mut self,
+        (btns, tgls, styles, tgl_styles, mut brushes, mut borders): Self::SystemData,
+    ) {
         for event in btns.read_events(&mut self.btn_rx) {
             if let Some(style) = styles.get(event.entity) {
                 if style.is::<ToggleButtonStyle>() {
@@ -88,6 +99,7 @@ impl<'a> System<'a> for ToggleButtonStyleSystem {
                                 tgl_style.brush((btn.state(), event.state)).clone(),
                             )
                             .ok();
+                        set_border(&mut borders, event.entity, tgl_style.border(event.state));
                     }
                 }
             }
@@ -106,6 +118,7 @@ impl<'a> System<'a> for ToggleButtonStyleSystem {
                             btn_style.brush((btn.state(), tgl.state())).clone(),
                         )
                         .ok();
+                    set_border(&mut borders, event.entity, btn_style.border(tgl.state()));
                 }
             }
         }
@@ -0,0 +1,94 @@
+use cassowary::Constraint;
+use specs::prelude::*;
+
+use layout::{align, Alignment, Position};
+use widget::grid::Grid;
+
+/// The row of a grid child. Children with a `Row` or `Col` are placed by the `GridSystem`, along
+/// the axes whose component is present.
+#[derive(Component, Copy, Clone, Debug, Deserialize, PartialEq)]
+pub struct Row(pub u32);
+
+/// The column of a grid child. Children with a `Row` or `Col` are placed by the `GridSystem`,
+/// along the axes whose component is present.
+#[derive(Component, Copy, Clone, Debug, Deserialize, PartialEq)]
+pub struct Col(pub u32);
+
+/// The number of rows covered by a grid child, one if not present.
+#[derive(Component, Copy, Clone, Debug, Deserialize, PartialEq)]
+#[storage(HashMapStorage)]
+pub struct RowSpan(pub u32);
+
+/// The number of columns covered by a grid child, one if not present.
+#[derive(Component, Copy, Clone, Debug, Deserialize, PartialEq)]
+#[storage(HashMapStorage)]
+pub struct ColSpan(pub u32);
+
+/// The placement of a grid child within its columns, `Stretch` if not present.
+#[derive(Component, Copy, Clone, Debug, Deserialize, PartialEq)]
+#[storage(HashMapStorage)]
+pub struct HorizontalAlignment(pub Alignment);
+
+/// The placement of a grid child within its rows, `Stretch` if not present.
+#[derive(Component, Copy, Clone, Debug, Deserialize, PartialEq)]
+#[storage(HashMapStorage)]
+pub struct VerticalAlignment(pub Alignment);
+
+/// Space left empty between a grid child and the edges of its cell.
+#[derive(Component, Copy, Clone, Debug, Default, Deserialize, PartialEq)]
+#[storage(HashMapStorage)]
+#[serde(default)]
+pub struct Margin {
+    pub left: f64,
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+}
+
+/// The placement of a child in a grid, gathered from its components. The child is not constrained
+/// along an axis without a column or row.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(in widget::grid) struct Cell {
+    pub col: Option<u32>,
+    pub row: Option<u32>,
+    pub col_span: u32,
+    pub row_span: u32,
+    pub halign: Alignment,
+    pub valign: Alignment,
+    pub margin: Margin,
+}
+
+impl Cell {
+    pub fn constraints(&self, grid: &Grid, pos: &Position) -> Option<Vec<Constraint>> {
+        let mut cons = Vec::new();
+        if let Some(col) = self.col {
+            let cols = grid.cols.vars();
+            let col_end = (col + self.col_span) as usize;
+            if col_end >= cols.len() {
+                return None;
+            }
+            cons.extend(align(
+                self.halign,
+                cols[col as usize] + self.margin.left,
+                cols[col_end] - self.margin.right,
+                pos.left_var(),
+                pos.right_var(),
+            ));
+        }
+        if let Some(row) = self.row {
+            let rows = grid.rows.vars();
+            let row_end = (row + self.row_span) as usize;
+            if row_end >= rows.len() {
+                return None;
+            }
+            cons.extend(align(
+                self.valign,
+                rows[row as usize] + self.margin.top,
+                rows[row_end] - self.margin.bottom,
+                pos.top_var(),
+                pos.bottom_var(),
+            ));
+        }
+        Some(cons)
+    }
+}
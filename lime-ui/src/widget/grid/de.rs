@@ -4,7 +4,7 @@ use specs::prelude::*;
 
 use de::{Deserialize, Insert, Seed};
 use layout::{Constraints, Position};
-use widget::grid::{Grid, Size};
+use widget::grid::{Col, Grid, Row, Size};
 
 impl Deserialize for Grid {
     fn deserialize<'de, 'a>(
@@ -47,58 +47,47 @@ impl<'de, 'a> serde::DeserializeSeed<'de> for GridSeed<'de, 'a> {
     }
 }
 
-#[derive(Deserialize)]
-pub(crate) struct Row(u32);
-
 impl Insert for Row {
     fn insert<'de, 'a>(self, seed: Seed<'de, 'a>) -> Result<Option<Self>, erased::Error> {
-        let grids = ReadStorage::<Grid>::fetch(seed.res);
-        if let Some(grid) = seed.parent.and_then(|ent| grids.get(ent)) {
-            let mut poss = WriteStorage::<Position>::fetch(seed.res);
-            let pos = poss.entry(seed.entity)
-                .unwrap()
-                .or_insert_with(Default::default);
-
-            let mut cons = WriteStorage::<Constraints>::fetch(seed.res);
-            let con = cons.entry(seed.entity)
-                .unwrap()
-                .or_insert_with(|| Constraints::new(pos));
-
-            grid.insert_row(self.0, pos, con);
-            Ok(None)
-        } else {
-            Err(serde::Error::custom(format!(
-                "row defined on entity '{}' which is not a child of a grid",
-                seed.get_name(seed.entity)
-            )))
-        }
+        insert_cell_component(self, seed, "row")
     }
 }
 
-#[derive(Deserialize)]
-pub(crate) struct Col(u32);
-
 impl Insert for Col {
     fn insert<'de, 'a>(self, seed: Seed<'de, 'a>) -> Result<Option<Self>, erased::Error> {
-        let grids = ReadStorage::<Grid>::fetch(seed.res);
-        if let Some(grid) = seed.parent.and_then(|ent| grids.get(ent)) {
-            let mut poss = WriteStorage::<Position>::fetch(seed.res);
-            let pos = poss.entry(seed.entity)
-                .unwrap()
-                .or_insert_with(Default::default);
-
-            let mut cons = WriteStorage::<Constraints>::fetch(seed.res);
-            let con = cons.entry(seed.entity)
-                .unwrap()
-                .or_insert_with(|| Constraints::new(pos));
+        insert_cell_component(self, seed, "column")
+    }
+}
 
-            grid.insert_col(self.0, pos, con);
-            Ok(None)
-        } else {
-            Err(serde::Error::custom(format!(
-                "column defined on entity '{}' which is not a child of a grid",
-                seed.get_name(seed.entity)
-            )))
-        }
+fn insert_cell_component<C>(
+    comp: C,
+    seed: Seed<'_, '_>,
+    kind: &str,
+) -> Result<Option<C>, erased::Error>
+where
+    C: Component,
+{
+    let in_grid = seed.parent
+        .map_or(false, |ent| ReadStorage::<Grid>::fetch(seed.res).contains(ent));
+    if !in_grid {
+        return Err(serde::Error::custom(format!(
+            "{} defined on entity '{}' which is not a child of a grid",
+            kind,
+            seed.get_name(seed.entity)
+        )));
     }
+
+    let mut poss = WriteStorage::<Position>::fetch(seed.res);
+    let pos = poss.entry(seed.entity)
+        .unwrap()
+        .or_insert_with(Default::default);
+
+    let mut cons = WriteStorage::<Constraints>::fetch(seed.res);
+    cons.entry(seed.entity)
+        .unwrap()
+        .or_insert_with(|| Constraints::new(pos));
+
+    WriteStorage::<C>::fetch(seed.res)
+        .insert(seed.entity, comp)
+        .map_err(serde::Error::custom)
 }
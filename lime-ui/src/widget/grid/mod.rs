@@ -1,19 +1,40 @@
-pub(crate) mod de;
+mod cell;
+mod de;
+mod sys;
 #[cfg(test)]
 mod tests;
+mod track;
+
+pub use self::cell::{Col, ColSpan, HorizontalAlignment, Margin, Row, RowSpan, VerticalAlignment};
+pub use self::sys::GridSystem;
 
 use cassowary::strength::*;
 use cassowary::WeightedRelation::*;
-use cassowary::{Expression, Variable};
+use cassowary::Constraint;
+use fnv::FnvHashMap;
 use specs::prelude::*;
 
 use layout::{Constraints, Position};
+use self::cell::Cell;
+use self::track::{TrackEdit, Tracks};
 
+/// Lays out children in rows and columns. Children are placed by giving them `Row` and `Col`
+/// components, along with optional spans, alignments and margins.
+///
+/// Rows and columns can be added, removed and resized after the grid is created. The `GridSystem`
+/// updates the `Row`, `Col` and spans of children so they stay in the same tracks, and removes the
+/// `Row` or `Col` of children whose only track was removed.
 #[derive(Component)]
 #[storage(HashMapStorage)]
 pub struct Grid {
-    rows: Vec<Variable>,
-    cols: Vec<Variable>,
+    rows: Tracks,
+    cols: Tracks,
+    cells: FnvHashMap<Entity, (Cell, Vec<Constraint>)>,
+    // Tracks added or removed since children were last placed, in order.
+    row_edits: Vec<TrackEdit>,
+    col_edits: Vec<TrackEdit>,
+    // Whether the tracks have changed since children were last placed.
+    dirty: bool,
 }
 
 #[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
@@ -23,7 +44,8 @@ pub enum Size {
     Abs(f64),
     // Relative size.
     Rel(f64),
-    // Space required is decided by children.
+    // Space required is decided by children. The row or column shrinks to the largest minimum
+    // size of the children placed in it, including their `IntrinsicSize`.
     Auto,
 }
 
@@ -34,80 +56,115 @@ impl Grid {
         cols: impl IntoIterator<Item = Size>,
         rows: impl IntoIterator<Item = Size>,
     ) -> Self {
-        let rows = rows.into_iter();
-        let cols = cols.into_iter();
-        cons.reserve(2 * rows.size_hint().0 + 2 * cols.size_hint().0 + 4);
-        let rows = layout(pos.top_var(), rows, pos.bottom_var(), cons);
-        let cols = layout(pos.left_var(), cols, pos.right_var(), cons);
-        Grid { rows, cols }
+        let rows = Tracks::new(pos.top_var(), rows, pos.bottom_var(), cons);
+        let cols = Tracks::new(pos.left_var(), cols, pos.right_var(), cons);
+        Grid {
+            rows,
+            cols,
+            cells: FnvHashMap::default(),
+            row_edits: Vec::new(),
+            col_edits: Vec::new(),
+            dirty: false,
+        }
+    }
+
+    pub fn rows(&self) -> &[Size] {
+        self.rows.sizes()
     }
 
+    pub fn cols(&self) -> &[Size] {
+        self.cols.sizes()
+    }
+
+    /// Insert a row before `row`, or after the last row if `row` is the number of rows. `cons`
+    /// must be the constraints of the grid's entity. Panics if `row` is out of range.
+    pub fn add_row(&mut self, row: u32, size: Size, cons: &mut Constraints) {
+        self.rows.insert(row as usize, size, cons);
+        self.row_edits.push(TrackEdit::Insert(row));
+        self.dirty = true;
+    }
+
+    /// Remove a row, returning its size. Panics if `row` is out of range.
+    pub fn remove_row(&mut self, row: u32, cons: &mut Constraints) -> Size {
+        let size = self.rows.remove(row as usize, cons);
+        self.row_edits.push(TrackEdit::Remove(row));
+        self.dirty = true;
+        size
+    }
+
+    /// Change the size of a row. Panics if `row` is out of range.
+    pub fn set_row_size(&mut self, row: u32, size: Size, cons: &mut Constraints) {
+        self.rows.resize(row as usize, size, cons);
+        self.dirty = true;
+    }
+
+    /// Insert a column before `col`, or after the last column if `col` is the number of columns.
+    /// `cons` must be the constraints of the grid's entity. Panics if `col` is out of range.
+    pub fn add_col(&mut self, col: u32, size: Size, cons: &mut Constraints) {
+        self.cols.insert(col as usize, size, cons);
+        self.col_edits.push(TrackEdit::Insert(col));
+        self.dirty = true;
+    }
+
+    /// Remove a column, returning its size. Panics if `col` is out of range.
+    pub fn remove_col(&mut self, col: u32, cons: &mut Constraints) -> Size {
+        let size = self.cols.remove(col as usize, cons);
+        self.col_edits.push(TrackEdit::Remove(col));
+        self.dirty = true;
+        size
+    }
+
+    /// Change the size of a column. Panics if `col` is out of range.
+    pub fn set_col_size(&mut self, col: u32, size: Size, cons: &mut Constraints) {
+        self.cols.resize(col as usize, size, cons);
+        self.dirty = true;
+    }
+
+    /// Constrain `pos` to a cell of the grid. The constraints refer to the boundaries of the
+    /// tracks directly, so they are not updated when tracks are added or removed.
+    #[deprecated(note = "give children `Row` and `Col` components instead")]
+    #[allow(deprecated)]
     pub fn insert(&self, col: u32, row: u32, pos: &Position, cons: &mut Constraints) {
         self.insert_col(col, pos, cons);
         self.insert_row(row, pos, cons);
     }
 
+    #[deprecated(note = "give children a `Col` component instead")]
     pub fn insert_col(&self, col: u32, pos: &Position, cons: &mut Constraints) {
-        cons.add(pos.left_var() | EQ(REQUIRED) | self.cols[col as usize]);
-        cons.add(pos.right_var() | EQ(REQUIRED) | self.cols[(col + 1) as usize]);
+        let cols = self.cols.vars();
+        cons.add(pos.left_var() | EQ(REQUIRED) | cols[col as usize]);
+        cons.add(pos.right_var() | EQ(REQUIRED) | cols[(col + 1) as usize]);
     }
 
+    #[deprecated(note = "give children a `Row` component instead")]
     pub fn insert_row(&self, row: u32, pos: &Position, cons: &mut Constraints) {
-        cons.add(pos.top_var() | EQ(REQUIRED) | self.rows[row as usize]);
-        cons.add(pos.bottom_var() | EQ(REQUIRED) | self.rows[(row + 1) as usize]);
+        let rows = self.rows.vars();
+        cons.add(pos.top_var() | EQ(REQUIRED) | rows[row as usize]);
+        cons.add(pos.bottom_var() | EQ(REQUIRED) | rows[(row + 1) as usize]);
     }
-}
 
-fn layout(
-    start: Variable,
-    mid: impl Iterator<Item = Size>,
-    end: Variable,
-    cons: &mut Constraints,
-) -> Vec<Variable> {
-    let mut vars = Vec::with_capacity(mid.size_hint().0 + 2);
-    let mut size_sum = Expression::from_constant(0.0);
-    let mut ratio_sum = 0.0;
-    let rem = Variable::new();
-    let mut flex_str = 0.0;
-
-    vars.push(start);
-    let mut prev = start;
-    for size in mid {
-        let var = Variable::new();
-        vars.push(var);
-        cons.add(prev | LE(REQUIRED) | var);
-
-        // Tie-breaker constraint. First columns are filled first.
-        cons.add(prev | EQ(flex_str) | var);
-        flex_str += 0.001;
-
-        match size {
-            Size::Abs(size) => {
-                cons.add(var - prev | EQ(STRONG) | size);
-                size_sum += var - prev;
-            }
-            Size::Rel(ratio) => {
-                assert!(ratio > 0.0);
-                cons.add(var - prev | EQ(STRONG) | ratio * rem);
-                ratio_sum += ratio;
-            }
-            Size::Auto => {
-                size_sum += var - prev;
-            }
+    /// Place `child` in `cell`, replacing the constraints of its previous cell if it has moved or
+    /// the tracks have changed.
+    fn place(&mut self, child: Entity, cell: Cell, pos: &Position, cons: &mut Constraints) {
+        if !self.dirty && self.cells.get(&child).map_or(false, |&(old, _)| old == cell) {
+            return;
         }
 
-        prev = var;
+        self.remove(child, Some(&mut *cons));
+        let cell_cons = cell.constraints(self, pos).unwrap_or_else(|| {
+            warn!("Grid child {:?} placed outside of the grid.", child);
+            Vec::new()
+        });
+        cons.extend(cell_cons.iter().cloned());
+        self.cells.insert(child, (cell, cell_cons));
     }
 
-    let mult = ratio_sum.recip();
-    if mult.is_normal() {
-        cons.add(rem | EQ(REQUIRED) | (end - size_sum - start) * mult);
-        cons.add(prev | EQ(REQUIRED) | end);
-    } else {
-        // No relative sizes. Use flex space.
-        cons.add(start | EQ(flex_str) | prev);
-        cons.add(prev | LE(REQUIRED) | end);
+    /// Forget the cell of `child`, removing its constraints from `cons` if it still has them.
+    fn remove(&mut self, child: Entity, cons: Option<&mut Constraints>) {
+        if let (Some((_, cell_cons)), Some(cons)) = (self.cells.remove(&child), cons) {
+            for con in cell_cons {
+                cons.remove(con);
+            }
+        }
     }
-
-    vars
 }
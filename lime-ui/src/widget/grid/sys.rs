@@ -0,0 +1,139 @@
+use specs::prelude::*;
+
+use layout::{Alignment, Constraints, Position};
+use tree::Node;
+use widget::grid::cell::Cell;
+use widget::grid::{
+    Col, ColSpan, Grid, HorizontalAlignment, Margin, Row, RowSpan, VerticalAlignment,
+};
+
+/// Places the children of grids according to their `Row` and `Col` components, updating their
+/// constraints when the components change. When tracks are added or removed, the components are
+/// updated first so children stay in the same tracks.
+pub struct GridSystem;
+
+impl GridSystem {
+    pub const NAME: &'static str = "ui::Grid";
+
+    pub(crate) fn add(_: &mut World, dispatcher: &mut DispatcherBuilder) {
+        dispatcher.add(GridSystem, GridSystem::NAME, &[]);
+    }
+}
+
+impl<'a> System<'a> for GridSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Grid>,
+        ReadStorage<'a, Node>,
+        ReadStorage<'a, Position>,
+        WriteStorage<'a, Constraints>,
+        WriteStorage<'a, Row>,
+        WriteStorage<'a, Col>,
+        WriteStorage<'a, RowSpan>,
+        WriteStorage<'a, ColSpan>,
+        ReadStorage<'a, HorizontalAlignment>,
+        ReadStorage<'a, VerticalAlignment>,
+        ReadStorage<'a, Margin>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut grids,
+            nodes,
+            poss,
+            mut cons,
+            mut rows,
+            mut cols,
+            mut row_spans,
+            mut col_spans,
+            haligns,
+            valigns,
+            margins,
+        ): Self::SystemData,
+    ) {
+        for (grid, node) in (&mut grids, &nodes).join() {
+            for &child in node.children() {
+                if !entities.is_alive(child) {
+                    continue;
+                }
+
+                // Keep children in the same tracks as rows and columns are added and removed.
+                for &edit in &grid.row_edits {
+                    let row = match rows.get(child) {
+                        Some(row) => row.0,
+                        None => break,
+                    };
+                    let span = row_spans.get(child).map_or(1, |span| span.0.max(1));
+                    match edit.apply(row, span) {
+                        Some((new_row, new_span)) => {
+                            if new_row != row {
+                                rows.insert(child, Row(new_row)).unwrap();
+                            }
+                            if new_span != span {
+                                row_spans.insert(child, RowSpan(new_span)).unwrap();
+                            }
+                        }
+                        None => {
+                            rows.remove(child);
+                        }
+                    }
+                }
+                for &edit in &grid.col_edits {
+                    let col = match cols.get(child) {
+                        Some(col) => col.0,
+                        None => break,
+                    };
+                    let span = col_spans.get(child).map_or(1, |span| span.0.max(1));
+                    match edit.apply(col, span) {
+                        Some((new_col, new_span)) => {
+                            if new_col != col {
+                                cols.insert(child, Col(new_col)).unwrap();
+                            }
+                            if new_span != span {
+                                col_spans.insert(child, ColSpan(new_span)).unwrap();
+                            }
+                        }
+                        None => {
+                            cols.remove(child);
+                        }
+                    }
+                }
+            }
+            grid.row_edits.clear();
+            grid.col_edits.clear();
+
+            for &child in node.children() {
+                if !entities.is_alive(child) {
+                    continue;
+                }
+
+                let row = rows.get(child);
+                let col = cols.get(child);
+                let cell = if row.is_some() || col.is_some() {
+                    Some(Cell {
+                        col: col.map(|col| col.0),
+                        row: row.map(|row| row.0),
+                        col_span: col_spans.get(child).map_or(1, |span| span.0.max(1)),
+                        row_span: row_spans.get(child).map_or(1, |span| span.0.max(1)),
+                        halign: haligns.get(child).map_or(Alignment::Stretch, |align| align.0),
+                        valign: valigns.get(child).map_or(Alignment::Stretch, |align| align.0),
+                        margin: margins.get(child).cloned().unwrap_or_default(),
+                    })
+                } else {
+                    None
+                };
+
+                match (cell, poss.get(child), cons.get_mut(child)) {
+                    (Some(cell), Some(pos), Some(con)) => grid.place(child, cell, pos, con),
+                    (_, _, con) => grid.remove(child, con),
+                }
+            }
+
+            // The constraints of deleted children were removed along with them.
+            grid.cells.retain(|&child, _| entities.is_alive(child));
+            grid.dirty = false;
+        }
+    }
+}
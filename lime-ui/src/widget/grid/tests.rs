@@ -1,6 +1,8 @@
 use std::iter;
 
 use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
+use fnv::FnvHashMap;
 use render::d2::Point;
 use serde_json as json;
 use specs::prelude::*;
@@ -8,8 +10,9 @@ use specs_mirror::StorageMutExt;
 use winit::WindowEvent::Resized;
 
 use super::*;
+use de;
 use event::tests::run_window_event;
-use layout::{Constraints, ConstraintsBuilder, Position};
+use layout::{Alignment, Constraints, ConstraintsBuilder, IntrinsicSize, Position};
 use tests::init_test;
 use tree::{Node, Root};
 use State;
@@ -45,6 +48,8 @@ fn create_grid(
         .build()
 }
 
+// Places the rect with the deprecated `Grid::insert`, which these tests still cover.
+#[allow(deprecated)]
 fn create_rect(
     world: &mut World,
     parent: Entity,
@@ -371,6 +376,282 @@ fn size() {
     }
 }
 
+#[test]
+fn intrinsic() {
+    let (mut world, mut dispatcher) = init_test();
+
+    let grid = create_root_grid(
+        &mut world,
+        vec![Size::Auto, Size::Rel(1.0)],
+        vec![Size::Auto],
+    );
+
+    let r1 = create_rect(&mut world, grid, 0, 0, |bld| bld);
+    let r2 = create_rect(&mut world, grid, 1, 0, |bld| bld);
+    world.write_storage().insert(r1, IntrinsicSize::new()).unwrap();
+
+    let set_size = |world: &World, size: (f64, f64)| {
+        let poss = world.read_storage::<Position>();
+        let mut cons = world.write_storage::<Constraints>();
+        let mut sizes = world.write_storage::<IntrinsicSize>();
+        sizes
+            .get_mut(r1)
+            .unwrap()
+            .set(size, poss.get(r1).unwrap(), cons.get_mut(r1).unwrap());
+    };
+
+    set_size(&world, (150.0, 40.0));
+    run_window_event(&mut world, &mut dispatcher, Resized((1000, 750).into()));
+
+    {
+        let comps = world.read_storage::<Position>();
+        let p1 = comps.get(r1).unwrap();
+        assert_approx_eq(p1.top_left(), Point(0.0, 0.0));
+        assert_approx_eq(p1.bottom_right(), Point(150.0, 40.0));
+        let p2 = comps.get(r2).unwrap();
+        assert_approx_eq(p2.top_left(), Point(150.0, 0.0));
+        assert_approx_eq(p2.bottom_right(), Point(1000.0, 40.0));
+    }
+
+    set_size(&world, (250.0, 60.0));
+    dispatcher.dispatch(&world.res);
+
+    {
+        let comps = world.read_storage::<Position>();
+        let p1 = comps.get(r1).unwrap();
+        assert_approx_eq(p1.top_left(), Point(0.0, 0.0));
+        assert_approx_eq(p1.bottom_right(), Point(250.0, 60.0));
+        let p2 = comps.get(r2).unwrap();
+        assert_approx_eq(p2.top_left(), Point(250.0, 0.0));
+        assert_approx_eq(p2.bottom_right(), Point(1000.0, 60.0));
+    }
+}
+
+fn create_cell(
+    world: &mut World,
+    parent: Entity,
+    col: u32,
+    row: u32,
+    build: impl FnOnce(ConstraintsBuilder) -> ConstraintsBuilder,
+) -> Entity {
+    let pos = Position::new();
+    let cons = build(pos.constraints_builder()).build();
+    Node::with_parent(world.create_entity(), parent)
+        .with(pos)
+        .with(cons)
+        .with(Col(col))
+        .with(Row(row))
+        .build()
+}
+
+#[test]
+fn cell() {
+    let (mut world, mut dispatcher) = init_test();
+
+    let grid = create_root_grid(
+        &mut world,
+        vec![Size::Abs(200.0), Size::Abs(200.0), Size::Rel(1.0)],
+        vec![Size::Abs(100.0), Size::Abs(100.0)],
+    );
+
+    let label = create_cell(&mut world, grid, 0, 0, |bld| bld);
+    world.write_storage().insert(label, ColSpan(2)).unwrap();
+
+    let button = create_cell(&mut world, grid, 1, 1, |bld| {
+        bld.size((100.0, 50.0), STRONG)
+    });
+    world
+        .write_storage()
+        .insert(button, HorizontalAlignment(Alignment::Center))
+        .unwrap();
+    world
+        .write_storage()
+        .insert(button, VerticalAlignment(Alignment::Center))
+        .unwrap();
+
+    let margin = create_cell(&mut world, grid, 2, 1, |bld| bld);
+    let margins = Margin {
+        left: 10.0,
+        top: 5.0,
+        right: 10.0,
+        bottom: 5.0,
+    };
+    world.write_storage().insert(margin, margins).unwrap();
+
+    run_window_event(&mut world, &mut dispatcher, Resized((1000, 750).into()));
+
+    {
+        let comps = world.read_storage::<Position>();
+        let p1 = comps.get(label).unwrap();
+        assert_approx_eq(p1.top_left(), Point(0.0, 0.0));
+        assert_approx_eq(p1.bottom_right(), Point(400.0, 100.0));
+        let p2 = comps.get(button).unwrap();
+        assert_approx_eq(p2.top_left(), Point(250.0, 125.0));
+        assert_approx_eq(p2.bottom_right(), Point(350.0, 175.0));
+        let p3 = comps.get(margin).unwrap();
+        assert_approx_eq(p3.top_left(), Point(410.0, 105.0));
+        assert_approx_eq(p3.bottom_right(), Point(990.0, 195.0));
+    }
+
+    world.write_storage().insert(button, Col(0)).unwrap();
+    world
+        .write_storage()
+        .insert(button, VerticalAlignment(Alignment::End))
+        .unwrap();
+    dispatcher.dispatch(&world.res);
+
+    {
+        let comps = world.read_storage::<Position>();
+        let p2 = comps.get(button).unwrap();
+        assert_approx_eq(p2.top_left(), Point(50.0, 150.0));
+        assert_approx_eq(p2.bottom_right(), Point(150.0, 200.0));
+    }
+}
+
+#[test]
+fn cell_row_only() {
+    let (mut world, mut dispatcher) = init_test();
+
+    let grid = create_root_grid(
+        &mut world,
+        vec![Size::Abs(200.0), Size::Rel(1.0)],
+        vec![Size::Abs(100.0), Size::Abs(100.0)],
+    );
+
+    // Without a `Col`, the horizontal position is left to the child's own constraints.
+    let pos = Position::new();
+    let cons = pos
+        .constraints_builder()
+        .with(pos.left_var() | EQ(REQUIRED) | 300.0)
+        .width(50.0, REQUIRED)
+        .build();
+    let rect = Node::with_parent(world.create_entity(), grid)
+        .with(pos)
+        .with(cons)
+        .with(Row(1))
+        .build();
+
+    run_window_event(&mut world, &mut dispatcher, Resized((1000, 750).into()));
+
+    let comps = world.read_storage::<Position>();
+    let pos = comps.get(rect).unwrap();
+    assert_approx_eq(pos.top_left(), Point(300.0, 100.0));
+    assert_approx_eq(pos.bottom_right(), Point(350.0, 200.0));
+}
+
+fn edit_grid(world: &World, grid: Entity, edit: impl FnOnce(&mut Grid, &mut Constraints)) {
+    let mut grids = world.write_storage::<Grid>();
+    let mut cons = world.write_storage::<Constraints>();
+    edit(grids.get_mut(grid).unwrap(), cons.get_mut(grid).unwrap());
+}
+
+#[test]
+fn edit() {
+    let (mut world, mut dispatcher) = init_test();
+
+    let grid = create_root_grid(
+        &mut world,
+        vec![Size::Abs(100.0)],
+        vec![Size::Abs(100.0), Size::Abs(100.0)],
+    );
+    let r1 = create_cell(&mut world, grid, 0, 1, |bld| bld);
+    let r2 = create_cell(&mut world, grid, 0, 0, |bld| bld);
+    world.write_storage().insert(r2, RowSpan(2)).unwrap();
+
+    run_window_event(&mut world, &mut dispatcher, Resized((1000, 750).into()));
+
+    {
+        let comps = world.read_storage::<Position>();
+        let p1 = comps.get(r1).unwrap();
+        assert_approx_eq(p1.top_left(), Point(0.0, 100.0));
+        assert_approx_eq(p1.bottom_right(), Point(100.0, 200.0));
+        let p2 = comps.get(r2).unwrap();
+        assert_approx_eq(p2.top_left(), Point(0.0, 0.0));
+        assert_approx_eq(p2.bottom_right(), Point(100.0, 200.0));
+    }
+
+    // Children after the new row move down, and children spanning it grow.
+    edit_grid(&world, grid, |grid, cons| {
+        grid.add_row(0, Size::Abs(50.0), cons);
+        grid.add_row(2, Size::Abs(10.0), cons);
+        grid.add_col(1, Size::Rel(1.0), cons);
+    });
+    dispatcher.dispatch(&world.res);
+
+    assert_eq!(world.read_storage::<Row>().get(r1), Some(&Row(3)));
+    assert_eq!(world.read_storage::<Row>().get(r2), Some(&Row(1)));
+    assert_eq!(world.read_storage::<RowSpan>().get(r2), Some(&RowSpan(3)));
+    {
+        let comps = world.read_storage::<Position>();
+        let p1 = comps.get(r1).unwrap();
+        assert_approx_eq(p1.top_left(), Point(0.0, 160.0));
+        assert_approx_eq(p1.bottom_right(), Point(100.0, 260.0));
+        let p2 = comps.get(r2).unwrap();
+        assert_approx_eq(p2.top_left(), Point(0.0, 50.0));
+        assert_approx_eq(p2.bottom_right(), Point(100.0, 260.0));
+    }
+
+    world.write_storage().insert(r1, Col(1)).unwrap();
+    edit_grid(&world, grid, |grid, cons| {
+        grid.set_row_size(0, Size::Abs(30.0), cons);
+    });
+    dispatcher.dispatch(&world.res);
+
+    {
+        let comps = world.read_storage::<Position>();
+        let p1 = comps.get(r1).unwrap();
+        assert_approx_eq(p1.top_left(), Point(100.0, 140.0));
+        assert_approx_eq(p1.bottom_right(), Point(1000.0, 240.0));
+    }
+
+    // Children after a removed row move up, children spanning it shrink, and children only in a
+    // removed column lose their `Col`.
+    edit_grid(&world, grid, |grid, cons| {
+        assert_eq!(grid.remove_row(0, cons), Size::Abs(30.0));
+        assert_eq!(grid.remove_row(1, cons), Size::Abs(10.0));
+        assert_eq!(grid.remove_col(0, cons), Size::Abs(100.0));
+        grid.add_row(2, Size::Auto, cons);
+    });
+    dispatcher.dispatch(&world.res);
+
+    {
+        let grids = world.read_storage::<Grid>();
+        let g = grids.get(grid).unwrap();
+        assert_eq!(g.rows(), &[Size::Abs(100.0), Size::Abs(100.0), Size::Auto]);
+        assert_eq!(g.cols(), &[Size::Rel(1.0)]);
+    }
+    assert_eq!(world.read_storage::<Row>().get(r1), Some(&Row(1)));
+    assert_eq!(world.read_storage::<Col>().get(r1), Some(&Col(0)));
+    assert_eq!(world.read_storage::<Row>().get(r2), Some(&Row(0)));
+    assert_eq!(world.read_storage::<RowSpan>().get(r2), Some(&RowSpan(2)));
+    assert_eq!(world.read_storage::<Col>().get(r2), None);
+
+    {
+        let comps = world.read_storage::<Position>();
+        let p1 = comps.get(r1).unwrap();
+        assert_approx_eq(p1.top_left(), Point(0.0, 100.0));
+        assert_approx_eq(p1.bottom_right(), Point(1000.0, 200.0));
+        let p2 = comps.get(r2).unwrap();
+        assert_ulps_eq!(p2.top_left().1, 0.0);
+        assert_ulps_eq!(p2.bottom_right().1, 200.0);
+    }
+}
+
+#[test]
+fn track_edit() {
+    use super::track::TrackEdit::*;
+
+    assert_eq!(Insert(1).apply(2, 1), Some((3, 1)));
+    assert_eq!(Insert(2).apply(2, 1), Some((3, 1)));
+    assert_eq!(Insert(3).apply(2, 2), Some((2, 3)));
+    assert_eq!(Insert(4).apply(2, 2), Some((2, 2)));
+    assert_eq!(Remove(1).apply(2, 1), Some((1, 1)));
+    assert_eq!(Remove(2).apply(2, 2), Some((2, 1)));
+    assert_eq!(Remove(3).apply(2, 2), Some((2, 1)));
+    assert_eq!(Remove(2).apply(2, 1), None);
+    assert_eq!(Remove(3).apply(2, 1), Some((2, 1)));
+}
+
 fn set_needs_layout(world: &mut World, entity: Entity, value: bool) {
     let mut storage = world.write_storage::<State>();
     let (state, chan) = storage.modify(entity).unwrap();
@@ -378,6 +659,7 @@ fn set_needs_layout(world: &mut World, entity: Entity, value: bool) {
 }
 
 #[test]
+#[allow(deprecated)]
 fn state() {
     let (mut world, mut dispatcher) = init_test();
 
@@ -444,3 +726,61 @@ fn de() {
         Size::Rel(1.0)
     );
 }
+
+#[test]
+fn de_cell() {
+    const DATA: &'static str = r#"
+    {
+        "grid": {
+            "Grid": {
+                "cols": [{ "type": "auto" }, { "type": "auto" }],
+                "rows": [{ "type": "auto" }]
+            },
+            "Children": {
+                "label": {
+                    "Row": 0,
+                    "Col": 0,
+                    "ColSpan": 2,
+                    "HorizontalAlignment": "center",
+                    "VerticalAlignment": "stretch",
+                    "Margin": { "left": 4, "right": 4 }
+                }
+            }
+        }
+    }
+    "#;
+
+    let (mut world, _) = init_test();
+    let registry = de::Registry::new();
+    let mut name_map = FnvHashMap::default();
+
+    de::deserialize_with_names(
+        &mut json::Deserializer::from_str(DATA),
+        &registry,
+        &mut world.res,
+        &mut name_map,
+    ).unwrap();
+
+    let label = name_map["label"];
+    assert_eq!(world.read_storage::<Row>().get(label), Some(&Row(0)));
+    assert_eq!(world.read_storage::<Col>().get(label), Some(&Col(0)));
+    assert_eq!(world.read_storage::<ColSpan>().get(label), Some(&ColSpan(2)));
+    assert_eq!(
+        world.read_storage::<HorizontalAlignment>().get(label),
+        Some(&HorizontalAlignment(Alignment::Center))
+    );
+    assert_eq!(
+        world.read_storage::<VerticalAlignment>().get(label),
+        Some(&VerticalAlignment(Alignment::Stretch))
+    );
+    assert_eq!(
+        world.read_storage::<Margin>().get(label),
+        Some(&Margin {
+            left: 4.0,
+            top: 0.0,
+            right: 4.0,
+            bottom: 0.0,
+        })
+    );
+    assert!(world.read_storage::<Constraints>().get(label).is_some());
+}
@@ -0,0 +1,179 @@
+use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
+use cassowary::{Constraint, Expression, Variable};
+
+use layout::Constraints;
+use widget::grid::Size;
+
+/// The rows or columns of a grid. Each track keeps its own constraints, so they can be updated
+/// incrementally as tracks are added, removed or resized.
+pub(in widget::grid) struct Tracks {
+    sizes: Vec<Size>,
+    // The boundaries between tracks, including the start and end of the grid.
+    vars: Vec<Variable>,
+    end: Variable,
+    rem: Variable,
+    track_cons: Vec<Vec<Constraint>>,
+    end_cons: Vec<Constraint>,
+}
+
+impl Tracks {
+    pub fn new(
+        start: Variable,
+        sizes: impl IntoIterator<Item = Size>,
+        end: Variable,
+        cons: &mut Constraints,
+    ) -> Self {
+        let sizes: Vec<Size> = sizes.into_iter().collect();
+        let mut vars = Vec::with_capacity(sizes.len() + 1);
+        vars.push(start);
+        vars.extend(sizes.iter().map(|_| Variable::new()));
+
+        cons.reserve(3 * sizes.len() + 2);
+        let mut tracks = Tracks {
+            sizes,
+            vars,
+            end,
+            rem: Variable::new(),
+            track_cons: Vec::new(),
+            end_cons: Vec::new(),
+        };
+        tracks.build_from(0, cons);
+        tracks
+    }
+
+    pub fn sizes(&self) -> &[Size] {
+        &self.sizes
+    }
+
+    pub fn vars(&self) -> &[Variable] {
+        &self.vars
+    }
+
+    pub fn insert(&mut self, idx: usize, size: Size, cons: &mut Constraints) {
+        assert!(idx <= self.sizes.len(), "track index out of range");
+        self.clear_from(idx, cons);
+        self.sizes.insert(idx, size);
+        self.vars.insert(idx + 1, Variable::new());
+        self.build_from(idx, cons);
+    }
+
+    pub fn remove(&mut self, idx: usize, cons: &mut Constraints) -> Size {
+        assert!(idx < self.sizes.len(), "track index out of range");
+        self.clear_from(idx, cons);
+        self.vars.remove(idx + 1);
+        let size = self.sizes.remove(idx);
+        self.build_from(idx, cons);
+        size
+    }
+
+    pub fn resize(&mut self, idx: usize, size: Size, cons: &mut Constraints) {
+        assert!(idx < self.sizes.len(), "track index out of range");
+        self.clear_from(idx, cons);
+        self.sizes[idx] = size;
+        self.build_from(idx, cons);
+    }
+
+    /// Remove the constraints of the tracks from `idx` onwards, and of the end of the grid.
+    fn clear_from(&mut self, idx: usize, cons: &mut Constraints) {
+        for track_cons in self.track_cons.drain(idx..) {
+            for con in track_cons {
+                cons.remove(con);
+            }
+        }
+        for con in self.end_cons.drain(..) {
+            cons.remove(con);
+        }
+    }
+
+    /// Add constraints for the tracks from `idx` onwards, and for the end of the grid. Later
+    /// tracks depend on the position and index of earlier ones, so they are rebuilt too.
+    fn build_from(&mut self, idx: usize, cons: &mut Constraints) {
+        for idx in idx..self.sizes.len() {
+            let track_cons = self.track_constraints(idx);
+            cons.extend(track_cons.iter().cloned());
+            self.track_cons.push(track_cons);
+        }
+
+        self.end_cons = self.end_constraints();
+        cons.extend(self.end_cons.iter().cloned());
+    }
+
+    fn track_constraints(&self, idx: usize) -> Vec<Constraint> {
+        let prev = self.vars[idx];
+        let var = self.vars[idx + 1];
+        let mut cons = vec![
+            prev | LE(REQUIRED) | var,
+            // Tie-breaker constraint. First columns are filled first.
+            prev | EQ(flex_strength(idx)) | var,
+        ];
+
+        match self.sizes[idx] {
+            Size::Abs(size) => cons.push(var - prev | EQ(STRONG) | size),
+            Size::Rel(ratio) => {
+                assert!(ratio > 0.0);
+                cons.push(var - prev | EQ(STRONG) | ratio * self.rem);
+            }
+            Size::Auto => cons.push(var - prev | EQ(WEAK) | 0.0),
+        }
+        cons
+    }
+
+    fn end_constraints(&self) -> Vec<Constraint> {
+        let start = self.vars[0];
+        let last = *self.vars.last().unwrap();
+
+        let mut size_sum = Expression::from_constant(0.0);
+        let mut ratio_sum = 0.0;
+        for (idx, &size) in self.sizes.iter().enumerate() {
+            match size {
+                Size::Rel(ratio) => ratio_sum += ratio,
+                Size::Abs(_) | Size::Auto => size_sum += self.vars[idx + 1] - self.vars[idx],
+            }
+        }
+
+        let mult = ratio_sum.recip();
+        if mult.is_normal() {
+            vec![
+                self.rem | EQ(REQUIRED) | (self.end - size_sum - start) * mult,
+                last | EQ(REQUIRED) | self.end,
+            ]
+        } else {
+            // No relative sizes. Use flex space.
+            vec![
+                start | EQ(flex_strength(self.sizes.len())) | last,
+                last | LE(REQUIRED) | self.end,
+            ]
+        }
+    }
+}
+
+/// A row or column added to or removed from a grid, recorded so that the `GridSystem` can move
+/// children placed after it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(in widget::grid) enum TrackEdit {
+    Insert(u32),
+    Remove(u32),
+}
+
+impl TrackEdit {
+    /// The index and span of a child covering `span` tracks from `index` after this edit, or
+    /// `None` if every track it covered was removed.
+    pub fn apply(self, index: u32, span: u32) -> Option<(u32, u32)> {
+        match self {
+            TrackEdit::Insert(at) if at <= index => Some((index + 1, span)),
+            TrackEdit::Insert(at) if at < index + span => Some((index, span + 1)),
+            TrackEdit::Remove(at) if at < index => Some((index - 1, span)),
+            TrackEdit::Remove(at) if at < index + span => if span > 1 {
+                Some((index, span - 1))
+            } else {
+                None
+            },
+            _ => Some((index, span)),
+        }
+    }
+}
+
+fn flex_strength(idx: usize) -> f64 {
+    0.001 * idx as f64
+}
@@ -1,2 +1,5 @@
 pub mod button;
 pub mod grid;
+pub mod scroll;
+pub mod stack;
+pub mod text;
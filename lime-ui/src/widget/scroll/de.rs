@@ -0,0 +1,66 @@
+use std::borrow::Cow;
+
+use erased_serde as erased;
+use serde::de as serde;
+use specs::prelude::*;
+
+use de::{Deserialize, Seed};
+use draw::Brush;
+use layout::{Constraints, Position};
+use widget::scroll::ScrollView;
+
+impl Deserialize for ScrollView {
+    fn deserialize<'de, 'a>(
+        mut seed: Seed<'de, 'a>,
+        deserializer: &mut erased::Deserializer<'de>,
+    ) -> Result<Self, erased::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "ScrollView")]
+        struct ScrollViewDe<'c> {
+            #[serde(borrow)]
+            content: Cow<'c, str>,
+            #[serde(default = "default_true")]
+            horizontal: bool,
+            #[serde(default = "default_true")]
+            vertical: bool,
+            #[serde(default)]
+            scrollbar: Option<Brush>,
+            #[serde(default)]
+            scrollbar_width: f32,
+        }
+
+        fn default_true() -> bool {
+            true
+        }
+
+        let ScrollViewDe {
+            content,
+            horizontal,
+            vertical,
+            scrollbar,
+            scrollbar_width,
+        } = serde::Deserialize::deserialize(deserializer)?;
+        let content = seed.get_entity(content)?;
+
+        let mut poss = WriteStorage::<Position>::fetch(seed.res);
+        let content_pos = poss.entry(content)
+            .unwrap()
+            .or_insert_with(Default::default)
+            .clone();
+        let pos = poss.entry(seed.entity)
+            .unwrap()
+            .or_insert_with(Default::default);
+
+        let mut cons = WriteStorage::<Constraints>::fetch(seed.res);
+        let con = cons.entry(seed.entity)
+            .unwrap()
+            .or_insert_with(|| Constraints::new(pos));
+
+        let mut scroll =
+            ScrollView::new(pos, con, content, &content_pos).with_directions(horizontal, vertical);
+        if let Some(brush) = scrollbar {
+            scroll = scroll.with_scrollbar(brush, scrollbar_width);
+        }
+        Ok(scroll)
+    }
+}
@@ -0,0 +1,155 @@
+mod de;
+mod sys;
+#[cfg(test)]
+mod tests;
+
+pub use self::sys::ScrollSystem;
+
+use cassowary::strength::REQUIRED;
+use cassowary::Constraint;
+use cassowary::WeightedRelation::*;
+use render::d2::{Point, Rect};
+use specs::prelude::*;
+
+use draw::Brush;
+use layout::{Constraints, Position};
+
+/// A viewport onto a content entity which may be larger than it. The content is offset by the
+/// scroll position, and drawing and hit-testing of the scroll view's children are clipped to its
+/// `Position`.
+#[derive(Component)]
+#[storage(HashMapStorage)]
+pub struct ScrollView {
+    content: Entity,
+    offset: Point,
+    horizontal: bool,
+    vertical: bool,
+    scrollbar: Option<Brush>,
+    scrollbar_width: f32,
+    cons: [Constraint; 2],
+}
+
+impl ScrollView {
+    pub fn new(
+        pos: &Position,
+        cons: &mut Constraints,
+        content: Entity,
+        content_pos: &Position,
+    ) -> Self {
+        let offset = Point::origin();
+        let view_cons = offset_constraints(pos, content_pos, offset);
+        cons.extend(view_cons.iter().cloned());
+        ScrollView {
+            content,
+            offset,
+            horizontal: true,
+            vertical: true,
+            scrollbar: None,
+            scrollbar_width: 0.0,
+            cons: view_cons,
+        }
+    }
+
+    /// Set which directions the content can be scrolled in.
+    pub fn with_directions(mut self, horizontal: bool, vertical: bool) -> Self {
+        self.horizontal = horizontal;
+        self.vertical = vertical;
+        self
+    }
+
+    pub fn with_scrollbar(mut self, brush: Brush, width: f32) -> Self {
+        self.scrollbar = Some(brush);
+        self.scrollbar_width = width;
+        self
+    }
+
+    pub fn content(&self) -> Entity {
+        self.content
+    }
+
+    pub fn offset(&self) -> Point {
+        self.offset
+    }
+
+    pub fn scrollbar(&self) -> Option<&Brush> {
+        self.scrollbar.as_ref()
+    }
+
+    /// Scroll to `offset`, clamped so the content covers as much of the view as possible.
+    pub fn scroll_to(
+        &mut self,
+        offset: Point,
+        pos: &Position,
+        cons: &mut Constraints,
+        content_pos: &Position,
+    ) {
+        let max = Point(
+            (content_pos.width() - pos.width()).max(0.0),
+            (content_pos.height() - pos.height()).max(0.0),
+        );
+        let offset = Point(
+            if self.horizontal {
+                offset.0.max(0.0).min(max.0)
+            } else {
+                0.0
+            },
+            if self.vertical {
+                offset.1.max(0.0).min(max.1)
+            } else {
+                0.0
+            },
+        );
+
+        if offset != self.offset {
+            self.offset = offset;
+            let view_cons = offset_constraints(pos, content_pos, offset);
+            for (old, new) in self.cons.iter_mut().zip(view_cons.iter()) {
+                cons.remove(old.clone());
+                cons.add(new.clone());
+                *old = new.clone();
+            }
+        }
+    }
+
+    pub fn scroll_by(
+        &mut self,
+        delta: Point,
+        pos: &Position,
+        cons: &mut Constraints,
+        content_pos: &Position,
+    ) {
+        let offset = Point(self.offset.0 + delta.0, self.offset.1 + delta.1);
+        self.scroll_to(offset, pos, cons, content_pos);
+    }
+
+    /// The rectangles to draw scrollbar thumbs in.
+    pub fn scrollbars(&self, pos: &Position, content_pos: &Position) -> Vec<Rect> {
+        let mut bars = Vec::new();
+        if self.scrollbar.is_none() {
+            return bars;
+        }
+
+        let width = self.scrollbar_width;
+        if self.vertical && content_pos.height() > pos.height() {
+            let (start, end) = thumb(pos.top(), pos.height(), content_pos.height(), self.offset.1);
+            bars.push(Rect::new(pos.right() - width, start, pos.right(), end));
+        }
+        if self.horizontal && content_pos.width() > pos.width() {
+            let (start, end) = thumb(pos.left(), pos.width(), content_pos.width(), self.offset.0);
+            bars.push(Rect::new(start, pos.bottom() - width, end, pos.bottom()));
+        }
+        bars
+    }
+}
+
+fn offset_constraints(pos: &Position, content_pos: &Position, offset: Point) -> [Constraint; 2] {
+    [
+        content_pos.left_var() | EQ(REQUIRED) | pos.left_var() - f64::from(offset.0),
+        content_pos.top_var() | EQ(REQUIRED) | pos.top_var() - f64::from(offset.1),
+    ]
+}
+
+fn thumb(start: f32, view: f32, content: f32, offset: f32) -> (f32, f32) {
+    let thumb_start = start + view * offset / content;
+    (thumb_start, thumb_start + view * view / content)
+}
@@ -0,0 +1,72 @@
+use render::d2::Point;
+use shrev::{EventChannel, ReaderId};
+use specs::prelude::*;
+
+use event::{Event, EventKind, EventPhase, EventSystem, MouseEvent, Propagation};
+use layout::{Constraints, Position};
+use widget::scroll::ScrollView;
+
+pub struct ScrollSystem {
+    reader: ReaderId<Event>,
+}
+
+impl ScrollSystem {
+    pub const NAME: &'static str = "ui::Scroll";
+
+    pub(crate) fn add(world: &mut World, dispatcher: &mut DispatcherBuilder) {
+        let reader = world
+            .write_resource::<EventChannel<Event>>()
+            .register_reader();
+        dispatcher.add(
+            ScrollSystem { reader },
+            ScrollSystem::NAME,
+            &[EventSystem::NAME],
+        );
+    }
+}
+
+impl<'a> System<'a> for ScrollSystem {
+    type SystemData = (
+        ReadExpect<'a, EventChannel<Event>>,
+        WriteExpect<'a, Propagation>,
+        WriteStorage<'a, ScrollView>,
+        WriteStorage<'a, Constraints>,
+        ReadStorage<'a, Position>,
+    );
+
+    fn run(
+        &mut self,
+        (events, mut propagation, mut scrolls, mut cons, poss): Self::SystemData,
+    ) {
+        for &event in events.read(&mut self.reader) {
+            if event.phase() == EventPhase::Capture || propagation.is_stopped(&event) {
+                continue;
+            }
+
+            if let EventKind::Mouse(MouseEvent::Scroll(x, y, _)) = event.kind() {
+                let ent = event.entity();
+                if let (Some(scroll), Some(pos), Some(con)) =
+                    (scrolls.get_mut(ent), poss.get(ent), cons.get_mut(ent))
+                {
+                    if let Some(content_pos) = poss.get(scroll.content()) {
+                        let offset = scroll.offset();
+                        scroll.scroll_by(Point(-x, -y), pos, con, content_pos);
+                        // Only the innermost view that can still scroll consumes the event, so
+                        // nested views don't all scroll at once.
+                        if scroll.offset() != offset {
+                            propagation.stop(&event);
+                        }
+                    }
+                }
+            }
+        }
+
+        // The content may have shrunk since the last frame.
+        for (scroll, pos, con) in (&mut scrolls, &poss, &mut cons).join() {
+            if let Some(content_pos) = poss.get(scroll.content()) {
+                let offset = scroll.offset();
+                scroll.scroll_to(offset, pos, con, content_pos);
+            }
+        }
+    }
+}
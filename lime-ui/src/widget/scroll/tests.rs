@@ -0,0 +1,213 @@
+use std::mem;
+
+use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
+use fnv::FnvHashMap;
+use render::d2::Point;
+use serde_json as json;
+use specs::prelude::*;
+use winit::dpi::LogicalPosition;
+use winit::{ModifiersState, MouseScrollDelta, TouchPhase, WindowEvent};
+
+use super::*;
+use de;
+use event::tests::{emit_mouse_event, run_window_event};
+use event::{MouseEvent, MouseFocus};
+use tests::init_test;
+use tree::{Node, Root};
+
+fn create_scroll_view(world: &mut World) -> (Entity, Entity) {
+    let root = world.read_resource::<Root>().entity();
+    create_nested_view(world, root, 100.0, 300.0)
+}
+
+/// Create a vertical scroll view in the top left corner of `parent`.
+fn create_nested_view(
+    world: &mut World,
+    parent: Entity,
+    height: f64,
+    content_height: f64,
+) -> (Entity, Entity) {
+    let parent_pos = world.read_storage::<Position>().get(parent).unwrap().clone();
+
+    let pos = Position::new();
+    let mut cons = pos
+        .constraints_builder()
+        .with(pos.left_var() | EQ(REQUIRED) | parent_pos.left_var())
+        .with(pos.top_var() | EQ(REQUIRED) | parent_pos.top_var())
+        .with(pos.width_var() | EQ(REQUIRED) | 100.0)
+        .with(pos.height_var() | EQ(REQUIRED) | height)
+        .build();
+    let view = Node::with_parent(world.create_entity(), parent).build();
+
+    let content_pos = Position::new();
+    let content_cons = content_pos
+        .constraints_builder()
+        .with(content_pos.width_var() | EQ(REQUIRED) | pos.width_var())
+        .with(content_pos.height_var() | EQ(REQUIRED) | content_height)
+        .build();
+    let content = Node::with_parent(world.create_entity(), view)
+        .with(content_pos.clone())
+        .with(content_cons)
+        .build();
+
+    let scroll =
+        ScrollView::new(&pos, &mut cons, content, &content_pos).with_directions(false, true);
+    world.write_storage().insert(view, pos).unwrap();
+    world.write_storage().insert(view, cons).unwrap();
+    world.write_storage().insert(view, scroll).unwrap();
+    (view, content)
+}
+
+fn scroll(world: &mut World, dispatcher: &mut Dispatcher, view: Entity, x: f32, y: f32) {
+    emit_mouse_event(
+        world,
+        view,
+        MouseEvent::Scroll(x, y, ModifiersState::default()),
+    );
+    dispatcher.dispatch(&world.res);
+}
+
+fn check_content(world: &mut World, view: Entity, content: Entity, top: f32) {
+    let poss = world.read_storage::<Position>();
+    let content_pos = poss.get(content).unwrap();
+    assert_ulps_eq!(content_pos.left(), 0.0);
+    assert_ulps_eq!(content_pos.top(), top);
+    assert_ulps_eq!(content_pos.height(), 300.0);
+    let scrolls = world.read_storage::<ScrollView>();
+    assert_eq!(scrolls.get(view).unwrap().offset(), Point(0.0, -top));
+}
+
+#[test]
+fn scroll_offset() {
+    let (mut world, mut dispatcher) = init_test();
+    let (view, content) = create_scroll_view(&mut world);
+    run_window_event(
+        &mut world,
+        &mut dispatcher,
+        WindowEvent::Resized((1000, 1000).into()),
+    );
+    check_content(&mut world, view, content, 0.0);
+
+    scroll(&mut world, &mut dispatcher, view, -20.0, -50.0);
+    check_content(&mut world, view, content, -50.0);
+
+    scroll(&mut world, &mut dispatcher, view, 0.0, -1000.0);
+    check_content(&mut world, view, content, -200.0);
+
+    scroll(&mut world, &mut dispatcher, view, 0.0, 80.0);
+    check_content(&mut world, view, content, -120.0);
+
+    scroll(&mut world, &mut dispatcher, view, 0.0, 1000.0);
+    check_content(&mut world, view, content, 0.0);
+}
+
+#[test]
+fn scroll_nested() {
+    let (mut world, mut dispatcher) = init_test();
+    let (outer, outer_content) = create_scroll_view(&mut world);
+    let (inner, _) = create_nested_view(&mut world, outer_content, 50.0, 150.0);
+    run_window_event(
+        &mut world,
+        &mut dispatcher,
+        WindowEvent::Resized((1000, 1000).into()),
+    );
+    run_window_event(
+        &mut world,
+        &mut dispatcher,
+        WindowEvent::CursorMoved {
+            position: LogicalPosition::new(50.0, 25.0),
+            modifiers: ModifiersState::default(),
+            device_id: unsafe { mem::zeroed() },
+        },
+    );
+
+    let mut wheel = |world: &mut World, y: f64| {
+        run_window_event(
+            world,
+            &mut dispatcher,
+            WindowEvent::MouseWheel {
+                delta: MouseScrollDelta::PixelDelta(LogicalPosition::new(0.0, y)),
+                phase: TouchPhase::Moved,
+                modifiers: ModifiersState::default(),
+                device_id: unsafe { mem::zeroed() },
+            },
+        );
+        let scrolls = world.read_storage::<ScrollView>();
+        (
+            scrolls.get(outer).unwrap().offset(),
+            scrolls.get(inner).unwrap().offset(),
+        )
+    };
+
+    // The inner view consumes the event while it can scroll, then the outer view takes over.
+    assert_eq!(wheel(&mut world, -30.0), (Point(0.0, 0.0), Point(0.0, 30.0)));
+    assert_eq!(wheel(&mut world, -200.0), (Point(0.0, 0.0), Point(0.0, 100.0)));
+    assert_eq!(wheel(&mut world, -50.0), (Point(0.0, 50.0), Point(0.0, 100.0)));
+}
+
+#[test]
+fn scroll_clips_hit_test() {
+    let (mut world, mut dispatcher) = init_test();
+    let root = world.read_resource::<Root>().entity();
+    let (_, content) = create_scroll_view(&mut world);
+    run_window_event(
+        &mut world,
+        &mut dispatcher,
+        WindowEvent::Resized((1000, 1000).into()),
+    );
+
+    let mut move_to = |world: &mut World, x: f64, y: f64| {
+        run_window_event(
+            world,
+            &mut dispatcher,
+            WindowEvent::CursorMoved {
+                position: LogicalPosition::new(x, y),
+                modifiers: ModifiersState::default(),
+                device_id: unsafe { mem::zeroed() },
+            },
+        );
+        world.read_resource::<MouseFocus>().entity()
+    };
+
+    assert_eq!(move_to(&mut world, 50.0, 50.0), Some(content));
+    assert_eq!(move_to(&mut world, 50.0, 150.0), Some(root));
+}
+
+#[test]
+fn de() {
+    const DATA: &'static str = r##"
+    {
+        "view": {
+            "ScrollView": {
+                "content": "content",
+                "horizontal": false,
+                "scrollbar": { "Color": "#808080" },
+                "scrollbar_width": 8
+            },
+            "Children": {
+                "content": {}
+            }
+        }
+    }
+    "##;
+
+    let (mut world, _) = init_test();
+    let registry = de::Registry::new();
+    let mut name_map = FnvHashMap::default();
+
+    de::deserialize_with_names(
+        &mut json::Deserializer::from_str(DATA),
+        &registry,
+        &mut world.res,
+        &mut name_map,
+    ).unwrap();
+
+    let scrolls = world.read_storage::<ScrollView>();
+    let scroll = scrolls.get(name_map["view"]).unwrap();
+    assert_eq!(scroll.content(), name_map["content"]);
+    assert!(!scroll.horizontal);
+    assert!(scroll.vertical);
+    assert_eq!(scroll.scrollbar_width, 8.0);
+    assert!(world.read_storage::<Position>().get(name_map["content"]).is_some());
+}
@@ -0,0 +1,48 @@
+use erased_serde as erased;
+use serde::de as serde;
+use specs::prelude::*;
+
+use de::{Deserialize, Seed};
+use layout::{Alignment, Constraints, Position};
+use widget::stack::{Direction, Stack};
+
+impl Deserialize for Stack {
+    fn deserialize<'de, 'a>(
+        seed: Seed<'de, 'a>,
+        deserializer: &mut erased::Deserializer<'de>,
+    ) -> Result<Self, erased::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "Stack")]
+        struct StackDe {
+            direction: Direction,
+            #[serde(default)]
+            spacing: f64,
+            #[serde(default)]
+            alignment: Alignment,
+            #[serde(default)]
+            wrap: bool,
+        }
+
+        let StackDe {
+            direction,
+            spacing,
+            alignment,
+            wrap,
+        } = serde::Deserialize::deserialize(deserializer)?;
+
+        let mut poss = WriteStorage::<Position>::fetch(seed.res);
+        let pos = poss.entry(seed.entity)
+            .unwrap()
+            .or_insert_with(Default::default);
+
+        let mut cons = WriteStorage::<Constraints>::fetch(seed.res);
+        cons.entry(seed.entity)
+            .unwrap()
+            .or_insert_with(|| Constraints::new(pos));
+
+        Ok(Stack::new(direction)
+            .with_spacing(spacing)
+            .with_alignment(alignment)
+            .with_wrap(wrap))
+    }
+}
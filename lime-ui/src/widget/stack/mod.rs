@@ -0,0 +1,217 @@
+mod de;
+mod sys;
+#[cfg(test)]
+mod tests;
+
+pub use self::sys::StackSystem;
+pub use layout::Alignment;
+
+use std::mem;
+
+use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
+use cassowary::{Constraint, Variable};
+use specs::prelude::*;
+
+use layout::{align, Constraints, Position};
+
+/// Lays out the children of its `Node` one after another along an axis. The stack shrinks to
+/// fit its children unless it is constrained otherwise.
+///
+/// The constraints are kept up to date by the `StackSystem` as children are added or removed.
+#[derive(Component)]
+#[storage(HashMapStorage)]
+pub struct Stack {
+    direction: Direction,
+    spacing: f64,
+    alignment: Alignment,
+    wrap: bool,
+    lines: Vec<Vec<Entity>>,
+    cons: Vec<Constraint>,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+impl Stack {
+    pub fn new(direction: Direction) -> Self {
+        Stack {
+            direction,
+            spacing: 0.0,
+            alignment: Alignment::Start,
+            wrap: false,
+            lines: Vec::new(),
+            cons: Vec::new(),
+        }
+    }
+
+    /// Set the space between adjacent children, and between lines if the stack wraps.
+    pub fn with_spacing(mut self, spacing: f64) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Set the placement of children across the direction of the stack.
+    pub fn with_alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Start a new line when the next child would not fit in the stack. Lines are broken using
+    /// the sizes from the last layout, so a change in size takes effect a frame later.
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub fn spacing(&self) -> f64 {
+        self.spacing
+    }
+
+    pub fn alignment(&self) -> Alignment {
+        self.alignment
+    }
+
+    pub fn wrap(&self) -> bool {
+        self.wrap
+    }
+
+    /// Update the constraints of the stack for `children`, if they have changed.
+    pub(crate) fn update(
+        &mut self,
+        pos: &Position,
+        cons: &mut Constraints,
+        children: Vec<Entity>,
+        poss: &ReadStorage<Position>,
+    ) {
+        let lines = self.break_lines(pos, children, poss);
+        if lines == self.lines {
+            return;
+        }
+
+        for con in self.cons.drain(..) {
+            cons.remove(con);
+        }
+        self.lines = lines;
+        let new_cons = self.layout(pos, poss);
+        cons.extend(new_cons.iter().cloned());
+        self.cons = new_cons;
+    }
+
+    fn break_lines(
+        &self,
+        pos: &Position,
+        children: Vec<Entity>,
+        poss: &ReadStorage<Position>,
+    ) -> Vec<Vec<Entity>> {
+        if children.is_empty() {
+            return Vec::new();
+        }
+        if !self.wrap {
+            return vec![children];
+        }
+
+        let available = self.main_size(pos) as f64;
+        let mut lines = Vec::new();
+        let mut line = Vec::new();
+        let mut used = 0.0;
+        for child in children {
+            let size = self.main_size(poss.get(child).unwrap()) as f64;
+            if line.is_empty() {
+                used = size;
+            } else if used + self.spacing + size > available + 0.5 {
+                lines.push(mem::replace(&mut line, Vec::new()));
+                used = size;
+            } else {
+                used += self.spacing + size;
+            }
+            line.push(child);
+        }
+        lines.push(line);
+        lines
+    }
+
+    fn layout(&self, pos: &Position, poss: &ReadStorage<Position>) -> Vec<Constraint> {
+        let (start, end) = self.main_vars(pos);
+        let (cross_start, cross_end) = self.cross_vars(pos);
+
+        let mut cons = Vec::new();
+        cons.push(end - start | EQ(WEAK) | 0.0);
+        cons.push(cross_end - cross_start | EQ(WEAK) | 0.0);
+
+        // Without wrapping, children may not overflow the stack. Otherwise they may, until
+        // the lines are broken again.
+        let contain = if self.wrap { MEDIUM } else { REQUIRED };
+
+        let mut prev_line: Option<Variable> = None;
+        for line in &self.lines {
+            // A single line spans the stack, so children can be aligned within it.
+            let (line_start, line_end) = if self.lines.len() == 1 {
+                (cross_start, cross_end)
+            } else {
+                let (line_start, line_end) = (Variable::new(), Variable::new());
+                cons.push(match prev_line {
+                    Some(prev) => line_start | EQ(REQUIRED) | prev + self.spacing,
+                    None => line_start | EQ(REQUIRED) | cross_start,
+                });
+                cons.push(line_end | GE(REQUIRED) | line_start);
+                cons.push(line_end | LE(REQUIRED) | cross_end);
+                cons.push(line_end - line_start | EQ(WEAK) | 0.0);
+                (line_start, line_end)
+            };
+            prev_line = Some(line_end);
+
+            let mut prev: Option<Variable> = None;
+            for &child in line {
+                let child_pos = poss.get(child).unwrap();
+                let (child_start, child_end) = self.main_vars(child_pos);
+                cons.push(match prev {
+                    Some(prev) => child_start | EQ(REQUIRED) | prev + self.spacing,
+                    None => child_start | EQ(REQUIRED) | start,
+                });
+                cons.push(child_end | LE(contain) | end);
+                prev = Some(child_end);
+
+                let (child_start, child_end) = self.cross_vars(child_pos);
+                cons.extend(align(
+                    self.alignment,
+                    line_start.into(),
+                    line_end.into(),
+                    child_start,
+                    child_end,
+                ));
+            }
+        }
+
+        cons
+    }
+
+    fn main_vars(&self, pos: &Position) -> (Variable, Variable) {
+        match self.direction {
+            Direction::Horizontal => (pos.left_var(), pos.right_var()),
+            Direction::Vertical => (pos.top_var(), pos.bottom_var()),
+        }
+    }
+
+    fn cross_vars(&self, pos: &Position) -> (Variable, Variable) {
+        match self.direction {
+            Direction::Horizontal => (pos.top_var(), pos.bottom_var()),
+            Direction::Vertical => (pos.left_var(), pos.right_var()),
+        }
+    }
+
+    fn main_size(&self, pos: &Position) -> f32 {
+        match self.direction {
+            Direction::Horizontal => pos.width(),
+            Direction::Vertical => pos.height(),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use specs::prelude::*;
+
+use layout::{Constraints, Position};
+use tree::Node;
+use widget::stack::Stack;
+
+pub struct StackSystem;
+
+impl StackSystem {
+    pub const NAME: &'static str = "ui::Stack";
+
+    pub(crate) fn add(_: &mut World, dispatcher: &mut DispatcherBuilder) {
+        dispatcher.add(StackSystem, StackSystem::NAME, &[]);
+    }
+}
+
+impl<'a> System<'a> for StackSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Stack>,
+        WriteStorage<'a, Constraints>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Node>,
+    );
+
+    fn run(&mut self, (entities, mut stacks, mut cons, poss, nodes): Self::SystemData) {
+        for (stack, pos, con, node) in (&mut stacks, &poss, &mut cons, &nodes).join() {
+            let children = node.children()
+                .iter()
+                .cloned()
+                .filter(|&child| entities.is_alive(child) && poss.get(child).is_some())
+                .collect();
+            stack.update(pos, con, children, &poss);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use cassowary::strength::*;
+use fnv::FnvHashMap;
+use render::d2::Point;
+use serde_json as json;
+use specs::prelude::*;
+use winit::WindowEvent::Resized;
+
+use super::*;
+use de;
+use event::tests::run_window_event;
+use layout::{Alignment, Constraints, Position};
+use tests::init_test;
+use tree::{Node, Root};
+
+fn create_root_stack(world: &mut World, stack: Stack) -> Entity {
+    let root = world.read_resource::<Root>().entity();
+    let cons = Constraints::new(world.read_storage().get(root).unwrap());
+    world.write_storage().insert(root, stack).unwrap();
+    world.write_storage().insert(root, cons).unwrap();
+    root
+}
+
+fn create_rect(world: &mut World, parent: Entity, size: (f64, f64)) -> Entity {
+    let pos = Position::new();
+    let cons = pos.constraints_builder().size(size, STRONG).build();
+    Node::with_parent(world.create_entity(), parent)
+        .with(pos)
+        .with(cons)
+        .build()
+}
+
+fn assert_approx_eq(l: Point, r: Point) {
+    assert_ulps_eq!(l.0, r.0);
+    assert_ulps_eq!(l.1, r.1);
+}
+
+fn assert_rect(world: &World, ent: Entity, top_left: Point, bottom_right: Point) {
+    let poss = world.read_storage::<Position>();
+    let pos = poss.get(ent).unwrap();
+    assert_approx_eq(pos.top_left(), top_left);
+    assert_approx_eq(pos.bottom_right(), bottom_right);
+}
+
+#[test]
+fn empty() {
+    let (mut world, mut dispatcher) = init_test();
+
+    create_root_stack(&mut world, Stack::new(Direction::Horizontal));
+
+    run_window_event(&mut world, &mut dispatcher, Resized((1000, 750).into()));
+}
+
+#[test]
+fn horizontal() {
+    let (mut world, mut dispatcher) = init_test();
+
+    let stack = create_root_stack(
+        &mut world,
+        Stack::new(Direction::Horizontal).with_spacing(10.0),
+    );
+    let r1 = create_rect(&mut world, stack, (100.0, 50.0));
+    let r2 = create_rect(&mut world, stack, (200.0, 80.0));
+    let r3 = create_rect(&mut world, stack, (50.0, 50.0));
+
+    run_window_event(&mut world, &mut dispatcher, Resized((1000, 750).into()));
+
+    assert_rect(&world, r1, Point(0.0, 0.0), Point(100.0, 50.0));
+    assert_rect(&world, r2, Point(110.0, 0.0), Point(310.0, 80.0));
+    assert_rect(&world, r3, Point(320.0, 0.0), Point(370.0, 50.0));
+}
+
+#[test]
+fn vertical_center() {
+    let (mut world, mut dispatcher) = init_test();
+
+    let stack = create_root_stack(
+        &mut world,
+        Stack::new(Direction::Vertical)
+            .with_spacing(20.0)
+            .with_alignment(Alignment::Center),
+    );
+    let r1 = create_rect(&mut world, stack, (100.0, 50.0));
+    let r2 = create_rect(&mut world, stack, (200.0, 80.0));
+
+    run_window_event(&mut world, &mut dispatcher, Resized((1000, 750).into()));
+
+    assert_rect(&world, r1, Point(450.0, 0.0), Point(550.0, 50.0));
+    assert_rect(&world, r2, Point(400.0, 70.0), Point(600.0, 150.0));
+}
+
+#[test]
+fn stretch() {
+    let (mut world, mut dispatcher) = init_test();
+
+    let stack = create_root_stack(
+        &mut world,
+        Stack::new(Direction::Vertical).with_alignment(Alignment::Stretch),
+    );
+    let r1 = create_rect(&mut world, stack, (100.0, 50.0));
+
+    run_window_event(&mut world, &mut dispatcher, Resized((1000, 750).into()));
+
+    assert_rect(&world, r1, Point(0.0, 0.0), Point(1000.0, 50.0));
+}
+
+#[test]
+fn add_remove() {
+    let (mut world, mut dispatcher) = init_test();
+
+    let stack = create_root_stack(&mut world, Stack::new(Direction::Horizontal));
+    let r1 = create_rect(&mut world, stack, (100.0, 100.0));
+    let r2 = create_rect(&mut world, stack, (200.0, 100.0));
+
+    run_window_event(&mut world, &mut dispatcher, Resized((1000, 750).into()));
+
+    assert_rect(&world, r2, Point(100.0, 0.0), Point(300.0, 100.0));
+
+    let r3 = create_rect(&mut world, stack, (300.0, 100.0));
+    world.delete_entity(r1).unwrap();
+    world.maintain();
+
+    run_window_event(&mut world, &mut dispatcher, Resized((1000, 750).into()));
+
+    assert_rect(&world, r2, Point(0.0, 0.0), Point(200.0, 100.0));
+    assert_rect(&world, r3, Point(200.0, 0.0), Point(500.0, 100.0));
+}
+
+#[test]
+fn wrap() {
+    let (mut world, mut dispatcher) = init_test();
+
+    let stack = create_root_stack(
+        &mut world,
+        Stack::new(Direction::Horizontal)
+            .with_spacing(10.0)
+            .with_wrap(true),
+    );
+    let rects: Vec<_> = (0..4)
+        .map(|_| create_rect(&mut world, stack, (400.0, 100.0)))
+        .collect();
+
+    // Lines are broken using the sizes from the previous frame.
+    run_window_event(&mut world, &mut dispatcher, Resized((1000, 750).into()));
+    run_window_event(&mut world, &mut dispatcher, Resized((1000, 750).into()));
+
+    assert_rect(&world, rects[0], Point(0.0, 0.0), Point(400.0, 100.0));
+    assert_rect(&world, rects[1], Point(410.0, 0.0), Point(810.0, 100.0));
+    assert_rect(&world, rects[2], Point(0.0, 110.0), Point(400.0, 210.0));
+    assert_rect(&world, rects[3], Point(410.0, 110.0), Point(810.0, 210.0));
+
+    run_window_event(&mut world, &mut dispatcher, Resized((500, 750).into()));
+    run_window_event(&mut world, &mut dispatcher, Resized((500, 750).into()));
+
+    for (i, &rect) in rects.iter().enumerate() {
+        let top = i as f32 * 110.0;
+        assert_rect(&world, rect, Point(0.0, top), Point(400.0, top + 100.0));
+    }
+}
+
+#[test]
+fn de() {
+    const DATA: &'static str = r#"
+    {
+        "stack": {
+            "Stack": {
+                "direction": "vertical",
+                "spacing": 5,
+                "alignment": "end"
+            },
+            "Children": {
+                "a": {}
+            }
+        }
+    }
+    "#;
+
+    let (mut world, _) = init_test();
+    let registry = de::Registry::new();
+    let mut name_map = FnvHashMap::default();
+
+    de::deserialize_with_names(
+        &mut json::Deserializer::from_str(DATA),
+        &registry,
+        &mut world.res,
+        &mut name_map,
+    ).unwrap();
+
+    let stacks = world.read_storage::<Stack>();
+    let stack = stacks.get(name_map["stack"]).unwrap();
+    assert_eq!(stack.direction(), Direction::Vertical);
+    assert_eq!(stack.spacing(), 5.0);
+    assert_eq!(stack.alignment(), Alignment::End);
+    assert!(!stack.wrap());
+    assert!(world.read_storage::<Position>().get(name_map["stack"]).is_some());
+    assert!(world.read_storage::<Constraints>().get(name_map["stack"]).is_some());
+}
@@ -0,0 +1,49 @@
+use std::borrow::Cow;
+
+use erased_serde as erased;
+use render::Color;
+use serde::de as serde;
+
+use de::{Deserialize, Seed};
+use widget::text::{Align, Fonts, Text};
+
+impl Deserialize for Text {
+    fn deserialize<'de, 'a>(
+        seed: Seed<'de, 'a>,
+        deserializer: &mut erased::Deserializer<'de>,
+    ) -> Result<Self, erased::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "Text")]
+        struct TextDe<'c> {
+            text: String,
+            #[serde(borrow)]
+            font: Cow<'c, str>,
+            size: f32,
+            color: Color,
+            #[serde(default)]
+            halign: Align,
+            #[serde(default)]
+            valign: Align,
+        }
+
+        let TextDe {
+            text,
+            font,
+            size,
+            color,
+            halign,
+            valign,
+        } = serde::Deserialize::deserialize(deserializer)?;
+
+        let fonts = match seed.res.try_fetch::<Fonts>() {
+            Some(fonts) => fonts,
+            None => return Err(serde::Error::custom("no fonts loaded")),
+        };
+        match fonts.get(&font) {
+            Some(font) => {
+                Ok(Text::new(text, font.clone(), size, color).with_align(halign, valign))
+            }
+            None => Err(serde::Error::custom(format!("unknown font '{}'", font))),
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use erased_serde as erased;
+use serde::de as serde;
+use specs::prelude::*;
+
+use de::{Deserialize, Insert, Seed};
+use widget::text::TextInput;
+
+impl Deserialize for TextInput {
+    fn deserialize<'de, 'a>(
+        _: Seed<'de, 'a>,
+        deserializer: &mut erased::Deserializer<'de>,
+    ) -> Result<Self, erased::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "TextInput")]
+        struct TextInputDe {
+            #[serde(default)]
+            text: String,
+        }
+
+        let TextInputDe { text } = serde::Deserialize::deserialize(deserializer)?;
+        Ok(TextInput::new(text))
+    }
+}
+
+impl Insert for TextInput {
+    fn insert<'de, 'a>(self, seed: Seed<'de, 'a>) -> Result<Option<Self>, erased::Error> {
+        self.insert_into(seed.entity, &mut WriteStorage::fetch(seed.res))
+            .map_err(serde::Error::custom)
+    }
+}
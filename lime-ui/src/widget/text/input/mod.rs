@@ -0,0 +1,249 @@
+mod de;
+mod sys;
+
+pub use self::sys::TextInputSystem;
+
+use std::cmp;
+use std::ops::Range;
+
+use shrev::EventChannel;
+use specs::prelude::*;
+use specs::storage::InsertResult;
+use specs_mirror::{Mirrored, MirroredStorage, StorageMutExt};
+
+/// An editable single line of text. The text is drawn by a `Text` component on the same entity,
+/// which is kept up to date by the `TextInputSystem`. Use `insert_into` to add an input to an
+/// entity, so its `Text` is updated before the first edit.
+#[derive(Clone, Debug, Default)]
+pub struct TextInput {
+    text: String,
+    caret: usize,
+    anchor: usize,
+    clipboard: String,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TextInputEvent {
+    pub entity: Entity,
+    pub kind: TextInputEventKind,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TextInputEventKind {
+    /// The input was added to its entity by `TextInput::insert_into`.
+    Inserted,
+    /// The text was edited.
+    Edit,
+    /// The caret or selection moved.
+    Select,
+}
+
+impl TextInput {
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = sanitize(&text.into());
+        let caret = text.len();
+        TextInput {
+            text,
+            caret,
+            anchor: caret,
+            clipboard: String::new(),
+        }
+    }
+
+    pub fn insert_into(
+        self,
+        entity: Entity,
+        storage: &mut WriteStorage<Self>,
+    ) -> InsertResult<Self> {
+        let res = storage.insert(entity, self);
+        if res.is_ok() {
+            storage.event_channel().single_write(TextInputEvent {
+                entity,
+                kind: TextInputEventKind::Inserted,
+            });
+        }
+        res
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The byte offset of the caret.
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    /// The byte range of the selected text. Empty if nothing is selected.
+    pub fn selection(&self) -> Range<usize> {
+        cmp::min(self.anchor, self.caret)..cmp::max(self.anchor, self.caret)
+    }
+
+    pub fn selected_text(&self) -> &str {
+        &self.text[self.selection()]
+    }
+
+    pub fn set_text(
+        &mut self,
+        entity: Entity,
+        text: impl Into<String>,
+        chan: &mut EventChannel<TextInputEvent>,
+    ) {
+        let text = sanitize(&text.into());
+        if self.text != text {
+            self.caret = text.len();
+            self.anchor = self.caret;
+            self.text = text;
+            chan.single_write(TextInputEvent::edit(entity));
+        }
+    }
+
+    /// Move the caret to the byte offset `caret`, which is rounded down to a character boundary.
+    /// If `extend` is true the selection is extended to the new position.
+    pub fn set_caret(
+        &mut self,
+        entity: Entity,
+        caret: usize,
+        extend: bool,
+        chan: &mut EventChannel<TextInputEvent>,
+    ) {
+        let mut caret = cmp::min(caret, self.text.len());
+        while !self.text.is_char_boundary(caret) {
+            caret -= 1;
+        }
+        let anchor = if extend { self.anchor } else { caret };
+        if self.caret != caret || self.anchor != anchor {
+            self.caret = caret;
+            self.anchor = anchor;
+            chan.single_write(TextInputEvent::select(entity));
+        }
+    }
+
+    pub fn select_all(&mut self, entity: Entity, chan: &mut EventChannel<TextInputEvent>) {
+        if self.anchor != 0 || self.caret != self.text.len() {
+            self.anchor = 0;
+            self.caret = self.text.len();
+            chan.single_write(TextInputEvent::select(entity));
+        }
+    }
+
+    /// Replace the selection with `text`, leaving the caret after the inserted text.
+    pub fn insert(&mut self, entity: Entity, text: &str, chan: &mut EventChannel<TextInputEvent>) {
+        let text = sanitize(text);
+        let sel = self.selection();
+        if sel.start == sel.end && text.is_empty() {
+            return;
+        }
+        self.text.replace_range(sel.clone(), &text);
+        self.caret = sel.start + text.len();
+        self.anchor = self.caret;
+        chan.single_write(TextInputEvent::edit(entity));
+    }
+
+    pub fn move_left(
+        &mut self,
+        entity: Entity,
+        extend: bool,
+        chan: &mut EventChannel<TextInputEvent>,
+    ) {
+        let caret = if !extend && self.caret != self.anchor {
+            self.selection().start
+        } else {
+            self.prev_boundary()
+        };
+        self.set_caret(entity, caret, extend, chan);
+    }
+
+    pub fn move_right(
+        &mut self,
+        entity: Entity,
+        extend: bool,
+        chan: &mut EventChannel<TextInputEvent>,
+    ) {
+        let caret = if !extend && self.caret != self.anchor {
+            self.selection().end
+        } else {
+            self.next_boundary()
+        };
+        self.set_caret(entity, caret, extend, chan);
+    }
+
+    /// Delete the selection, or the character before the caret if nothing is selected.
+    pub fn delete_backward(&mut self, entity: Entity, chan: &mut EventChannel<TextInputEvent>) {
+        if self.caret == self.anchor {
+            self.anchor = self.prev_boundary();
+        }
+        self.insert(entity, "", chan);
+    }
+
+    /// Delete the selection, or the character after the caret if nothing is selected.
+    pub fn delete_forward(&mut self, entity: Entity, chan: &mut EventChannel<TextInputEvent>) {
+        if self.caret == self.anchor {
+            self.anchor = self.next_boundary();
+        }
+        self.insert(entity, "", chan);
+    }
+
+    /// Copy the selection to this input's clipboard. The system clipboard is not used.
+    pub fn copy(&mut self) {
+        if self.caret != self.anchor {
+            self.clipboard = self.selected_text().to_owned();
+        }
+    }
+
+    pub fn cut(&mut self, entity: Entity, chan: &mut EventChannel<TextInputEvent>) {
+        if self.caret != self.anchor {
+            self.copy();
+            self.insert(entity, "", chan);
+        }
+    }
+
+    pub fn paste(&mut self, entity: Entity, chan: &mut EventChannel<TextInputEvent>) {
+        let clipboard = self.clipboard.clone();
+        self.insert(entity, &clipboard, chan);
+    }
+
+    fn prev_boundary(&self) -> usize {
+        self.text[..self.caret]
+            .chars()
+            .next_back()
+            .map(|chr| self.caret - chr.len_utf8())
+            .unwrap_or(0)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.text[self.caret..]
+            .chars()
+            .next()
+            .map(|chr| self.caret + chr.len_utf8())
+            .unwrap_or(self.caret)
+    }
+}
+
+impl TextInputEvent {
+    fn edit(entity: Entity) -> Self {
+        TextInputEvent {
+            entity,
+            kind: TextInputEventKind::Edit,
+        }
+    }
+
+    fn select(entity: Entity) -> Self {
+        TextInputEvent {
+            entity,
+            kind: TextInputEventKind::Select,
+        }
+    }
+}
+
+impl Component for TextInput {
+    type Storage = MirroredStorage<Self, HashMapStorage<Self>>;
+}
+
+impl Mirrored for TextInput {
+    type Event = TextInputEvent;
+}
+
+fn sanitize(text: &str) -> String {
+    text.chars().filter(|chr| !chr.is_control()).collect()
+}
@@ -0,0 +1,143 @@
+use shrev::{EventChannel, ReaderId};
+use specs::prelude::*;
+use specs_mirror::{StorageExt, StorageMutExt};
+use winit::{ModifiersState, MouseButton, VirtualKeyCode};
+
+use event::{
+    Event, EventKind, EventPhase, EventSystem, KeyboardEvent, KeyboardFocus, MouseEvent,
+    MouseFocus,
+};
+use layout::Position;
+use widget::text::{Text, TextInput, TextInputEvent, TextInputEventKind};
+
+pub struct TextInputSystem {
+    event_rx: ReaderId<Event>,
+    input_rx: ReaderId<TextInputEvent>,
+}
+
+impl TextInputSystem {
+    pub const NAME: &'static str = "ui::TextInput";
+
+    pub(crate) fn add(world: &mut World, dispatcher: &mut DispatcherBuilder) {
+        let event_rx = world
+            .write_resource::<EventChannel<Event>>()
+            .register_reader();
+        let input_rx = world.write_storage::<TextInput>().register_reader();
+        dispatcher.add(
+            TextInputSystem { event_rx, input_rx },
+            TextInputSystem::NAME,
+            &[EventSystem::NAME],
+        );
+    }
+}
+
+fn key_down(
+    entity: Entity,
+    key: VirtualKeyCode,
+    mods: ModifiersState,
+    input: &mut TextInput,
+    chan: &mut EventChannel<TextInputEvent>,
+) {
+    match key {
+        VirtualKeyCode::Back => input.delete_backward(entity, chan),
+        VirtualKeyCode::Delete => input.delete_forward(entity, chan),
+        VirtualKeyCode::Left => input.move_left(entity, mods.shift, chan),
+        VirtualKeyCode::Right => input.move_right(entity, mods.shift, chan),
+        VirtualKeyCode::Home => input.set_caret(entity, 0, mods.shift, chan),
+        VirtualKeyCode::End => {
+            let end = input.text().len();
+            input.set_caret(entity, end, mods.shift, chan)
+        }
+        VirtualKeyCode::A if mods.ctrl => input.select_all(entity, chan),
+        VirtualKeyCode::C if mods.ctrl => input.copy(),
+        VirtualKeyCode::X if mods.ctrl => input.cut(entity, chan),
+        VirtualKeyCode::V if mods.ctrl => input.paste(entity, chan),
+        _ => (),
+    }
+}
+
+fn button_down(
+    entity: Entity,
+    mods: ModifiersState,
+    hover: &MouseFocus,
+    input: &mut TextInput,
+    chan: &mut EventChannel<TextInputEvent>,
+    text: Option<&Text>,
+    pos: Option<&Position>,
+) {
+    if let (Some(text), Some(pos)) = (text, pos) {
+        let x = hover.point().0;
+        let offsets = text.caret_offsets(pos);
+        let idx = (0..offsets.len())
+            .min_by(|&l, &r| {
+                let l = (offsets[l] - x).abs();
+                let r = (offsets[r] - x).abs();
+                l.partial_cmp(&r).unwrap()
+            })
+            .unwrap_or(0);
+        let caret = input
+            .text()
+            .char_indices()
+            .nth(idx)
+            .map(|(caret, _)| caret)
+            .unwrap_or(input.text().len());
+        input.set_caret(entity, caret, mods.shift, chan);
+    }
+}
+
+impl<'a> System<'a> for TextInputSystem {
+    type SystemData = (
+        ReadExpect<'a, EventChannel<Event>>,
+        WriteExpect<'a, KeyboardFocus>,
+        ReadExpect<'a, MouseFocus>,
+        WriteStorage<'a, TextInput>,
+        WriteStorage<'a, Text>,
+        ReadStorage<'a, Position>,
+    );
+
+    fn run(
+        &mut self,
+        (events, mut kb_focus, hover, mut inputs, mut texts, poss): Self::SystemData,
+    ) {
+        for &event in events.read(&mut self.event_rx) {
+            if event.phase() != EventPhase::Target {
+                continue;
+            }
+
+            let entity = event.entity();
+            if let Some((input, chan)) = inputs.modify(entity) {
+                match event.kind() {
+                    EventKind::Keyboard(KeyboardEvent::Char(chr)) => if !chr.is_control() {
+                        input.insert(entity, chr.encode_utf8(&mut [0; 4]), chan);
+                    },
+                    EventKind::Keyboard(KeyboardEvent::KeyDown(key, mods)) => {
+                        key_down(entity, key, mods, input, chan)
+                    }
+                    EventKind::Mouse(MouseEvent::ButtonDown(MouseButton::Left, mods)) => {
+                        kb_focus.set_entity(entity);
+                        button_down(
+                            entity,
+                            mods,
+                            &hover,
+                            input,
+                            chan,
+                            texts.get(entity),
+                            poss.get(entity),
+                        );
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        for event in inputs.read_events(&mut self.input_rx) {
+            if event.kind != TextInputEventKind::Select {
+                if let (Some(input), Some(text)) =
+                    (inputs.get(event.entity), texts.get_mut(event.entity))
+                {
+                    text.set_text(input.text());
+                }
+            }
+        }
+    }
+}
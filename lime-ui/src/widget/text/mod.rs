@@ -0,0 +1,199 @@
+mod de;
+mod input;
+mod sys;
+#[cfg(test)]
+mod tests;
+
+pub use self::input::{TextInput, TextInputEvent, TextInputEventKind, TextInputSystem};
+pub use self::sys::TextSizeSystem;
+
+use fnv::FnvHashMap;
+use render::d2::{Font, Point};
+use render::Color;
+use rusttype::{point, PositionedGlyph, Scale};
+use specs::prelude::*;
+
+use layout::Position;
+
+#[derive(Clone, Component, Debug)]
+pub struct Text {
+    text: String,
+    font: Font,
+    size: f32,
+    color: Color,
+    halign: Align,
+    valign: Align,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq)]
+pub enum Align {
+    Start,
+    Center,
+    End,
+}
+
+/// Fonts available to `Text` components, keyed by name.
+#[derive(Debug, Default)]
+pub struct Fonts {
+    map: FnvHashMap<String, Font>,
+}
+
+impl Text {
+    pub fn new(text: impl Into<String>, font: Font, size: f32, color: Color) -> Self {
+        Text {
+            text: text.into(),
+            font,
+            size,
+            color,
+            halign: Align::Start,
+            valign: Align::Start,
+        }
+    }
+
+    pub fn with_align(mut self, halign: Align, valign: Align) -> Self {
+        self.set_align(halign, valign);
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+    }
+
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    pub fn set_font(&mut self, font: Font) {
+        self.font = font;
+    }
+
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    pub fn set_size(&mut self, size: f32) {
+        self.size = size;
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    pub fn align(&self) -> (Align, Align) {
+        (self.halign, self.valign)
+    }
+
+    pub fn set_align(&mut self, halign: Align, valign: Align) {
+        self.halign = halign;
+        self.valign = valign;
+    }
+
+    /// Lay out the text inside `pos`. Lines are broken only at newline characters.
+    pub fn glyphs(&self, pos: &Position) -> Vec<PositionedGlyph<'static>> {
+        let font = self.font.rusttype();
+        let scale = Scale::uniform(self.size);
+        let v_metrics = font.v_metrics(scale);
+        let line_height = v_metrics.ascent - v_metrics.descent + v_metrics.line_gap;
+
+        let line_count = self.text.lines().count();
+        let height = line_height * line_count as f32 - v_metrics.line_gap;
+        let top = self.valign.offset(pos.top(), pos.height(), height);
+
+        let mut glyphs = Vec::with_capacity(self.text.len());
+        for (idx, line) in self.text.lines().enumerate() {
+            let width = line_width(&self.font, scale, line);
+            let left = self.halign.offset(pos.left(), pos.width(), width);
+            let baseline = top + line_height * idx as f32 + v_metrics.ascent;
+            glyphs.extend(font.layout(line, scale, point(left, baseline)));
+        }
+        glyphs
+    }
+
+    /// The horizontal positions of the boundaries between characters on the first line of text,
+    /// when laid out inside `pos`. There is one more offset than there are characters.
+    pub fn caret_offsets(&self, pos: &Position) -> Vec<f32> {
+        let scale = Scale::uniform(self.size);
+        let line = self.text.lines().next().unwrap_or("");
+        let width = line_width(&self.font, scale, line);
+        let left = self.halign.offset(pos.left(), pos.width(), width);
+
+        let mut offsets: Vec<f32> = self.font
+            .rusttype()
+            .layout(line, scale, point(left, 0.0))
+            .map(|glyph| glyph.position().x)
+            .collect();
+        offsets.push(left + width);
+        offsets
+    }
+
+    /// The top and bottom of the first line of text, when laid out inside `pos`.
+    pub fn line_extent(&self, pos: &Position) -> (f32, f32) {
+        let height = self.measure().1;
+        let top = self.valign.offset(pos.top(), pos.height(), height);
+        let v_metrics = self.font.rusttype().v_metrics(Scale::uniform(self.size));
+        (top, top + v_metrics.ascent - v_metrics.descent)
+    }
+
+    /// The size of the text's bounding box, ignoring alignment.
+    pub fn measure(&self) -> Point {
+        let scale = Scale::uniform(self.size);
+        let v_metrics = self.font.rusttype().v_metrics(scale);
+        let line_height = v_metrics.ascent - v_metrics.descent + v_metrics.line_gap;
+
+        let (count, width) = self.text.lines().fold((0, 0.0f32), |(count, width), line| {
+            (count + 1, width.max(line_width(&self.font, scale, line)))
+        });
+        let height = if count == 0 {
+            0.0
+        } else {
+            line_height * count as f32 - v_metrics.line_gap
+        };
+        Point(width, height)
+    }
+}
+
+impl Align {
+    fn offset(self, start: f32, available: f32, size: f32) -> f32 {
+        match self {
+            Align::Start => start,
+            Align::Center => start + (available - size) / 2.0,
+            Align::End => start + available - size,
+        }
+    }
+}
+
+impl Default for Align {
+    fn default() -> Self {
+        Align::Start
+    }
+}
+
+impl Fonts {
+    pub fn new() -> Self {
+        Fonts::default()
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, font: Font) -> Option<Font> {
+        self.map.insert(name.into(), font)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Font> {
+        self.map.get(name)
+    }
+}
+
+pub(crate) fn line_width(font: &Font, scale: Scale, line: &str) -> f32 {
+    font.rusttype()
+        .layout(line, scale, point(0.0, 0.0))
+        .last()
+        .map(|glyph| glyph.position().x + glyph.unpositioned().h_metrics().advance_width)
+        .unwrap_or(0.0)
+}
@@ -0,0 +1,41 @@
+use specs::prelude::*;
+
+use layout::{Constraints, IntrinsicSize, Position};
+use widget::text::{Text, TextInputSystem};
+
+/// Keeps the `IntrinsicSize` of entities with `Text` and `Constraints` up to date with the
+/// measured size of their text.
+pub struct TextSizeSystem;
+
+impl TextSizeSystem {
+    pub const NAME: &'static str = "ui::TextSize";
+
+    pub(crate) fn add(_: &mut World, dispatcher: &mut DispatcherBuilder) {
+        dispatcher.add(
+            TextSizeSystem,
+            TextSizeSystem::NAME,
+            &[TextInputSystem::NAME],
+        );
+    }
+}
+
+impl<'a> System<'a> for TextSizeSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Text>,
+        ReadStorage<'a, Position>,
+        WriteStorage<'a, Constraints>,
+        WriteStorage<'a, IntrinsicSize>,
+    );
+
+    fn run(&mut self, (entities, texts, poss, mut cons, mut sizes): Self::SystemData) {
+        for (ent, text, pos, con) in (&*entities, &texts, &poss, &mut cons).join() {
+            let size = text.measure();
+            sizes
+                .entry(ent)
+                .unwrap()
+                .or_insert_with(IntrinsicSize::new)
+                .set((size.0 as f64, size.1 as f64), pos, con);
+        }
+    }
+}
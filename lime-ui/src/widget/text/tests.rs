@@ -0,0 +1,228 @@
+use fnv::FnvHashMap;
+use serde_json as json;
+use specs::prelude::*;
+use specs_mirror::{StorageExt, StorageMutExt};
+use winit::{ModifiersState, VirtualKeyCode};
+
+use super::*;
+use de;
+use event::tests::emit_keyboard_event;
+use event::KeyboardEvent;
+use tests::init_test;
+use tree::{Node, Root};
+
+fn create_input(world: &mut World, text: &str) -> Entity {
+    let root = world.read_resource::<Root>().entity();
+    let entity = Node::with_parent(world.create_entity(), root).build();
+    TextInput::new(text)
+        .insert_into(entity, &mut world.write_storage())
+        .unwrap();
+    entity
+}
+
+fn check_input(world: &mut World, input: Entity, text: &str, caret: usize, selected: &str) {
+    let inputs = world.read_storage::<TextInput>();
+    let input = inputs.get(input).unwrap();
+    assert_eq!(input.text(), text);
+    assert_eq!(input.caret(), caret);
+    assert_eq!(input.selected_text(), selected);
+}
+
+fn type_chars(world: &mut World, input: Entity, text: &str) {
+    for chr in text.chars() {
+        emit_keyboard_event(world, input, KeyboardEvent::Char(chr));
+    }
+}
+
+fn press_key(world: &mut World, input: Entity, key: VirtualKeyCode, shift: bool, ctrl: bool) {
+    let mods = ModifiersState {
+        shift,
+        ctrl,
+        ..ModifiersState::default()
+    };
+    emit_keyboard_event(world, input, KeyboardEvent::KeyDown(key, mods));
+    emit_keyboard_event(world, input, KeyboardEvent::KeyUp(key, mods));
+}
+
+fn read_edits(world: &mut World, reader: &mut ReaderId<TextInputEvent>) -> usize {
+    world
+        .read_storage::<TextInput>()
+        .read_events(reader)
+        .filter(|ev| ev.kind == TextInputEventKind::Edit)
+        .count()
+}
+
+#[test]
+fn align() {
+    assert_eq!(Align::Start.offset(10.0, 100.0, 40.0), 10.0);
+    assert_eq!(Align::Center.offset(10.0, 100.0, 40.0), 40.0);
+    assert_eq!(Align::End.offset(10.0, 100.0, 40.0), 70.0);
+}
+
+#[test]
+fn de_unknown_font() {
+    const DATA: &'static str = r##"
+    {
+        "label": {
+            "Text": {
+                "text": "hello",
+                "font": "missing",
+                "size": 16,
+                "color": "#FFFFFF"
+            }
+        }
+    }
+    "##;
+
+    let (mut world, _) = init_test();
+    let registry = de::Registry::new();
+
+    let err = de::deserialize(
+        &mut json::Deserializer::from_str(DATA),
+        &registry,
+        &mut world.res,
+    ).unwrap_err();
+    assert!(err.to_string().contains("unknown font 'missing'"));
+}
+
+#[test]
+fn de_input() {
+    const DATA: &'static str = r#"
+    {
+        "input": {
+            "TextInput": {
+                "text": "ab\tc"
+            }
+        }
+    }
+    "#;
+
+    let (mut world, _) = init_test();
+    let mut reader = world.write_storage::<TextInput>().register_reader();
+    let registry = de::Registry::new();
+    let mut name_map = FnvHashMap::default();
+
+    de::deserialize_with_names(
+        &mut json::Deserializer::from_str(DATA),
+        &registry,
+        &mut world.res,
+        &mut name_map,
+    ).unwrap();
+
+    let input = name_map["input"];
+    check_input(&mut world, input, "abc", 3, "");
+    let events: Vec<_> = world
+        .read_storage::<TextInput>()
+        .read_events(&mut reader)
+        .cloned()
+        .collect();
+    assert_eq!(
+        events,
+        vec![TextInputEvent {
+            entity: input,
+            kind: TextInputEventKind::Inserted,
+        }]
+    );
+}
+
+#[test]
+fn input_edit() {
+    let (mut world, mut dispatcher) = init_test();
+    let mut reader = world.write_storage::<TextInput>().register_reader();
+    let input = create_input(&mut world, "");
+
+    type_chars(&mut world, input, "héllo");
+    dispatcher.dispatch(&world.res);
+    check_input(&mut world, input, "héllo", 6, "");
+    assert_eq!(read_edits(&mut world, &mut reader), 5);
+
+    press_key(&mut world, input, VirtualKeyCode::Left, false, false);
+    press_key(&mut world, input, VirtualKeyCode::Left, false, false);
+    press_key(&mut world, input, VirtualKeyCode::Left, false, false);
+    press_key(&mut world, input, VirtualKeyCode::Back, false, false);
+    dispatcher.dispatch(&world.res);
+    check_input(&mut world, input, "hllo", 1, "");
+    assert_eq!(read_edits(&mut world, &mut reader), 1);
+
+    press_key(&mut world, input, VirtualKeyCode::Delete, false, false);
+    emit_keyboard_event(&mut world, input, KeyboardEvent::Char('\u{8}'));
+    dispatcher.dispatch(&world.res);
+    check_input(&mut world, input, "hlo", 1, "");
+
+    press_key(&mut world, input, VirtualKeyCode::End, false, false);
+    press_key(&mut world, input, VirtualKeyCode::Back, false, false);
+    press_key(&mut world, input, VirtualKeyCode::Home, false, false);
+    press_key(&mut world, input, VirtualKeyCode::Back, false, false);
+    dispatcher.dispatch(&world.res);
+    check_input(&mut world, input, "hl", 0, "");
+}
+
+#[test]
+fn input_select() {
+    let (mut world, mut dispatcher) = init_test();
+    let input = create_input(&mut world, "hello world");
+
+    press_key(&mut world, input, VirtualKeyCode::Left, true, false);
+    press_key(&mut world, input, VirtualKeyCode::Left, true, false);
+    dispatcher.dispatch(&world.res);
+    check_input(&mut world, input, "hello world", 9, "ld");
+
+    press_key(&mut world, input, VirtualKeyCode::Left, false, false);
+    dispatcher.dispatch(&world.res);
+    check_input(&mut world, input, "hello world", 9, "");
+
+    press_key(&mut world, input, VirtualKeyCode::Home, true, false);
+    type_chars(&mut world, input, "goodbye wor");
+    dispatcher.dispatch(&world.res);
+    check_input(&mut world, input, "goodbye world", 11, "");
+
+    press_key(&mut world, input, VirtualKeyCode::A, false, true);
+    dispatcher.dispatch(&world.res);
+    check_input(&mut world, input, "goodbye world", 13, "goodbye world");
+
+    emit_keyboard_event(&mut world, input, KeyboardEvent::Char('\u{1}'));
+    press_key(&mut world, input, VirtualKeyCode::Delete, false, false);
+    dispatcher.dispatch(&world.res);
+    check_input(&mut world, input, "", 0, "");
+}
+
+#[test]
+fn input_copy_paste() {
+    let (mut world, mut dispatcher) = init_test();
+    let input = create_input(&mut world, "abc");
+
+    press_key(&mut world, input, VirtualKeyCode::Left, true, false);
+    press_key(&mut world, input, VirtualKeyCode::Left, true, false);
+    press_key(&mut world, input, VirtualKeyCode::C, false, true);
+    press_key(&mut world, input, VirtualKeyCode::End, false, false);
+    press_key(&mut world, input, VirtualKeyCode::V, false, true);
+    press_key(&mut world, input, VirtualKeyCode::V, false, true);
+    dispatcher.dispatch(&world.res);
+    check_input(&mut world, input, "abcbcbc", 7, "");
+
+    press_key(&mut world, input, VirtualKeyCode::Home, false, false);
+    press_key(&mut world, input, VirtualKeyCode::Right, true, false);
+    press_key(&mut world, input, VirtualKeyCode::X, false, true);
+    press_key(&mut world, input, VirtualKeyCode::End, false, false);
+    press_key(&mut world, input, VirtualKeyCode::V, false, true);
+    dispatcher.dispatch(&world.res);
+    check_input(&mut world, input, "bcbcbca", 7, "");
+}
+
+#[test]
+fn input_events_ignored_for_other_entities() {
+    let (mut world, mut dispatcher) = init_test();
+    let input = create_input(&mut world, "abc");
+    let other = world.create_entity().build();
+
+    type_chars(&mut world, other, "def");
+    dispatcher.dispatch(&world.res);
+    check_input(&mut world, input, "abc", 3, "");
+
+    {
+        let mut inputs = world.write_storage::<TextInput>();
+        let (input_comp, chan) = inputs.modify(input).unwrap();
+        input_comp.set_text(input, "xyz", chan);
+    }
+    check_input(&mut world, input, "xyz", 3, "");
+}
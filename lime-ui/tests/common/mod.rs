@@ -0,0 +1,65 @@
+use std::mem;
+use std::path::Path;
+
+use render::{self, snapshot, RenderConfig};
+#[cfg(not(feature = "software"))]
+use render::ImageTarget as Target;
+#[cfg(feature = "software")]
+use render::SoftwareTarget as Target;
+use shrev::EventChannel;
+use specs::prelude::*;
+use ui::draw::DrawSystem;
+use {env_logger, ui, winit};
+
+struct D3;
+
+impl<'a> System<'a> for D3 {
+    type SystemData = ();
+
+    fn run(&mut self, (): Self::SystemData) {}
+}
+
+/// Renders the UI built by `build` in a window of size `dimensions`, and compares it with the
+/// golden image in `tests/data/<name>`.
+pub fn test(name: &str, dimensions: [u32; 2], build: impl FnOnce(&mut World)) {
+    env_logger::try_init().ok();
+
+    let mut world = World::new();
+    let mut dispatcher = DispatcherBuilder::new()
+        .with(D3, "D3", &[])
+        .with(DrawSystem, DrawSystem::NAME, &[]);
+    world.add_resource(EventChannel::<winit::Event>::new());
+    let config = RenderConfig::default();
+    #[cfg(not(feature = "software"))]
+    render::init::<Target>(&mut world, &mut dispatcher, dimensions, config, "D3", DrawSystem::NAME);
+    #[cfg(feature = "software")]
+    render::init_software(&mut world, &mut dispatcher, dimensions, config, "D3", DrawSystem::NAME);
+    ui::init(&mut world, &mut dispatcher);
+    let mut dispatcher = dispatcher.build();
+
+    build(&mut world);
+    world
+        .write_resource::<EventChannel<winit::Event>>()
+        .single_write(winit::Event::WindowEvent {
+            window_id: unsafe { mem::zeroed() },
+            event: winit::WindowEvent::Resized((dimensions[0], dimensions[1]).into()),
+        });
+
+    // The first frame lays out the UI, and the second draws it.
+    for _ in 0..2 {
+        dispatcher.dispatch(&world.res);
+        world.maintain();
+    }
+
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("data")
+        .join(name);
+    world
+        .write_resource::<Target>()
+        .read(|data, dimensions| {
+            snapshot::assert_matches(dir, data, dimensions, snapshot::DEFAULT_TOLERANCE);
+            Ok(())
+        })
+        .unwrap();
+}
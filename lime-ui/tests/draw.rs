@@ -0,0 +1,38 @@
+extern crate cassowary;
+extern crate env_logger;
+extern crate lime_render as render;
+extern crate lime_ui as ui;
+extern crate shrev;
+extern crate specs;
+extern crate winit;
+
+mod common;
+
+use cassowary::strength::*;
+use render::Color;
+use specs::prelude::*;
+use ui::draw::Brush;
+use ui::layout::Position;
+use ui::tree::{Node, Root};
+
+#[test]
+fn rect() {
+    common::test("rect", [600, 400], |world| {
+        let root = world.read_resource::<Root>().entity();
+
+        let pos = Position::new();
+        let cons = {
+            let poss = world.read_storage::<Position>();
+            pos.constraints_builder()
+                .size((400.0, 200.0), STRONG)
+                .center(poss.get(root).unwrap(), STRONG)
+                .build()
+        };
+
+        Node::with_parent(world.create_entity(), root)
+            .with(pos)
+            .with(cons)
+            .with(Brush::Color(Color::RED))
+            .build();
+    });
+}